@@ -0,0 +1,99 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::execute::{create_instance, migrate_instances, set_instance_status, update_config};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReplyMsg};
+use crate::query::{query_config, query_instance, query_instances};
+use crate::reply::{decode_reply_id, instantiate_instance_reply, migrate_instance_reply};
+use crate::state::{Config, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:prudent-pots-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // The instantiator is the owner, deploying and migrating the instances
+    let config = Config {
+        owner: info.sender,
+        pp_code_id: msg.pp_code_id,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "instantiate"),
+        attr("owner", config.owner),
+        attr("pp_code_id", config.pp_code_id.to_string()),
+    ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { owner, pp_code_id } => {
+            update_config(deps, info, owner, pp_code_id)
+        }
+        ExecuteMsg::CreateInstance {
+            label,
+            owner,
+            config,
+            next_game_start,
+        } => create_instance(deps, env, info, label, owner, *config, next_game_start),
+        ExecuteMsg::SetInstanceStatus { addr, status } => {
+            set_instance_status(deps, info, addr, status)
+        }
+        ExecuteMsg::MigrateInstances {
+            code_id,
+            migrate_msg,
+            start_after,
+            limit,
+        } => migrate_instances(deps, info, code_id, migrate_msg, start_after, limit),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let (reply_msg, index) = decode_reply_id(msg.id);
+    match reply_msg {
+        ReplyMsg::InstantiateInstance => instantiate_instance_reply(deps, env, msg),
+        ReplyMsg::MigrateInstance => migrate_instance_reply(deps, index),
+        _ => Err(ContractError::UnknownReply {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Instance { addr } => to_json_binary(&query_instance(deps, addr)?),
+        QueryMsg::Instances {
+            status,
+            start_after,
+            limit,
+        } => to_json_binary(&query_instances(deps, status, start_after, limit)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("migrate", "successful"))
+}
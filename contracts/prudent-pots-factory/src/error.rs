@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use cw_utils::ParseReplyError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Input provided is invalid.")]
+    InvalidInput {},
+
+    #[error("Instance {addr:?} not found.")]
+    InstanceNotFound { addr: String },
+
+    #[error("Unknown Reply ID")]
+    UnknownReply {},
+}
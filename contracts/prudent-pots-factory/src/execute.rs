@@ -0,0 +1,214 @@
+use cosmwasm_std::{
+    attr, to_json_binary, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, SubMsg,
+    WasmMsg,
+};
+use cw_storage_plus::Bound;
+use prudent_pots::state::GameConfig;
+
+use crate::{
+    msg::ReplyMsg,
+    reply::encode_reply_id,
+    state::{
+        InstanceStatus, PendingInstance, PendingMigration, CONFIG, INSTANCES, PENDING_INSTANCE,
+        PENDING_MIGRATIONS,
+    },
+    ContractError,
+};
+
+const DEFAULT_MIGRATE_LIMIT: u32 = 10;
+const MAX_MIGRATE_LIMIT: u32 = 30;
+
+fn validate_is_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if CONFIG.load(storage)?.owner != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    pp_code_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(pp_code_id) = pp_code_id {
+        config.pp_code_id = pp_code_id;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "update_config"),
+        attr("owner", config.owner),
+        attr("pp_code_id", config.pp_code_id.to_string()),
+    ]))
+}
+
+pub fn create_instance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    label: String,
+    owner: Option<String>,
+    config: GameConfig,
+    next_game_start: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    if label.trim().is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender.clone(),
+    };
+
+    // The registry entry is saved on reply, once the instance address is known
+    let code_id = CONFIG.load(deps.storage)?.pp_code_id;
+    PENDING_INSTANCE.save(
+        deps.storage,
+        &PendingInstance {
+            label: label.clone(),
+            owner: owner.clone(),
+            code_id,
+        },
+    )?;
+
+    // The factory is the wasm admin of each instance, so it can migrate them all at once.
+    // The initial funds are forwarded, as the instance needs them to fill the pots of the first round.
+    let msg = SubMsg::reply_on_success(
+        WasmMsg::Instantiate {
+            admin: Some(env.contract.address.to_string()),
+            code_id,
+            msg: to_json_binary(&prudent_pots::msg::InstantiateMsg {
+                config,
+                next_game_start,
+            })?,
+            funds: info.funds,
+            label: label.clone(),
+        },
+        ReplyMsg::InstantiateInstance.into(),
+    );
+
+    Ok(Response::new().add_submessage(msg).add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "create_instance"),
+        attr("label", label),
+        attr("owner", owner),
+        attr("code_id", code_id.to_string()),
+    ]))
+}
+
+pub fn set_instance_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+    status: InstanceStatus,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let mut instance =
+        INSTANCES
+            .may_load(deps.storage, &addr)?
+            .ok_or(ContractError::InstanceNotFound {
+                addr: addr.to_string(),
+            })?;
+    instance.status = status;
+    INSTANCES.save(deps.storage, &addr, &instance)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_instance_status"),
+        attr("addr", addr),
+        attr("status", format!("{:?}", instance.status)),
+    ]))
+}
+
+pub fn migrate_instances(
+    deps: DepsMut,
+    info: MessageInfo,
+    code_id: u64,
+    migrate_msg: prudent_pots::msg::MigrateMsg,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    // Instances are migrated in pages, so the registry can grow past the gas limit of a single transaction
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATE_LIMIT)
+        .min(MAX_MIGRATE_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let instances = INSTANCES
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The registry and the config are only updated on reply, once each migration succeeded
+    let mut submsgs: Vec<SubMsg> = vec![];
+    let mut migrated: Vec<String> = vec![];
+    for (addr, instance) in &instances {
+        if instance.status != InstanceStatus::Active || instance.code_id == code_id {
+            continue;
+        }
+        let index = submsgs.len() as u64;
+        PENDING_MIGRATIONS.save(
+            deps.storage,
+            index,
+            &PendingMigration {
+                addr: addr.clone(),
+                code_id,
+            },
+        )?;
+        submsgs.push(SubMsg::reply_on_success(
+            WasmMsg::Migrate {
+                contract_addr: addr.to_string(),
+                new_code_id: code_id,
+                msg: to_json_binary(&migrate_msg)?,
+            },
+            encode_reply_id(ReplyMsg::MigrateInstance, index),
+        ));
+        migrated.push(addr.to_string());
+    }
+
+    // Without any instance left to migrate, new instances are created from the code id right away
+    if submsgs.is_empty() {
+        let mut config = CONFIG.load(deps.storage)?;
+        config.pp_code_id = code_id;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    // The last address of the page is where the next batch starts from
+    let next_start_after = if instances.len() == limit {
+        instances.last().map(|(addr, _)| addr.to_string())
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_submessages(submsgs)
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "migrate_instances"),
+            attr("code_id", code_id.to_string()),
+            attr("migrated", format!("{:?}", migrated)),
+            attr(
+                "next_start_after",
+                next_start_after.unwrap_or("none".to_string()),
+            ),
+        ]))
+}
@@ -0,0 +1,10 @@
+pub mod contract;
+mod error;
+pub mod execute;
+pub mod msg;
+pub mod query;
+pub mod reply;
+pub mod state;
+pub mod tests;
+
+pub use crate::error::ContractError;
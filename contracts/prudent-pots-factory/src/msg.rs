@@ -0,0 +1,78 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use num_enum::{FromPrimitive, IntoPrimitive};
+use prudent_pots::state::GameConfig;
+
+use crate::state::{Config, Instance, InstanceStatus};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub pp_code_id: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        owner: Option<String>,
+        pp_code_id: Option<u64>,
+    },
+    CreateInstance {
+        label: String,
+        owner: Option<String>, // defaults to the factory owner
+        config: Box<GameConfig>,
+        next_game_start: Option<u64>,
+    },
+    SetInstanceStatus {
+        addr: String,
+        status: InstanceStatus,
+    },
+    MigrateInstances {
+        code_id: u64,
+        migrate_msg: prudent_pots::msg::MigrateMsg,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// Reply
+
+#[derive(FromPrimitive, IntoPrimitive)]
+#[repr(u64)]
+pub enum ReplyMsg {
+    InstantiateInstance = 1,
+    MigrateInstance = 2,
+    #[default]
+    Unknown,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(InstanceResponse)]
+    Instance { addr: String },
+    #[returns(InstancesResponse)]
+    Instances {
+        status: Option<InstanceStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct InstanceResponse {
+    pub instance: Instance,
+}
+
+#[cw_serde]
+pub struct InstancesResponse {
+    pub instances: Vec<Instance>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
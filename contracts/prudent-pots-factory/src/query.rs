@@ -0,0 +1,52 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+    msg::{ConfigResponse, InstanceResponse, InstancesResponse},
+    state::{InstanceStatus, CONFIG, INSTANCES},
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse { config })
+}
+
+pub fn query_instance(deps: Deps, addr: String) -> StdResult<InstanceResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let instance = INSTANCES.load(deps.storage, &addr)?;
+
+    Ok(InstanceResponse { instance })
+}
+
+pub fn query_instances(
+    deps: Deps,
+    status: Option<InstanceStatus>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<InstancesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let instances = INSTANCES
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| item.map(|(_, instance)| instance))
+        .filter(|instance| match (&status, instance) {
+            (Some(status), Ok(instance)) => instance.status == *status,
+            _ => true,
+        })
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(InstancesResponse { instances })
+}
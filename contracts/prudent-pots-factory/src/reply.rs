@@ -0,0 +1,85 @@
+use cosmwasm_std::{attr, to_json_binary, DepsMut, Env, Reply, Response, WasmMsg};
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::{
+    msg::ReplyMsg,
+    state::{Instance, InstanceStatus, CONFIG, INSTANCES, PENDING_INSTANCE, PENDING_MIGRATIONS},
+    ContractError,
+};
+
+/// Builds the id of a submessage, packing the reply kind in the lowest byte and the index of the message above it.
+pub fn encode_reply_id(reply_msg: ReplyMsg, index: u64) -> u64 {
+    (index << 8) | u64::from(reply_msg)
+}
+
+/// Unpacks the reply kind and the index of the message from the id of a submessage.
+pub fn decode_reply_id(id: u64) -> (ReplyMsg, u64) {
+    (ReplyMsg::from(id & 0xff), id >> 8)
+}
+
+// This reply function is called by reply_on_success for each instance created by the factory.
+// The instance ownership is handed over to its owner, who has to accept it on the instance.
+pub fn instantiate_instance_reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let pending_instance = PENDING_INSTANCE.load(deps.storage)?;
+    PENDING_INSTANCE.remove(deps.storage);
+
+    let res = parse_reply_instantiate_data(msg)?;
+    let addr = deps.api.addr_validate(&res.contract_address)?;
+
+    INSTANCES.save(
+        deps.storage,
+        &addr,
+        &Instance {
+            addr: addr.clone(),
+            label: pending_instance.label.clone(),
+            owner: pending_instance.owner.clone(),
+            code_id: pending_instance.code_id,
+            status: InstanceStatus::Active,
+            created_at: env.block.time.seconds(),
+        },
+    )?;
+
+    let transfer_ownership_msg = WasmMsg::Execute {
+        contract_addr: addr.to_string(),
+        msg: to_json_binary(&prudent_pots::msg::ExecuteMsg::TransferOwnership {
+            new_owner: pending_instance.owner.to_string(),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(transfer_ownership_msg)
+        .add_attributes(vec![
+            attr("method", "reply"),
+            attr("action", "instantiate_instance_reply"),
+            attr("instance_addr", addr),
+            attr("label", pending_instance.label),
+            attr("owner", pending_instance.owner),
+        ]))
+}
+
+// This reply function is called by reply_on_success for each instance migrated by the factory.
+// New instances are created from the code id the existing ones have been migrated to.
+pub fn migrate_instance_reply(deps: DepsMut, index: u64) -> Result<Response, ContractError> {
+    let pending_migration = PENDING_MIGRATIONS.load(deps.storage, index)?;
+    PENDING_MIGRATIONS.remove(deps.storage, index);
+
+    let mut instance = INSTANCES.load(deps.storage, &pending_migration.addr)?;
+    instance.code_id = pending_migration.code_id;
+    INSTANCES.save(deps.storage, &pending_migration.addr, &instance)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.pp_code_id = pending_migration.code_id;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "reply"),
+        attr("action", "migrate_instance_reply"),
+        attr("instance_addr", pending_migration.addr),
+        attr("code_id", pending_migration.code_id.to_string()),
+    ]))
+}
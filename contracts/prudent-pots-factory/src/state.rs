@@ -0,0 +1,43 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub pp_code_id: u64, // the prudent-pots code id new instances are created from
+}
+
+#[cw_serde]
+pub enum InstanceStatus {
+    Active,
+    Archived, // archived instances are kept in the registry, but skipped by the batch migrations
+}
+
+#[cw_serde]
+pub struct Instance {
+    pub addr: Addr,
+    pub label: String,
+    pub owner: Addr, // the game owner the instance ownership is transferred to
+    pub code_id: u64,
+    pub status: InstanceStatus,
+    pub created_at: u64,
+}
+
+#[cw_serde]
+pub struct PendingInstance {
+    pub label: String,
+    pub owner: Addr,
+    pub code_id: u64,
+}
+
+#[cw_serde]
+pub struct PendingMigration {
+    pub addr: Addr,
+    pub code_id: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const INSTANCES: Map<&Addr, Instance> = Map::new("instances");
+pub const PENDING_INSTANCE: Item<PendingInstance> = Item::new("pending_instance");
+pub const PENDING_MIGRATIONS: Map<u64, PendingMigration> = Map::new("pending_migrations"); // keyed by the index in the batch
@@ -0,0 +1,85 @@
+use cosmwasm_std::{coins, testing::mock_info, Addr};
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{InstancesResponse, QueryMsg},
+    state::InstanceStatus,
+    tests::integration::{
+        fixtures::{
+            default_game_config, default_with_balances, ADMIN_ADDRESS, DENOM_GAME, PARTNER_ADDRESS,
+        },
+        helpers::create_instance,
+    },
+    ContractError,
+};
+
+#[test]
+fn test_create_instance_works() {
+    let (mut app, factory_addr, pp_code_id) = default_with_balances();
+    let admin_info = mock_info(ADMIN_ADDRESS, &coins(5_000_000, DENOM_GAME));
+
+    // Only the factory owner can create instances
+    let res = create_instance(
+        &mut app,
+        &factory_addr,
+        &mock_info(PARTNER_ADDRESS, &[]),
+        "Partner pots",
+        None,
+        default_game_config(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    create_instance(
+        &mut app,
+        &factory_addr,
+        &admin_info,
+        "Partner pots",
+        Some(PARTNER_ADDRESS.to_string()),
+        default_game_config(),
+    )
+    .unwrap();
+
+    let instances: InstancesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &factory_addr,
+            &QueryMsg::Instances {
+                status: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(instances.instances.len(), 1);
+    let instance = &instances.instances[0];
+    assert_eq!(instance.label, "Partner pots");
+    assert_eq!(instance.code_id, pp_code_id);
+    assert_eq!(instance.status, InstanceStatus::Active);
+
+    // The factory is the wasm admin, while the partner can take over the game ownership
+    let contract_info = app.wrap().query_wasm_contract_info(&instance.addr).unwrap();
+    assert_eq!(contract_info.admin, Some(factory_addr.to_string()));
+    app.execute_contract(
+        Addr::unchecked(PARTNER_ADDRESS),
+        instance.addr.clone(),
+        &prudent_pots::msg::ExecuteMsg::AcceptOwnership {},
+        &[],
+    )
+    .unwrap();
+    let ownership: prudent_pots::msg::OwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(&instance.addr, &prudent_pots::msg::QueryMsg::Ownership {})
+        .unwrap();
+    assert_eq!(ownership.owner, Some(Addr::unchecked(PARTNER_ADDRESS)));
+
+    // The initial funds are forwarded to the instance pots
+    let balance = app
+        .wrap()
+        .query_balance(&instance.addr, DENOM_GAME)
+        .unwrap();
+    assert_eq!(balance.amount.u128(), 5_000_000);
+}
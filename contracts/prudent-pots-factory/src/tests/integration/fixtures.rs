@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, Addr, Decimal, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor};
+use prudent_pots::state::{BidCurve, GameConfig};
+
+use crate::msg::InstantiateMsg;
+
+pub const DENOM_GAME: &str = "udenom";
+pub const ADMIN_ADDRESS: &str = "admin_address";
+pub const ADMIN_BALANCE: u128 = 1_000_000_000_000u128;
+pub const PARTNER_ADDRESS: &str = "partner_address";
+
+pub fn factory_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_reply(crate::contract::reply)
+    .with_migrate(crate::contract::migrate);
+    Box::new(contract)
+}
+
+pub fn pp_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        prudent_pots::contract::execute,
+        prudent_pots::contract::instantiate,
+        prudent_pots::contract::query,
+    )
+    .with_reply(prudent_pots::contract::reply)
+    .with_migrate(prudent_pots::contract::migrate);
+    Box::new(contract)
+}
+
+pub fn default_game_config() -> GameConfig {
+    GameConfig {
+        fee: 5,
+        fee_reallocation: 5,
+        fee_address: Addr::unchecked("treasury_addr"),
+        game_denom: DENOM_GAME.to_string(),
+        game_cw721_collections: vec![],
+        game_duration: 3600,
+        game_duration_epoch: 600,
+        game_extend: 600,
+        game_end_threshold: 600,
+        min_pot_initial_allocation: Uint128::new(1_000_000u128),
+        decay_factor: Decimal::from_str("0.05").unwrap(),
+        reallocations_limit: 10,
+        nft_claim_timeout: 86400,
+        raffle_expiry_rounds: 1,
+        min_bid_floor: Uint128::zero(),
+        voting_power_sources: vec![],
+        timelock_delay: 86400,
+        bid_curve: BidCurve::Linear {},
+        max_bid_multiplier: Decimal::from_ratio(2u128, 1u128),
+        max_player_exposure: Uint128::zero(),
+    }
+}
+
+pub fn default_with_balances() -> (App, Addr, u64) {
+    let bank = BankKeeper::new();
+
+    let mut app = AppBuilder::new()
+        .with_bank(bank)
+        .build(|router, _api, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(ADMIN_ADDRESS),
+                    vec![coin(ADMIN_BALANCE, DENOM_GAME)],
+                )
+                .unwrap();
+        });
+
+    let pp_code_id = app.store_code(pp_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let factory_addr = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(ADMIN_ADDRESS),
+            &InstantiateMsg { pp_code_id },
+            &[],
+            "prudent-pots-factory",
+            Some(ADMIN_ADDRESS.to_string()),
+        )
+        .unwrap();
+
+    (app, factory_addr, pp_code_id)
+}
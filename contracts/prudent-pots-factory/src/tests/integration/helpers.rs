@@ -0,0 +1,65 @@
+use cosmwasm_std::{Addr, MessageInfo};
+use cw_multi_test::{error::AnyError, App, AppResponse, Executor};
+use prudent_pots::state::GameConfig;
+
+use crate::{msg::ExecuteMsg, state::InstanceStatus};
+
+pub fn create_instance(
+    app: &mut App,
+    factory_addr: &Addr,
+    info: &MessageInfo,
+    label: &str,
+    owner: Option<String>,
+    config: GameConfig,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        factory_addr.clone(),
+        &ExecuteMsg::CreateInstance {
+            label: label.to_string(),
+            owner,
+            config: Box::new(config),
+            next_game_start: None,
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_instance_status(
+    app: &mut App,
+    factory_addr: &Addr,
+    info: &MessageInfo,
+    addr: &Addr,
+    status: InstanceStatus,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        factory_addr.clone(),
+        &ExecuteMsg::SetInstanceStatus {
+            addr: addr.to_string(),
+            status,
+        },
+        &info.funds,
+    )
+}
+
+pub fn migrate_instances(
+    app: &mut App,
+    factory_addr: &Addr,
+    info: &MessageInfo,
+    code_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        factory_addr.clone(),
+        &ExecuteMsg::MigrateInstances {
+            code_id,
+            migrate_msg: prudent_pots::msg::MigrateMsg::default(),
+            start_after,
+            limit,
+        },
+        &info.funds,
+    )
+}
@@ -0,0 +1,110 @@
+use cosmwasm_std::{coins, testing::mock_info};
+
+use crate::{
+    msg::{ConfigResponse, InstancesResponse, QueryMsg},
+    state::InstanceStatus,
+    tests::integration::{
+        fixtures::{
+            default_game_config, default_with_balances, pp_contract, ADMIN_ADDRESS, DENOM_GAME,
+        },
+        helpers::{create_instance, migrate_instances, set_instance_status},
+    },
+    ContractError,
+};
+
+fn query_instances(
+    app: &cw_multi_test::App,
+    factory_addr: &cosmwasm_std::Addr,
+) -> InstancesResponse {
+    app.wrap()
+        .query_wasm_smart(
+            factory_addr,
+            &QueryMsg::Instances {
+                status: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_migrate_instances_works() {
+    let (mut app, factory_addr, _pp_code_id) = default_with_balances();
+    let admin_info = mock_info(ADMIN_ADDRESS, &coins(5_000_000, DENOM_GAME));
+
+    for label in ["Board 1", "Board 2", "Board 3"] {
+        create_instance(
+            &mut app,
+            &factory_addr,
+            &admin_info,
+            label,
+            None,
+            default_game_config(),
+        )
+        .unwrap();
+    }
+    let instances = query_instances(&app, &factory_addr).instances;
+
+    // Archived instances are left on their code id
+    set_instance_status(
+        &mut app,
+        &factory_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        &instances[2].addr,
+        InstanceStatus::Archived,
+    )
+    .unwrap();
+
+    let new_code_id = app.store_code(pp_contract());
+    let res = migrate_instances(
+        &mut app,
+        &factory_addr,
+        &mock_info("user1", &[]),
+        new_code_id,
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Instances are migrated in batches, the first page only holds the first instance
+    migrate_instances(
+        &mut app,
+        &factory_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        new_code_id,
+        None,
+        Some(1),
+    )
+    .unwrap();
+    migrate_instances(
+        &mut app,
+        &factory_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        new_code_id,
+        Some(instances[0].addr.to_string()),
+        None,
+    )
+    .unwrap();
+
+    for (instance, expected_code_id) in query_instances(&app, &factory_addr).instances.iter().zip([
+        new_code_id,
+        new_code_id,
+        instances[2].code_id,
+    ]) {
+        assert_eq!(instance.code_id, expected_code_id);
+        let contract_info = app.wrap().query_wasm_contract_info(&instance.addr).unwrap();
+        assert_eq!(contract_info.code_id, expected_code_id);
+    }
+
+    // New instances are created from the code id the existing ones have been migrated to
+    let config: ConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&factory_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.config.pp_code_id, new_code_id);
+}
@@ -0,0 +1,4 @@
+mod create_instance;
+mod fixtures;
+mod helpers;
+mod migrate_instances;
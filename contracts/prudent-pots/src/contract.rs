@@ -1,25 +1,47 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, Uint128,
+    attr, from_json, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    StdResult, Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 use crate::error::ContractError;
 use crate::execute::{
-    allocate_tokens, game_end, reallocate_tokens, update_config, update_next_game,
+    accept_ownership, allocate_from_balance, allocate_tokens, cancel_proposal, cancel_round,
+    claim_balance, claim_nft, create_table, deposit, execute_proposal, game_end, pause,
+    propose_config, reallocate_tokens, receive_nft, recover_assets, refund, register_nfts,
+    renounce_ownership, set_auto_compound, set_balance_payouts, set_jackpot,
+    set_participation_gate, set_vote_options, sponsor_raffle, start_season, transfer_ownership,
+    unpause, update_allowlist, update_blocklist, update_config, update_next_game, update_operators,
+    vote, withdraw,
 };
+use crate::helpers::config::validate_game_config;
 use crate::helpers::game_end::prepare_next_game;
+use crate::helpers::migrate::{
+    migrate_v1_config, migrate_v2_0_config, migrate_v2_0_ownership, migrate_v2_1_config,
+};
+use crate::helpers::raffle::validate_cw721_token_recoverable;
+use crate::helpers::reply::decode_reply_id;
+use crate::helpers::table::{execute_on_table, query_on_table, reserve_table_denoms};
 use crate::helpers::validate::{validate_funds, validate_pot_initial_amount};
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReplyMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveNftMsg, ReplyMsg};
 use crate::query::{
-    query_all_players_allocations, query_bid_range, query_game_config, query_game_state,
-    query_player_allocations, query_player_reallocations, query_pot_state, query_pots_state,
-    query_raffle, query_raffle_denom_split, query_raffle_winner, query_reallocation_fee_pool,
+    query_address_list, query_all_players_allocations, query_auto_compound, query_balance,
+    query_bid_curve, query_bid_range, query_emergency_state, query_game_config, query_game_state,
+    query_jackpot, query_nft_registrations, query_ownership, query_participation,
+    query_pending_config, query_pending_proposals, query_player_allocations, query_player_limits,
+    query_player_reallocations, query_player_vote, query_pot_state, query_pots_state, query_raffle,
+    query_raffle_denom_split, query_raffle_sponsors, query_raffle_winner,
+    query_reallocation_fee_pool, query_recovery_log, query_refund_claim, query_season,
+    query_season_standings, query_tables, query_unclaimed_nfts, query_vote_tally, query_votes,
     query_winning_pots,
 };
-use crate::reply::transfer_nft_reply;
-use crate::state::{GAME_CONFIG, REALLOCATION_FEE_POOL};
+use crate::reply::transfer_nft_to_winner_reply;
+use crate::state::{
+    Ownership, ALLOWLIST, BLOCKLIST, GAME_CONFIG, OPERATORS, OWNERSHIP, REALLOCATION_FEE_POOL,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:prudent-pots";
@@ -35,15 +57,7 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     // Validate game_config fields and initial funds
-    if msg.config.fee > 100 || msg.config.fee_reallocation > 100 {
-        return Err(ContractError::InvalidInput {});
-    }
-    if msg.config.game_duration == 0 {
-        return Err(ContractError::InvalidInput {});
-    }
-    if msg.config.min_pot_initial_allocation.is_zero() {
-        return Err(ContractError::InvalidInput {});
-    }
+    validate_game_config(deps.api, &msg.config)?;
 
     // On instantiation there is no raffle. All funds are always for the first no raffled round.
     let total_amount = validate_funds(&info.funds, &msg.config.game_denom)?;
@@ -52,6 +66,16 @@ pub fn instantiate(
     GAME_CONFIG.save(deps.storage, &msg.config)?;
     REALLOCATION_FEE_POOL.save(deps.storage, &Uint128::zero())?;
 
+    // The instantiator is the owner, and can add operators later on
+    OWNERSHIP.save(
+        deps.storage,
+        &Ownership {
+            owner: Some(info.sender.clone()),
+            pending_owner: None,
+        },
+    )?;
+    OPERATORS.save(deps.storage, &vec![])?;
+
     // Initialize game state and pots for the next game
     prepare_next_game(
         deps,
@@ -77,43 +101,178 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { config } => update_config(deps, env, info, *config),
-        ExecuteMsg::AllocateTokens { pot_id } => allocate_tokens(deps, env, info, pot_id),
+        ExecuteMsg::UpdateConfig { table_id, config } => {
+            execute_on_table(deps, table_id, |deps| update_config(deps, info, *config))
+        }
+        ExecuteMsg::AllocateTokens { table_id, pot_id } => {
+            execute_on_table(deps, table_id, |deps| {
+                allocate_tokens(deps, env, info, pot_id)
+            })
+        }
+        ExecuteMsg::AllocateFromBalance {
+            table_id,
+            pot_id,
+            amount,
+        } => execute_on_table(deps, table_id, |deps| {
+            allocate_from_balance(deps, env, info, pot_id, amount)
+        }),
         ExecuteMsg::ReallocateTokens {
+            table_id,
             from_pot_id,
             to_pot_id,
-        } => reallocate_tokens(deps, env, info, from_pot_id, to_pot_id),
+        } => execute_on_table(deps, table_id, |deps| {
+            reallocate_tokens(deps, env, info, from_pot_id, to_pot_id)
+        }),
         ExecuteMsg::GameEnd {
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
+            table_id,
             next_game_start,
-        } => game_end(
-            deps,
-            env,
-            info,
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
-            next_game_start,
-        ),
+        } => execute_on_table(deps, table_id, |deps| {
+            game_end(deps, env, info, next_game_start)
+        }),
         ExecuteMsg::UpdateNextGame {
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
+            table_id,
             next_game_start,
-        } => update_next_game(
-            deps,
-            env,
-            info,
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
+        } => execute_on_table(deps, table_id, |deps| {
+            update_next_game(deps, env, info, next_game_start)
+        }),
+        ExecuteMsg::SponsorRaffle { table_id, message } => {
+            execute_on_table(deps, table_id, |deps| sponsor_raffle(deps, info, message))
+        }
+        ExecuteMsg::ReceiveNft(receive_msg) => {
+            let table_id = match from_json(&receive_msg.msg)? {
+                ReceiveNftMsg::SetRaffleNft { table_id } => table_id,
+                ReceiveNftMsg::SponsorRaffle { table_id, .. } => table_id,
+            };
+            execute_on_table(deps, table_id, |deps| {
+                receive_nft(deps, env, info, receive_msg)
+            })
+        }
+        ExecuteMsg::ClaimNft {
+            table_id,
+            cw721_addr,
+            token_id,
+        } => execute_on_table(deps, table_id, |deps| {
+            claim_nft(deps, env, info, cw721_addr, token_id)
+        }),
+        ExecuteMsg::RecoverAssets {
+            table_id,
+            recover_raffle,
+            cw721_tokens,
+        } => {
+            // The raffle prizes and escrowed NFTs of every table are only reachable from the storage root
+            for cw721_token in &cw721_tokens {
+                validate_cw721_token_recoverable(deps.storage, cw721_token)?;
+            }
+            execute_on_table(deps, table_id, |deps| {
+                recover_assets(deps, env, info, recover_raffle, cw721_tokens)
+            })
+        }
+        ExecuteMsg::RegisterNfts {
+            table_id,
+            collection,
+            token_ids,
+        } => execute_on_table(deps, table_id, |deps| {
+            register_nfts(deps, env, info, collection, token_ids)
+        }),
+        ExecuteMsg::TransferOwnership { new_owner } => transfer_ownership(deps, info, new_owner),
+        ExecuteMsg::AcceptOwnership {} => accept_ownership(deps, info),
+        ExecuteMsg::RenounceOwnership {} => renounce_ownership(deps, info),
+        ExecuteMsg::UpdateOperators { add, remove } => update_operators(deps, info, add, remove),
+        ExecuteMsg::Pause { table_id } => {
+            execute_on_table(deps, table_id, |deps| pause(deps, info))
+        }
+        ExecuteMsg::Unpause { table_id } => {
+            execute_on_table(deps, table_id, |deps| unpause(deps, env, info))
+        }
+        ExecuteMsg::CancelRound { table_id } => {
+            execute_on_table(deps, table_id, |deps| cancel_round(deps, info))
+        }
+        ExecuteMsg::Refund { table_id } => {
+            execute_on_table(deps, table_id, |deps| refund(deps, info))
+        }
+        ExecuteMsg::ProposeConfig { table_id, changes } => {
+            execute_on_table(deps, table_id, |deps| {
+                propose_config(deps, env, info, changes)
+            })
+        }
+        ExecuteMsg::ExecuteProposal {
+            table_id,
+            proposal_id,
+        } => execute_on_table(deps, table_id, |deps| {
+            execute_proposal(deps, env, info, proposal_id)
+        }),
+        ExecuteMsg::CancelProposal {
+            table_id,
+            proposal_id,
+        } => execute_on_table(deps, table_id, |deps| {
+            cancel_proposal(deps, info, proposal_id)
+        }),
+        ExecuteMsg::SetVoteOptions { table_id, options } => {
+            execute_on_table(deps, table_id, |deps| set_vote_options(deps, info, options))
+        }
+        ExecuteMsg::Vote {
+            table_id,
+            option_id,
+        } => execute_on_table(deps, table_id, |deps| vote(deps, env, info, option_id)),
+        ExecuteMsg::CreateTable {
+            config,
             next_game_start,
-        ),
+        } => create_table(deps, env, info, *config, next_game_start),
+        ExecuteMsg::StartSeason { table_id, config } => {
+            execute_on_table(deps, table_id, |deps| start_season(deps, info, config))
+        }
+        ExecuteMsg::SetJackpot { table_id, config } => {
+            execute_on_table(deps, table_id, |deps| set_jackpot(deps, info, config))
+        }
+        ExecuteMsg::SetParticipationGate { table_id, gate } => {
+            execute_on_table(deps, table_id, |deps| {
+                set_participation_gate(deps, info, gate)
+            })
+        }
+        ExecuteMsg::UpdateAllowlist {
+            table_id,
+            add,
+            remove,
+        } => execute_on_table(deps, table_id, |deps| {
+            update_allowlist(deps, info, add, remove)
+        }),
+        ExecuteMsg::UpdateBlocklist {
+            table_id,
+            add,
+            remove,
+        } => execute_on_table(deps, table_id, |deps| {
+            update_blocklist(deps, info, add, remove)
+        }),
+        ExecuteMsg::SetAutoCompound {
+            table_id,
+            pot_strategy,
+        } => execute_on_table(deps, table_id, |deps| {
+            set_auto_compound(deps, info, pot_strategy)
+        }),
+        ExecuteMsg::ClaimBalance { table_id } => {
+            execute_on_table(deps, table_id, |deps| claim_balance(deps, info))
+        }
+        ExecuteMsg::Deposit { table_id } => {
+            execute_on_table(deps, table_id, |deps| deposit(deps, info))
+        }
+        ExecuteMsg::Withdraw { table_id, amount } => {
+            execute_on_table(deps, table_id, |deps| withdraw(deps, info, amount))
+        }
+        ExecuteMsg::SetBalancePayouts { table_id, enabled } => {
+            execute_on_table(deps, table_id, |deps| {
+                set_balance_payouts(deps, info, enabled)
+            })
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
-    match msg.id.into() {
-        ReplyMsg::TransferNft {} => transfer_nft_reply(msg.result),
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let (reply_msg, table_id, round_count) = decode_reply_id(msg.id);
+    match reply_msg {
+        ReplyMsg::TransferNftToWinner => execute_on_table(deps, Some(table_id), |deps| {
+            transfer_nft_to_winner_reply(deps, env, round_count, msg.result)
+        }),
         _ => Err(ContractError::UnknownReply {}),
     }
 }
@@ -121,27 +280,183 @@ pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, Contract
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GameConfig {} => to_json_binary(&query_game_config(deps)?),
-        QueryMsg::GameState {} => to_json_binary(&query_game_state(deps)?),
-        QueryMsg::BidRange { address } => to_json_binary(&query_bid_range(deps, env, address)?),
-        QueryMsg::PotState { pot_id } => to_json_binary(&query_pot_state(deps, pot_id)?),
-        QueryMsg::PotsState {} => to_json_binary(&query_pots_state(deps)?),
-        QueryMsg::WinningPots {} => to_json_binary(&query_winning_pots(deps)?),
-        QueryMsg::PlayerAllocations { address } => {
-            to_json_binary(&query_player_allocations(deps, address)?)
-        }
-        QueryMsg::PlayerReallocations { address } => {
-            to_json_binary(&query_player_reallocations(deps, address)?)
-        }
-        QueryMsg::AllPlayersAllocations {} => to_json_binary(&query_all_players_allocations(deps)?),
-        QueryMsg::ReallocationFeePool {} => to_json_binary(&query_reallocation_fee_pool(deps)?),
-        QueryMsg::Raffle {} => to_json_binary(&query_raffle(deps)?),
-        QueryMsg::RaffleWinner {} => to_json_binary(&query_raffle_winner(deps)?),
-        QueryMsg::RaffleDenomSplit {} => to_json_binary(&query_raffle_denom_split(deps)?),
+        QueryMsg::GameConfig { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_game_config(deps)?)
+        }),
+        QueryMsg::GameState { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_game_state(deps)?)
+        }),
+        QueryMsg::BidRange { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_bid_range(deps, env, address)?)
+        }),
+        QueryMsg::BidCurve { table_id, epochs } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_bid_curve(deps, env, epochs)?)
+        }),
+        QueryMsg::PotState { table_id, pot_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_pot_state(deps, pot_id)?)
+        }),
+        QueryMsg::PotsState { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_pots_state(deps)?)
+        }),
+        QueryMsg::WinningPots { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_winning_pots(deps)?)
+        }),
+        QueryMsg::PlayerAllocations { table_id, address } => {
+            query_on_table(deps, table_id, |deps| {
+                to_json_binary(&query_player_allocations(deps, address)?)
+            })
+        }
+        QueryMsg::PlayerReallocations { table_id, address } => {
+            query_on_table(deps, table_id, |deps| {
+                to_json_binary(&query_player_reallocations(deps, address)?)
+            })
+        }
+        QueryMsg::AllPlayersAllocations { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_all_players_allocations(deps)?)
+        }),
+        QueryMsg::ReallocationFeePool { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_reallocation_fee_pool(deps)?)
+        }),
+        QueryMsg::Raffle { table_id } => {
+            query_on_table(deps, table_id, |deps| to_json_binary(&query_raffle(deps)?))
+        }
+        QueryMsg::RaffleWinner { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_raffle_winner(deps)?)
+        }),
+        QueryMsg::RaffleDenomSplit { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_raffle_denom_split(deps)?)
+        }),
+        QueryMsg::RaffleSponsors {
+            table_id,
+            round,
+            start_after,
+            limit,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_raffle_sponsors(deps, round, start_after, limit)?)
+        }),
+        QueryMsg::UnclaimedNfts {
+            table_id,
+            recipient,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_unclaimed_nfts(deps, recipient)?)
+        }),
+        QueryMsg::RecoveryLog {
+            table_id,
+            start_after,
+            limit,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_recovery_log(deps, start_after, limit)?)
+        }),
+        QueryMsg::NftRegistrations { table_id, address } => {
+            query_on_table(deps, table_id, |deps| {
+                to_json_binary(&query_nft_registrations(deps, address)?)
+            })
+        }
+        QueryMsg::PlayerLimits { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_player_limits(deps, env, address)?)
+        }),
+        QueryMsg::Ownership {} => to_json_binary(&query_ownership(deps)?),
+        QueryMsg::EmergencyState { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_emergency_state(deps)?)
+        }),
+        QueryMsg::RefundClaim { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_refund_claim(deps, address)?)
+        }),
+        QueryMsg::PendingConfig { table_id } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_pending_config(deps)?)
+        }),
+        QueryMsg::PendingProposals {
+            table_id,
+            start_after,
+            limit,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_pending_proposals(deps, start_after, limit)?)
+        }),
+        QueryMsg::Votes { table_id } => {
+            query_on_table(deps, table_id, |deps| to_json_binary(&query_votes(deps)?))
+        }
+        QueryMsg::PlayerVote { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_player_vote(deps, address)?)
+        }),
+        QueryMsg::VoteTally { table_id, round } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_vote_tally(deps, round)?)
+        }),
+        QueryMsg::Tables { start_after, limit } => {
+            to_json_binary(&query_tables(deps, start_after, limit)?)
+        }
+        QueryMsg::Season { table_id } => {
+            query_on_table(deps, table_id, |deps| to_json_binary(&query_season(deps)?))
+        }
+        QueryMsg::SeasonStandings { table_id, limit } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_season_standings(deps, limit)?)
+        }),
+        QueryMsg::Jackpot { table_id } => {
+            query_on_table(deps, table_id, |deps| to_json_binary(&query_jackpot(deps)?))
+        }
+        QueryMsg::Participation { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_participation(deps, address)?)
+        }),
+        QueryMsg::Allowlist {
+            table_id,
+            start_after,
+            limit,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_address_list(deps, &ALLOWLIST, start_after, limit)?)
+        }),
+        QueryMsg::Blocklist {
+            table_id,
+            start_after,
+            limit,
+        } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_address_list(deps, &BLOCKLIST, start_after, limit)?)
+        }),
+        QueryMsg::AutoCompound { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_auto_compound(deps, address)?)
+        }),
+        QueryMsg::Balance { table_id, address } => query_on_table(deps, table_id, |deps| {
+            to_json_binary(&query_balance(deps, address)?)
+        }),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::new().add_attribute("migrate", "successful"))
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName {
+            contract: stored.contract,
+        });
+    }
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = CONTRACT_VERSION.parse()?;
+    if stored_version > version {
+        return Err(ContractError::CannotMigrateToOlderVersion {
+            stored_version: stored.version,
+            version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // Migration steps run in order, so older contracts go through each state upgrade
+    if stored_version < Version::new(2, 0, 0) {
+        migrate_v1_config(deps.storage, &msg)?;
+    }
+    if stored_version < Version::new(2, 1, 0) {
+        migrate_v2_0_config(deps.storage, &msg)?;
+        migrate_v2_0_ownership(deps.storage, &deps.querier, &env)?;
+    }
+    if stored_version < Version::new(2, 2, 0) {
+        migrate_v2_1_config(deps.storage, &msg)?;
+        validate_game_config(deps.api, &GAME_CONFIG.load(deps.storage)?)?;
+        // The existing game becomes the default table, reserving its denoms
+        reserve_table_denoms(deps.storage)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("migrate", "successful"),
+        attr("from_version", stored.version),
+        attr("to_version", CONTRACT_VERSION),
+    ]))
 }
@@ -1,4 +1,6 @@
-use cosmwasm_std::{DivideByZeroError, OverflowError, StdError, Uint128};
+use cosmwasm_std::{
+    CheckedMultiplyFractionError, DivideByZeroError, OverflowError, StdError, Uint128,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -45,21 +47,120 @@ pub enum ContractError {
     #[error("Raffle NFT specified is invalid.")]
     InvalidRaffleNft {},
 
+    #[error("Sponsor message exceeds the maximum length of {max} characters.")]
+    SponsorMessageTooLong { max: usize },
+
+    #[error("The game is paused.")]
+    GamePaused {},
+
+    #[error("The current round has been cancelled, players can only be refunded.")]
+    RoundCancelled {},
+
+    #[error("The current round has not been cancelled.")]
+    RoundNotCancelled {},
+
+    #[error("No refund available for your address.")]
+    NoRefundAvailable {},
+
+    #[error("No claimable balance available for your address.")]
+    NoClaimableBalance {},
+
+    #[error("This address is blocked from playing.")]
+    Blocked {},
+
+    #[error("This address is not allowed to play on this table.")]
+    NotAllowed {},
+
     #[error("The next game start time is invalid.")]
     InvalidNextGameStart {},
 
+    #[error("The raffle prize has been unwon for {rounds_unwon:?} rounds, it cannot be recovered before {raffle_expiry_rounds:?}.")]
+    RaffleNotExpired {
+        rounds_unwon: u64,
+        raffle_expiry_rounds: u64,
+    },
+
+    #[error("The CW721 token is a raffle prize or is escrowed, it cannot be recovered.")]
+    Cw721TokenNotRecoverable {},
+
     #[error("Expected a CW721 token transfer but none was received.")]
     Cw721TokenNotReceived {},
 
+    #[error("The CW721 collection is not whitelisted.")]
+    InvalidCw721Collection {},
+
+    #[error("The CW721 token {token_id:?} is not owned by the sender.")]
+    Cw721TokenNotOwned { token_id: String },
+
+    #[error("No unclaimed NFT found for the given collection and token.")]
+    UnclaimedNftNotFound {},
+
+    #[error("The unclaimed NFT cannot be reclaimed before {reclaimable_at:?}.")]
+    NftClaimTimeoutNotReached { reclaimable_at: u64 },
+
+    #[error("Invalid config field {field:?}: {reason}.")]
+    InvalidConfigField { field: String, reason: String },
+
+    #[error("The vote option does not exist.")]
+    InvalidVoteOption {},
+
+    #[error("Only players with allocations in the current round can vote.")]
+    NoAllocationsToVote {},
+
+    #[error("The vote options cannot be changed once votes have been cast in the current round.")]
+    VotingInProgress {},
+
+    #[error("Config proposal not found.")]
+    ProposalNotFound {},
+
+    #[error("The config proposal cannot be executed before {eta:?}.")]
+    ProposalNotReady { eta: u64 },
+
+    #[error("The contract holds no {denom:?} funds to use as game denom.")]
+    GameDenomNotFunded { denom: String },
+
+    #[error("The game denom cannot change while funds are reserved in {denom:?}.")]
+    GameDenomInUse { denom: String },
+
+    #[error("Cannot migrate from contract {contract:?}.")]
+    InvalidContractName { contract: String },
+
+    #[error("Cannot migrate from version {stored_version:?} to the older version {version:?}.")]
+    CannotMigrateToOlderVersion {
+        stored_version: String,
+        version: String,
+    },
+
+    #[error("The migrate message is missing the {field:?} field required by this migration.")]
+    MissingMigrateField { field: String },
+
+    #[error("A season is already in progress.")]
+    SeasonInProgress {},
+
+    #[error("Table {table_id:?} not found.")]
+    TableNotFound { table_id: u64 },
+
+    #[error("The denom {denom:?} is already used by table {table_id:?}.")]
+    DenomInUse { denom: String, table_id: u64 },
+
+    #[error("{0}")]
+    SemVer(#[from] semver::Error),
+
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
     #[error("{0}")]
     DivideByZeroError(#[from] DivideByZeroError),
 
+    #[error("{0}")]
+    CheckedMultiplyFractionError(#[from] CheckedMultiplyFractionError),
+
     #[error("Bid amount out of range. Min: {min:?}, Max: {max:?}")]
     BidOutOfRange { min: Uint128, max: Uint128 },
 
+    #[error("Player exposure cap exceeded. Max: {max:?}")]
+    ExposureCapExceeded { max: Uint128 },
+
     #[error("Insufficient funds available for initiating the next round.")]
     NotEnoughFundsForNextRound {},
 
@@ -70,3 +171,10 @@ pub enum ContractError {
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+pub fn invalid_config_field(field: &str, reason: &str) -> ContractError {
+    ContractError::InvalidConfigField {
+        field: field.to_string(),
+        reason: reason.to_string(),
+    }
+}
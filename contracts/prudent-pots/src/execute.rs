@@ -1,126 +1,203 @@
-use std::str::FromStr;
-
 use cosmwasm_std::{
-    attr, to_json_binary, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, SubMsg,
-    Uint128, WasmMsg,
+    attr, coin, from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, Uint128, WasmMsg,
 };
+use cw721::Cw721ReceiveMsg;
 
 use crate::{
     helpers::{
+        balance::{add_to_balance, deduct_from_balance},
+        compound::{process_auto_compound, take_claimable},
+        config::{
+            apply_timelocked_config, load_game_configs, save_game_configs, validate_game_config,
+            validate_game_denom_change,
+        },
         game_end::{
             calculate_total_losing_tokens, get_distribution_send_msgs, prepare_next_game,
             process_raffle_winner,
         },
+        gate::{update_address_list, verify_participation},
+        jackpot::{
+            add_to_jackpot_pool, get_jackpot_trigger, process_jackpot_payout,
+            validate_jackpot_config,
+        },
+        nft::{
+            calculate_player_limits, get_nft_weight, is_nft_owner, reverify_nft_registrations,
+            validate_register_nfts,
+        },
         pot::{
-            calculate_max_bid, calculate_min_bid, get_winning_pots, set_first_bidder_if_not_set,
-            update_player_allocation, update_pot_state,
+            calculate_max_bid, calculate_min_bid, get_player_exposure, get_winning_pots,
+            set_first_bidder_if_not_set, update_player_allocation, update_pot_state,
         },
+        raffle::{
+            add_raffle_sponsor, get_unexpired_sponsorships, set_raffle_nft,
+            update_raffle_unwon_rounds, validate_sponsor_message,
+        },
+        refund::move_pending_refunds_to_claims,
+        season::{
+            add_to_season_pool, award_season_points, get_active_season, process_season_end,
+            validate_season_config,
+        },
+        table::{get_reserved_denoms, validate_table_denoms, TableStorage},
         validate::{
             extend_game_time, validate_existing_allocation, validate_funds, validate_game_end_time,
-            validate_game_time, validate_increase_player_reallocations, validate_is_contract_admin,
-            validate_is_contract_admin_game_end, validate_pot_limit_not_exceeded,
+            validate_game_time, validate_increase_player_reallocations, validate_is_operator,
+            validate_is_operator_game_end, validate_is_owner, validate_nft_received,
+            validate_not_paused, validate_player_exposure, validate_pot_initial_amount,
+            validate_pot_limit_not_exceeded,
         },
+        vote::validate_vote_options,
+    },
+    msg::{ReceiveNftMsg, UpdateGameConfig},
+    state::{
+        AssetRecovery, AutoCompoundStrategy, ConfigProposal, Cw721Token, GameConfig, Jackpot,
+        JackpotConfig, NftRegistration, Ownership, ParticipationGate, Raffle, RaffleSponsor,
+        RegisteredNft, Season, SeasonConfig, TimelockedConfig, VoteOption, ALLOWLIST,
+        AUTO_COMPOUND, BALANCE_PAYOUTS, BLOCKLIST, GAME_CONFIG, GAME_STATE, JACKPOT,
+        NFT_REGISTRATIONS, OPERATORS, OWNERSHIP, PARTICIPATION_GATE, PARTICIPATION_VERIFIED,
+        PAUSED, PENDING_NFT_TRANSFERS, PLAYER_ALLOCATIONS, PLAYER_REALLOCATION_FEES, PLAYER_VOTES,
+        POT_STATES, PROPOSALS, PROPOSAL_COUNT, RAFFLE, RAFFLE_UNWON_ROUNDS, REALLOCATION_FEE_POOL,
+        RECOVERY_LOG, REFUND_CLAIMS, REFUND_CLAIMS_TOTAL, ROUND_CANCELLED, SEASON, TABLE_COUNT,
+        TABLE_ID, UNCLAIMED_NFTS, VOTE_OPTIONS,
     },
-    msg::{ReplyMsg, UpdateGameConfig},
-    state::{GAME_CONFIG, GAME_STATE, PLAYER_ALLOCATIONS, RAFFLE, REALLOCATION_FEE_POOL},
     ContractError,
 };
 
 pub fn update_config(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
     update_config: UpdateGameConfig,
 ) -> Result<Response, ContractError> {
-    validate_is_contract_admin(&deps.querier, &env, &info.sender)?;
+    validate_is_owner(deps.storage, &info.sender)?;
 
-    let mut game_config = GAME_CONFIG.load(deps.storage)?;
+    let (mut game_config, mut pending_config) = load_game_configs(deps.storage)?;
 
-    if let Some(fee) = update_config.fee {
-        if fee > 10 {
-            return Err(ContractError::InvalidInput {});
-        }
-        game_config.fee = fee;
+    // Emergency fields do not affect the bid ranges nor the payouts, so they take effect immediately
+    if let Some(game_end_threshold) = update_config.game_end_threshold {
+        game_config.game_end_threshold = game_end_threshold;
+        pending_config.game_end_threshold = game_end_threshold;
     }
-    if let Some(fee_reallocation) = update_config.fee_reallocation {
-        if fee_reallocation > 50 {
-            return Err(ContractError::InvalidInput {});
-        }
-        game_config.fee_reallocation = fee_reallocation;
+    if let Some(nft_claim_timeout) = update_config.nft_claim_timeout {
+        game_config.nft_claim_timeout = nft_claim_timeout;
+        pending_config.nft_claim_timeout = nft_claim_timeout;
     }
-    if let Some(fee_address) = update_config.fee_address {
-        game_config.fee_address = deps.api.addr_validate(fee_address.as_str())?;
+    if let Some(raffle_expiry_rounds) = update_config.raffle_expiry_rounds {
+        game_config.raffle_expiry_rounds = raffle_expiry_rounds;
+        pending_config.raffle_expiry_rounds = raffle_expiry_rounds;
     }
-    if let Some(game_denom) = update_config.game_denom {
-        game_config.game_denom = game_denom;
+
+    // Any other field is staged, and applied by prepare_next_game at the start of the next round
+    if let Some(fee_reallocation) = update_config.fee_reallocation {
+        pending_config.fee_reallocation = fee_reallocation;
     }
-    if !game_config
-        .game_cw721_addrs
+    if !pending_config
+        .game_cw721_collections
         .iter()
-        .eq(update_config.game_cw721_addrs.iter())
+        .eq(update_config.game_cw721_collections.iter())
     {
-        for address in &update_config.game_cw721_addrs {
-            deps.api.addr_validate(address.as_str())?;
-        }
-        game_config.game_cw721_addrs = update_config.game_cw721_addrs;
+        pending_config.game_cw721_collections = update_config.game_cw721_collections;
     }
     if let Some(game_duration) = update_config.game_duration {
-        game_config.game_duration = game_duration;
+        pending_config.game_duration = game_duration;
     }
     if let Some(game_duration_epoch) = update_config.game_duration_epoch {
-        game_config.game_duration_epoch = game_duration_epoch;
+        pending_config.game_duration_epoch = game_duration_epoch;
     }
     if let Some(game_extend) = update_config.game_extend {
-        if game_extend > game_config.game_duration {
-            return Err(ContractError::InvalidInput {});
-        }
-        game_config.game_extend = game_extend;
-    }
-    if let Some(game_end_threshold) = update_config.game_end_threshold {
-        game_config.game_end_threshold = game_end_threshold;
+        pending_config.game_extend = game_extend;
     }
     if let Some(min_pot_initial_allocation) = update_config.min_pot_initial_allocation {
-        game_config.min_pot_initial_allocation = min_pot_initial_allocation;
+        pending_config.min_pot_initial_allocation = min_pot_initial_allocation;
     }
     if let Some(decay_factor) = update_config.decay_factor {
-        if decay_factor.lt(&Decimal::from_str("0.01")?)
-            || decay_factor.gt(&Decimal::from_str("0.99")?)
-        {
-            return Err(ContractError::InvalidInput {});
-        }
-        game_config.decay_factor = decay_factor;
+        pending_config.decay_factor = decay_factor;
+    }
+    if let Some(min_bid_floor) = update_config.min_bid_floor {
+        pending_config.min_bid_floor = min_bid_floor;
     }
-    if let Some(reallocations_limit) = update_config.reallocations_limit {
-        game_config.reallocations_limit = reallocations_limit;
+    if let Some(voting_power_sources) = update_config.voting_power_sources {
+        pending_config.voting_power_sources = voting_power_sources;
     }
-    GAME_CONFIG.save(deps.storage, &game_config)?;
+    if let Some(bid_curve) = update_config.bid_curve {
+        pending_config.bid_curve = bid_curve;
+    }
+    if let Some(max_bid_multiplier) = update_config.max_bid_multiplier {
+        pending_config.max_bid_multiplier = max_bid_multiplier;
+    }
+    if let Some(max_player_exposure) = update_config.max_player_exposure {
+        pending_config.max_player_exposure = max_player_exposure;
+    }
+    let config_attributes = save_game_configs(deps.storage, deps.api, game_config, pending_config)?;
 
-    Ok(Response::new().add_attributes(vec![
-        attr("method", "execute"),
-        attr("action", "update_config"),
-        attr("admin", info.sender),
-        attr("config", format!("{:?}", game_config)),
-    ]))
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "update_config"),
+            attr("admin", info.sender),
+        ])
+        .add_attributes(config_attributes))
 }
 
 pub fn allocate_tokens(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     pot_id: u8,
 ) -> Result<Response, ContractError> {
     let game_config = GAME_CONFIG.load(deps.storage)?;
-    let game_state = GAME_STATE.load(deps.storage)?;
 
+    validate_not_paused(deps.storage)?;
     validate_game_time(deps.storage, &env)?;
+    verify_participation(&mut deps, &info.sender)?;
     let amount = validate_funds(&info.funds, &game_config.game_denom)?;
+
+    allocate(deps, env, info.sender, pot_id, amount, "allocate_tokens")
+}
+
+pub fn allocate_from_balance(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: u8,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    validate_not_paused(deps.storage)?;
+    validate_game_time(deps.storage, &env)?;
+    verify_participation(&mut deps, &info.sender)?;
+    // The tokens are already in the contract, so they only move from the balance to the pot
+    deduct_from_balance(deps.storage, &info.sender, amount)?;
+
+    allocate(
+        deps,
+        env,
+        info.sender,
+        pot_id,
+        amount,
+        "allocate_from_balance",
+    )
+}
+
+// Shared by allocate_tokens and allocate_from_balance, once the amount has been funded
+fn allocate(
+    mut deps: DepsMut,
+    env: Env,
+    player: Addr,
+    pot_id: u8,
+    amount: Uint128,
+    action: &str,
+) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
     validate_pot_limit_not_exceeded(deps.storage, pot_id, amount)?;
-    validate_existing_allocation(deps.storage, &info.sender, pot_id)?;
+    validate_existing_allocation(deps.storage, &player, pot_id)?;
 
     // Dynamic bid constraints
 
+    // re-verify the NFT registrations snapshot, so tokens transferred away since are not discounted
+    reverify_nft_registrations(&mut deps, &player)?;
     // min bid based on current addy so we discount by NFT holding
-    let min_bid = calculate_min_bid(&deps.as_ref(), &env, Some(info.sender.to_string()))?;
+    let min_bid = calculate_min_bid(&deps.as_ref(), &env, Some(player.to_string()))?;
 
     // get the originial min bid calculation without taking in account NFT holding discount
     let original_min_bid = calculate_min_bid(&deps.as_ref(), &env, None)?;
@@ -132,42 +209,52 @@ pub fn allocate_tokens(
             max: max_bid,
         });
     }
+    validate_player_exposure(
+        &game_config,
+        get_player_exposure(deps.storage, &player)?.checked_add(amount)?,
+    )?;
     // we do that here so the extend_count doesnt increase before we evaluate the min max bid amounts
     extend_game_time(deps.storage, &env)?;
 
     // Update the player's allocation and pot state
-    update_player_allocation(deps.storage, &info.sender, pot_id, amount, true)?;
+    update_player_allocation(deps.storage, &player, pot_id, amount, true)?;
     update_pot_state(deps.storage, pot_id, amount, true)?;
 
     // Update the first bidder for the current pot_id
-    set_first_bidder_if_not_set(deps.storage, pot_id, &info.sender, env.block.time.seconds())?;
+    set_first_bidder_if_not_set(deps.storage, pot_id, &player, env.block.time.seconds())?;
 
     Ok(Response::new().add_attributes(vec![
         attr("method", "execute"),
-        attr("action", "allocate_tokens"),
+        attr("action", action),
         attr("round_count", game_state.round_count.to_string()),
-        attr("player", info.sender),
+        attr("player", player),
         attr("pot_id", pot_id.to_string()),
         attr("amount", amount.to_string()),
     ]))
 }
 
 pub fn reallocate_tokens(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     from_pot_id: u8,
     to_pot_id: u8,
 ) -> Result<Response, ContractError> {
-    let game_config = GAME_CONFIG.load(deps.storage)?;
     let game_state = GAME_STATE.load(deps.storage)?;
 
     if from_pot_id == to_pot_id {
         return Err(ContractError::InvalidPot {});
     }
+    validate_not_paused(deps.storage)?;
     validate_game_time(deps.storage, &env)?;
+    verify_participation(&mut deps, &info.sender)?;
     extend_game_time(deps.storage, &env)?;
-    validate_increase_player_reallocations(deps.storage, &info.sender)?;
+
+    // NFT holders get extra reallocations and a lower fee, from the re-verified registrations snapshot
+    reverify_nft_registrations(&mut deps, &info.sender)?;
+    let (reallocations_limit, fee_reallocation) =
+        calculate_player_limits(&deps.as_ref(), &env, &info.sender, false)?;
+    validate_increase_player_reallocations(deps.storage, &info.sender, reallocations_limit)?;
     validate_existing_allocation(deps.storage, &info.sender, to_pot_id)?;
 
     // Load and check the player's allocations
@@ -183,7 +270,7 @@ pub fn reallocate_tokens(
 
     validate_pot_limit_not_exceeded(deps.storage, to_pot_id, amount)?;
 
-    let fee = amount.multiply_ratio(game_config.fee_reallocation, 100u128);
+    let fee = amount.multiply_ratio(fee_reallocation, 100u128);
     let net_amount = amount.checked_sub(fee)?;
 
     // Deduct the burning fee and update the burning fee pool
@@ -191,6 +278,12 @@ pub fn reallocate_tokens(
         current = current.checked_add(fee)?;
         Ok(current)
     })?;
+    // Keep track of the fees paid by the player, so they can be refunded if the round is cancelled
+    PLAYER_REALLOCATION_FEES.update(
+        deps.storage,
+        info.sender.to_string(),
+        |current| -> Result<_, ContractError> { Ok(current.unwrap_or_default().checked_add(fee)?) },
+    )?;
 
     // Update allocations and pot states using helper functions
     update_player_allocation(deps.storage, &info.sender, from_pot_id, amount, false)?; // sub
@@ -211,49 +304,67 @@ pub fn reallocate_tokens(
 }
 
 pub fn game_end(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    new_raffle_cw721_id: Option<String>,
-    new_raffle_cw721_addr: Option<String>,
     next_game_start: Option<u64>,
 ) -> Result<Response, ContractError> {
+    validate_not_paused(deps.storage)?;
     validate_game_end_time(deps.storage, &env)?;
-    validate_is_contract_admin_game_end(deps.storage, &deps.querier, &env, &info.sender)?;
+    validate_is_operator_game_end(deps.storage, &env, &info.sender)?;
 
     // if passed, it should be in the future
     if next_game_start.is_some() && next_game_start.unwrap() <= env.block.time.seconds() {
         return Err(ContractError::InvalidNextGameStart {});
     }
 
-    // Ensure both or neither options are provided
-    if new_raffle_cw721_id.is_some() != new_raffle_cw721_addr.is_some() {
-        return Err(ContractError::InvalidRaffleNft {});
-    }
-
     // Determine the winning pots and calculate total losing tokens
     let winning_pots = get_winning_pots(deps.storage)?;
     let total_losing_tokens = calculate_total_losing_tokens(deps.storage, &winning_pots)?;
+    let jackpot_trigger = get_jackpot_trigger(deps.storage, &winning_pots)?;
 
     let mut msgs: Vec<CosmosMsg> = vec![];
 
     // Process raffle winner and prepare distribution messages
-    let process_raffle_winner_resp = process_raffle_winner(
-        &deps.as_ref(),
-        &env,
-        &info.funds,
-        &winning_pots,
-        new_raffle_cw721_id,
-        new_raffle_cw721_addr,
-    )?;
+    let process_raffle_winner_resp =
+        process_raffle_winner(&deps.as_ref(), &env, &info.funds, &winning_pots)?;
     msgs.extend(process_raffle_winner_resp.msgs.clone());
 
+    update_raffle_unwon_rounds(deps.storage, &process_raffle_winner_resp.raffle_winner)?;
+
+    // Keep track of the NFT sent to the raffle winner so the reply can escrow it on failure
+    if let Some(outgoing_nft) = &process_raffle_winner_resp.outgoing_nft {
+        PENDING_NFT_TRANSFERS.save(deps.storage, outgoing_nft.round_count, outgoing_nft)?;
+    }
+
     // Add messages for redistributing tokens from losing to winning pots
-    let (send_msgs, treasury_outgoing_tokens) =
+    let distribution_resp =
         get_distribution_send_msgs(&deps.as_ref(), &winning_pots, total_losing_tokens)?;
-    msgs.extend(send_msgs.clone());
+    let treasury_outgoing_tokens = distribution_resp.treasury_outgoing_tokens;
+    msgs.extend(distribution_resp.msgs.clone());
     REALLOCATION_FEE_POOL.save(deps.storage, &Uint128::zero())?;
 
+    // Award the season points of the ended round, the fee slice stays in the contract as season pool
+    let game_state = GAME_STATE.load(deps.storage)?;
+    if get_active_season(deps.storage, game_state.round_count)?.is_some() {
+        add_to_season_pool(deps.storage, distribution_resp.season_pool_tokens)?;
+        award_season_points(deps.storage, &distribution_resp.player_winnings)?;
+    }
+
+    // Grow the jackpot and pay it out to the winning pots players if the board met one of its triggers
+    add_to_jackpot_pool(deps.storage, distribution_resp.jackpot_pool_tokens)?;
+    let mut total_outgoing_jackpot = Uint128::zero();
+    if let Some(trigger) = &jackpot_trigger {
+        let (jackpot_msgs, jackpot_amount) = process_jackpot_payout(
+            deps.storage,
+            game_state.round_count,
+            &winning_pots,
+            trigger.clone(),
+        )?;
+        msgs.extend(jackpot_msgs);
+        total_outgoing_jackpot = jackpot_amount;
+    }
+
     // Iterate again the msgs generated to know how much tokens effectively we send,
     // as total_losing_tokens contains also next game funds we want to preserve.
     let total_outgoing_raffle: Uint128 = process_raffle_winner_resp
@@ -269,7 +380,8 @@ pub fn game_end(
         .flatten()
         .map(|coin| coin.amount)
         .sum();
-    let total_outgoing_distribution: Uint128 = send_msgs
+    let total_outgoing_distribution: Uint128 = distribution_resp
+        .msgs
         .iter()
         .filter_map(|msg| {
             if let CosmosMsg::Bank(BankMsg::Send { amount, .. }) = msg {
@@ -281,19 +393,54 @@ pub fn game_end(
         .flatten()
         .map(|coin| coin.amount)
         .sum();
-    let total_outgoing_tokens = total_outgoing_raffle.checked_add(total_outgoing_distribution)?;
+    let total_outgoing_tokens = total_outgoing_raffle
+        .checked_add(total_outgoing_distribution)?
+        .checked_add(total_outgoing_jackpot)?;
+    // The auto-compounding winnings stay in the contract, but they are not part of the next round initial pots
+    let total_compounding_tokens: Uint128 = distribution_resp
+        .compounding_winnings
+        .iter()
+        .map(|winnings| winnings.amount)
+        .sum();
+
+    // The winnings credited to the player balances are reserved with the balances when preparing the next game
+    let mut total_balance_tokens = Uint128::zero();
+    for (player, amount) in &distribution_resp.balance_winnings {
+        add_to_balance(deps.storage, &Addr::unchecked(player), *amount)?;
+        total_balance_tokens = total_balance_tokens.checked_add(*amount)?;
+    }
 
     // Reset and prepare for the next game
+    let game_denom = GAME_CONFIG.load(deps.storage)?.game_denom;
     let (old_round_count, _new_round_count, old_extend_count) = prepare_next_game(
-        deps,
+        deps.branch(),
         &env,
-        total_outgoing_tokens,
+        total_outgoing_tokens.checked_add(total_compounding_tokens)?,
         process_raffle_winner_resp.new_raffle_cw721_id,
         process_raffle_winner_resp.new_raffle_cw721_addr,
         Some(process_raffle_winner_resp.new_raffle_denom_amount),
         next_game_start,
     )?;
 
+    // Allocate the auto-compounding winnings to the next round pots, now that they are funded.
+    // They are sent instead when the game denom switched, as the next round pots are in the new denom.
+    let (compounded_tokens, claimable_tokens) =
+        if GAME_CONFIG.load(deps.storage)?.game_denom == game_denom {
+            process_auto_compound(deps.branch(), &env, distribution_resp.compounding_winnings)?
+        } else {
+            for winnings in distribution_resp.compounding_winnings {
+                msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: winnings.player.to_string(),
+                    amount: vec![coin(winnings.amount.u128(), &game_denom)],
+                }));
+            }
+            (Uint128::zero(), Uint128::zero())
+        };
+
+    // Pay out the season pool once its last round is ended, it was already reserved out of the next round pots
+    let (season_msgs, season_payouts) = process_season_end(deps.storage, old_round_count)?;
+    msgs.extend(season_msgs);
+
     Ok(Response::new()
         .add_messages(msgs)
         .add_submessages(process_raffle_winner_resp.submsgs)
@@ -308,22 +455,27 @@ pub fn game_end(
                 total_outgoing_distribution.checked_sub(treasury_outgoing_tokens)?, // this is just about legacy distribution
             ),
             attr("treasury_outgoing_tokens", treasury_outgoing_tokens),
+            attr("season_pool_tokens", distribution_resp.season_pool_tokens),
+            attr("season_payouts", format!("{:?}", season_payouts)),
+            attr("jackpot_pool_tokens", distribution_resp.jackpot_pool_tokens),
+            attr("jackpot_trigger", format!("{:?}", jackpot_trigger)),
+            attr("jackpot_outgoing_tokens", total_outgoing_jackpot),
+            attr("compounded_tokens", compounded_tokens),
+            attr("claimable_tokens", claimable_tokens),
+            attr("balance_tokens", total_balance_tokens),
         ])
         .add_attributes(process_raffle_winner_resp.attributes) // this contains the raffle event attributes including the treasury denom fee split, which is not included above
-        .add_attribute("total_outgoing_tokens", total_outgoing_tokens)) // this is the total of distribution + raffle + treasury
+        .add_attribute("total_outgoing_tokens", total_outgoing_tokens)) // this is the total of distribution + raffle + treasury + jackpot
 }
 
 pub fn update_next_game(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    new_raffle_cw721_id: Option<String>,
-    new_raffle_cw721_addr: Option<String>,
     next_game_start: Option<u64>,
 ) -> Result<Response, ContractError> {
-    validate_is_contract_admin(&deps.querier, &env, &info.sender)?;
+    validate_is_operator(deps.storage, &info.sender)?;
 
-    let mut submsgs: Vec<SubMsg> = vec![];
     let mut response_attributes = vec![];
 
     // Handle start time update
@@ -343,44 +495,6 @@ pub fn update_next_game(
         response_attributes.push(attr("next_game_start", start_time.to_string()));
     }
 
-    // Handle raffle NFT update, if a raffle_id is passed we assume we want to set an NFT for the next round's raffle
-    if let Some(raffle_id) = &new_raffle_cw721_id {
-        // Ensure both or neither options are provided or throw an error
-        if new_raffle_cw721_id.is_some() != new_raffle_cw721_addr.is_some() {
-            return Err(ContractError::InvalidRaffleNft {});
-        }
-
-        // Update the raffle CW721 token ID and address, only if there is no one yet
-        RAFFLE.update(deps.storage, |mut raffle| -> Result<_, ContractError> {
-            if raffle.cw721_token_id.is_none() {
-                raffle.cw721_token_id = Some(raffle_id.clone());
-                raffle.cw721_addr = new_raffle_cw721_addr.clone();
-            } else {
-                // Otherwise throw
-                return Err(ContractError::InvalidRaffleNft {});
-            }
-            Ok(raffle)
-        })?;
-
-        // Transfer the NFT
-        let transfer_nft_msg = SubMsg::reply_always(
-            WasmMsg::Execute {
-                contract_addr: new_raffle_cw721_addr.clone().unwrap(),
-                msg: to_json_binary(&cw721::Cw721ExecuteMsg::TransferNft {
-                    recipient: env.contract.address.to_string(),
-                    token_id: raffle_id.to_string(),
-                })?,
-                funds: vec![],
-            },
-            ReplyMsg::TransferNft as u64,
-        );
-        submsgs.push(transfer_nft_msg);
-        response_attributes.extend(vec![
-            attr("raffle_cw721_addr", new_raffle_cw721_addr.unwrap()),
-            attr("raffle_cw721_id", raffle_id),
-        ]);
-    }
-
     // Handle raffle funds update, validate_funds to obtain current sent funds
     let game_denom = GAME_CONFIG.load(deps.storage)?.game_denom;
     let total_amount = validate_funds(&info.funds, &game_denom).unwrap_or_default();
@@ -400,17 +514,1048 @@ pub fn update_next_game(
     }
 
     // Create response with dynamic attributes based on the current execution
-    let mut response = Response::new()
+    Ok(Response::new()
         .add_attributes(vec![
             attr("method", "execute"),
             attr("action", "update_next_game"),
         ])
-        .add_attributes(response_attributes);
+        .add_attributes(response_attributes))
+}
+
+pub fn sponsor_raffle(
+    deps: DepsMut,
+    info: MessageInfo,
+    message: Option<String>,
+) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    validate_sponsor_message(&message)?;
+    let amount = validate_funds(&info.funds, &game_config.game_denom)?;
+
+    // Increment the denom amount for the current raffle, the same way the admin does via update_next_game
+    RAFFLE.update(deps.storage, |mut raffle| -> Result<_, ContractError> {
+        raffle.denom_amount = raffle.denom_amount.checked_add(amount)?;
+        Ok(raffle)
+    })?;
+
+    add_raffle_sponsor(
+        deps.storage,
+        game_state.round_count,
+        RaffleSponsor {
+            sponsor: info.sender.clone(),
+            denom_amount: amount,
+            cw721_addr: None,
+            cw721_token_id: None,
+            message,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "sponsor_raffle"),
+        attr("round_count", game_state.round_count.to_string()),
+        attr("sponsor", info.sender),
+        attr("raffle_denom_amount", amount.to_string()),
+    ]))
+}
+
+pub fn receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive_msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // Only whitelisted collections can forward NFTs, and the token should be owned by the contract at this point
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    if !game_config
+        .game_cw721_collections
+        .iter()
+        .any(|collection| collection.addr == info.sender)
+    {
+        return Err(ContractError::InvalidRaffleNft {});
+    }
+    validate_nft_received(&deps.querier, &env, &info.sender, &receive_msg.token_id)?;
+
+    match from_json(&receive_msg.msg)? {
+        ReceiveNftMsg::SetRaffleNft { .. } => {
+            set_raffle_nft_received(deps, info.sender, receive_msg.sender, receive_msg.token_id)
+        }
+        ReceiveNftMsg::SponsorRaffle { message, .. } => sponsor_raffle_nft(
+            deps,
+            info.sender, // the cw721 contract forwarding the hook
+            receive_msg.sender,
+            receive_msg.token_id,
+            message,
+        ),
+    }
+}
+
+fn set_raffle_nft_received(
+    deps: DepsMut,
+    cw721_addr: Addr,
+    sender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // The original NFT sender should be an operator, as for update_next_game
+    let sender = deps.api.addr_validate(&sender)?;
+    validate_is_operator(deps.storage, &sender)?;
+
+    set_raffle_nft(deps.storage, &cw721_addr, &token_id)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_raffle_nft"),
+        attr("raffle_cw721_addr", cw721_addr),
+        attr("raffle_cw721_id", token_id),
+    ]))
+}
+
+fn sponsor_raffle_nft(
+    deps: DepsMut,
+    cw721_addr: Addr,
+    sender: String,
+    token_id: String,
+    message: Option<String>,
+) -> Result<Response, ContractError> {
+    let game_state = GAME_STATE.load(deps.storage)?;
+    let sponsor = deps.api.addr_validate(&sender)?;
+
+    validate_sponsor_message(&message)?;
+    set_raffle_nft(deps.storage, &cw721_addr, &token_id)?;
+
+    add_raffle_sponsor(
+        deps.storage,
+        game_state.round_count,
+        RaffleSponsor {
+            sponsor: sponsor.clone(),
+            denom_amount: Uint128::zero(),
+            cw721_addr: Some(cw721_addr.to_string()),
+            cw721_token_id: Some(token_id.clone()),
+            message,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "sponsor_raffle_nft"),
+        attr("round_count", game_state.round_count.to_string()),
+        attr("sponsor", sponsor),
+        attr("raffle_cw721_addr", cw721_addr),
+        attr("raffle_cw721_id", token_id),
+    ]))
+}
+
+pub fn claim_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw721_addr: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let unclaimed_nft = UNCLAIMED_NFTS
+        .may_load(deps.storage, (&cw721_addr, &token_id))?
+        .ok_or(ContractError::UnclaimedNftNotFound {})?;
+
+    // The raffle winner can claim at any time, the owner only after the claim timeout
+    if info.sender != unclaimed_nft.recipient {
+        validate_is_owner(deps.storage, &info.sender)?;
+
+        let nft_claim_timeout = GAME_CONFIG.load(deps.storage)?.nft_claim_timeout;
+        let reclaimable_at = unclaimed_nft.escrow_time.saturating_add(nft_claim_timeout);
+        if env.block.time.seconds() < reclaimable_at {
+            return Err(ContractError::NftClaimTimeoutNotReached { reclaimable_at });
+        }
+    }
+
+    UNCLAIMED_NFTS.remove(deps.storage, (&cw721_addr, &token_id));
+
+    // A failing transfer here reverts the claim, so the NFT stays escrowed
+    let transfer_nft_msg = WasmMsg::Execute {
+        contract_addr: cw721_addr.clone(),
+        msg: to_json_binary(&cw721::Cw721ExecuteMsg::TransferNft {
+            recipient: info.sender.to_string(),
+            token_id: token_id.clone(),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(transfer_nft_msg)
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "claim_nft"),
+            attr("recipient", info.sender),
+            attr("cw721_addr", cw721_addr),
+            attr("cw721_id", token_id),
+        ]))
+}
+
+pub fn recover_assets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recover_raffle: bool,
+    cw721_tokens: Vec<Cw721Token>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+    let raffle = RAFFLE.load(deps.storage)?;
+
+    // Sweep any coin other than the game denoms, as pots, fee pools and raffle prizes of every table are only made of them
+    let reserved_denoms = get_reserved_denoms(deps.storage)?;
+    let mut recovered_coins: Vec<Coin> = deps
+        .querier
+        .query_all_balances(&env.contract.address)?
+        .into_iter()
+        .filter(|balance| {
+            balance.denom != game_config.game_denom
+                && !reserved_denoms.contains(&balance.denom)
+                && !balance.amount.is_zero()
+        })
+        .collect();
+    let mut recovered_cw721_tokens: Vec<Cw721Token> = vec![];
+
+    // Reclaim the raffle prizes only if they have been unwon for more than raffle_expiry_rounds
+    if recover_raffle {
+        let rounds_unwon = RAFFLE_UNWON_ROUNDS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        if rounds_unwon <= game_config.raffle_expiry_rounds {
+            return Err(ContractError::RaffleNotExpired {
+                rounds_unwon,
+                raffle_expiry_rounds: game_config.raffle_expiry_rounds,
+            });
+        }
+
+        // What has been sponsored during the last raffle_expiry_rounds is not expired, and stays as raffle prize
+        let (unexpired_denom_amount, unexpired_cw721_tokens) = get_unexpired_sponsorships(
+            deps.storage,
+            game_state.round_count,
+            game_config.raffle_expiry_rounds,
+        )?;
+        let expired_denom_amount = raffle.denom_amount.saturating_sub(unexpired_denom_amount);
+        if !expired_denom_amount.is_zero() {
+            recovered_coins.push(coin(expired_denom_amount.u128(), &game_config.game_denom));
+        }
+        let mut new_raffle = Raffle {
+            cw721_token_id: None,
+            cw721_addr: None,
+            denom_amount: raffle.denom_amount.checked_sub(expired_denom_amount)?,
+        };
+        if let (Some(token_id), Some(cw721_addr)) =
+            (raffle.cw721_token_id.clone(), raffle.cw721_addr.clone())
+        {
+            let cw721_token = Cw721Token {
+                cw721_addr,
+                token_id,
+            };
+            if unexpired_cw721_tokens.contains(&cw721_token) {
+                new_raffle.cw721_token_id = Some(cw721_token.token_id);
+                new_raffle.cw721_addr = Some(cw721_token.cw721_addr);
+            } else {
+                recovered_cw721_tokens.push(cw721_token);
+            }
+        }
+
+        RAFFLE.save(deps.storage, &new_raffle)?;
+        RAFFLE_UNWON_ROUNDS.save(deps.storage, &0)?;
+    }
+
+    // Sweep cw721 tokens sent by mistake, the raffle prizes and escrowed NFTs of every table are checked upfront
+    for cw721_token in cw721_tokens {
+        deps.api.addr_validate(&cw721_token.cw721_addr)?;
+        recovered_cw721_tokens.push(cw721_token);
+    }
+
+    // Throw an error if there is nothing to recover, this method is meant to be executed for a reason
+    if recovered_coins.is_empty() && recovered_cw721_tokens.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !recovered_coins.is_empty() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: recovered_coins.clone(),
+        }));
+    }
+    for cw721_token in &recovered_cw721_tokens {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: cw721_token.cw721_addr.clone(),
+            msg: to_json_binary(&cw721::Cw721ExecuteMsg::TransferNft {
+                recipient: info.sender.to_string(),
+                token_id: cw721_token.token_id.clone(),
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    // Record the recovery in the on-chain audit log
+    let recovery_id = RECOVERY_LOG
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |last_id| last_id + 1);
+    RECOVERY_LOG.save(
+        deps.storage,
+        recovery_id,
+        &AssetRecovery {
+            id: recovery_id,
+            recipient: info.sender.clone(),
+            round_count: game_state.round_count,
+            time: env.block.time.seconds(),
+            coins: recovered_coins.clone(),
+            cw721_tokens: recovered_cw721_tokens.clone(),
+            expired_raffle: recover_raffle,
+        },
+    )?;
+
+    Ok(Response::new().add_messages(msgs).add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "recover_assets"),
+        attr("recovery_id", recovery_id.to_string()),
+        attr("recipient", info.sender),
+        attr("recovered_coins", format!("{:?}", recovered_coins)),
+        attr(
+            "recovered_cw721_tokens",
+            format!("{:?}", recovered_cw721_tokens),
+        ),
+        attr("expired_raffle", recover_raffle.to_string()),
+    ]))
+}
+
+pub fn register_nfts(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: String,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    let collection = deps.api.addr_validate(&collection)?;
+    let cw721_collection = validate_register_nfts(deps.storage, &collection, &token_ids)?;
+
+    // Verify the ownership once, the snapshot is then re-verified lazily when used for a bid
+    let mut tokens = vec![];
+    for token_id in &token_ids {
+        if !is_nft_owner(&deps.querier, &collection, token_id, &info.sender)? {
+            return Err(ContractError::Cw721TokenNotOwned {
+                token_id: token_id.to_string(),
+            });
+        }
+        tokens.push(RegisteredNft {
+            token_id: token_id.to_string(),
+            weight: get_nft_weight(deps.storage, &deps.querier, &cw721_collection, token_id)?,
+        });
+    }
+
+    // Replace any previous registration of the sender for this collection
+    NFT_REGISTRATIONS.save(
+        deps.storage,
+        (&info.sender, &collection),
+        &NftRegistration {
+            collection: collection.clone(),
+            tokens,
+            round_count: game_state.round_count,
+            block_height: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "register_nfts"),
+        attr("round_count", game_state.round_count.to_string()),
+        attr("player", info.sender),
+        attr("collection", collection),
+        attr("token_ids", token_ids.join(",")),
+    ]))
+}
+
+pub fn transfer_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
 
-    // If submsgs is not empty, append the message to transfer the NFT
-    if !submsgs.is_empty() {
-        response = response.add_submessages(submsgs);
+    // The new owner has to accept it, until then the current owner keeps the ownership
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    OWNERSHIP.update(deps.storage, |mut ownership| -> Result<_, ContractError> {
+        ownership.pending_owner = Some(new_owner.clone());
+        Ok(ownership)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "transfer_ownership"),
+        attr("owner", info.sender),
+        attr("pending_owner", new_owner),
+    ]))
+}
+
+pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut ownership = OWNERSHIP.load(deps.storage)?;
+    if ownership.pending_owner.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
     }
 
-    Ok(response)
+    ownership.owner = ownership.pending_owner.take();
+    OWNERSHIP.save(deps.storage, &ownership)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "accept_ownership"),
+        attr("owner", info.sender),
+    ]))
+}
+
+pub fn renounce_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    // Without owner the config is frozen, and game_end becomes permissionless
+    OWNERSHIP.save(
+        deps.storage,
+        &Ownership {
+            owner: None,
+            pending_owner: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "renounce_ownership"),
+        attr("owner", info.sender),
+    ]))
+}
+
+pub fn update_operators(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let mut operators = OPERATORS.load(deps.storage)?;
+    for operator in &add {
+        let operator = deps.api.addr_validate(operator)?;
+        if !operators.contains(&operator) {
+            operators.push(operator);
+        }
+    }
+    for operator in &remove {
+        let operator = deps.api.addr_validate(operator)?;
+        operators.retain(|existing| *existing != operator);
+    }
+    OPERATORS.save(deps.storage, &operators)?;
+
+    let mut response_attributes = vec![
+        attr("method", "execute"),
+        attr("action", "update_operators"),
+        attr("operators_count", operators.len().to_string()),
+    ];
+    if !add.is_empty() {
+        response_attributes.push(attr("added", add.join(",")));
+    }
+    if !remove.is_empty() {
+        response_attributes.push(attr("removed", remove.join(",")));
+    }
+
+    Ok(Response::new().add_attributes(response_attributes))
+}
+
+pub fn pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    PAUSED.save(deps.storage, &true)?;
+
+    Ok(Response::new().add_attributes(vec![attr("method", "execute"), attr("action", "pause")]))
+}
+
+pub fn unpause(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let mut response_attributes = vec![attr("method", "execute"), attr("action", "unpause")];
+
+    // The players not refunded yet can still claim their refund, the remaining balance seeds the next round keeping the same raffle
+    if ROUND_CANCELLED.may_load(deps.storage)?.unwrap_or_default() {
+        let pending_refunds = move_pending_refunds_to_claims(deps.storage)?;
+        response_attributes.push(attr("pending_refunds", pending_refunds.to_string()));
+        ROUND_CANCELLED.save(deps.storage, &false)?;
+        REALLOCATION_FEE_POOL.save(deps.storage, &Uint128::zero())?;
+
+        let raffle = RAFFLE.load(deps.storage)?;
+        let (old_round_count, new_round_count, _old_extend_count) = prepare_next_game(
+            deps.branch(),
+            &env,
+            Uint128::zero(),
+            raffle.cw721_token_id,
+            raffle.cw721_addr,
+            Some(raffle.denom_amount),
+            None,
+        )?;
+        response_attributes.push(attr("cancelled_round_count", old_round_count.to_string()));
+        response_attributes.push(attr("round_count", new_round_count.to_string()));
+    }
+
+    PAUSED.save(deps.storage, &false)?;
+
+    Ok(Response::new().add_attributes(response_attributes))
+}
+
+pub fn cancel_round(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    if ROUND_CANCELLED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::RoundCancelled {});
+    }
+
+    // Cancelling also pauses the game, until the owner unpauses it once every player has been refunded
+    ROUND_CANCELLED.save(deps.storage, &true)?;
+    PAUSED.save(deps.storage, &true)?;
+
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "cancel_round"),
+        attr("round_count", game_state.round_count.to_string()),
+    ]))
+}
+
+pub fn refund(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    if !ROUND_CANCELLED.may_load(deps.storage)?.unwrap_or_default() {
+        // Once unpaused, the refunds not claimed during the cancelled round are kept as claims
+        let amount = REFUND_CLAIMS
+            .may_load(deps.storage, &info.sender)?
+            .ok_or(ContractError::RoundNotCancelled {})?;
+        REFUND_CLAIMS.remove(deps.storage, &info.sender);
+        REFUND_CLAIMS_TOTAL.update(deps.storage, |total| -> Result<_, ContractError> {
+            Ok(total.checked_sub(amount)?)
+        })?;
+
+        return Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(amount.u128(), game_config.game_denom)],
+            })
+            .add_attributes(vec![
+                attr("method", "execute"),
+                attr("action", "refund"),
+                attr("round_count", game_state.round_count.to_string()),
+                attr("player", info.sender),
+                attr("amount", amount.to_string()),
+            ]));
+    }
+
+    // Refund the player allocations, adding back the reallocation fees paid this round
+    let allocations = PLAYER_ALLOCATIONS
+        .may_load(deps.storage, info.sender.to_string())?
+        .unwrap_or_default();
+    let fees = PLAYER_REALLOCATION_FEES
+        .may_load(deps.storage, info.sender.to_string())?
+        .unwrap_or_default();
+
+    let mut amount = fees;
+    for allocation in &allocations {
+        amount = amount.checked_add(allocation.amount)?;
+        POT_STATES.update(
+            deps.storage,
+            allocation.pot_id,
+            |pot_state| -> Result<_, ContractError> {
+                let mut pot_state = pot_state.ok_or(ContractError::InvalidPot {})?;
+                pot_state.amount = pot_state.amount.checked_sub(allocation.amount)?;
+                Ok(pot_state)
+            },
+        )?;
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NoRefundAvailable {});
+    }
+
+    REALLOCATION_FEE_POOL.update(deps.storage, |current| -> Result<_, ContractError> {
+        Ok(current.checked_sub(fees)?)
+    })?;
+    PLAYER_ALLOCATIONS.remove(deps.storage, info.sender.to_string());
+    PLAYER_REALLOCATION_FEES.remove(deps.storage, info.sender.to_string());
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), game_config.game_denom)],
+        })
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "refund"),
+            attr("round_count", game_state.round_count.to_string()),
+            attr("player", info.sender),
+            attr("amount", amount.to_string()),
+        ]))
+}
+
+pub fn propose_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    changes: TimelockedConfig,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    // Validate the proposal against the current configs, it is validated again once executed
+    let (game_config, pending_config) = load_game_configs(deps.storage)?;
+    let (game_config, pending_config) =
+        apply_timelocked_config(game_config, pending_config, &changes)?;
+    validate_game_config(deps.api, &game_config)?;
+    validate_game_config(deps.api, &pending_config)?;
+    validate_table_denoms(deps.storage, &[pending_config.game_denom])?;
+
+    // Executed and cancelled proposals are removed, so ids are tracked apart to never be reused
+    let proposal_id = PROPOSAL_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(1)
+        .ok_or(ContractError::InvalidInput {})?;
+    PROPOSAL_COUNT.save(deps.storage, &proposal_id)?;
+    let eta = env
+        .block
+        .time
+        .seconds()
+        .checked_add(GAME_CONFIG.load(deps.storage)?.timelock_delay)
+        .ok_or(ContractError::InvalidInput {})?;
+    PROPOSALS.save(
+        deps.storage,
+        proposal_id,
+        &ConfigProposal {
+            id: proposal_id,
+            changes: changes.clone(),
+            proposed_at: env.block.time.seconds(),
+            eta,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "propose_config"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("eta", eta.to_string()),
+        attr("changes", format!("{:?}", changes)),
+    ]))
+}
+
+pub fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    validate_is_operator(deps.storage, &info.sender)?;
+
+    let proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound {})?;
+    if env.block.time.seconds() < proposal.eta {
+        return Err(ContractError::ProposalNotReady { eta: proposal.eta });
+    }
+
+    let (game_config, pending_config) = load_game_configs(deps.storage)?;
+    let (game_config, pending_config) =
+        apply_timelocked_config(game_config, pending_config, &proposal.changes)?;
+    validate_game_denom_change(
+        deps.storage,
+        &deps.querier,
+        &env,
+        &game_config,
+        &pending_config,
+    )?;
+    let config_attributes = save_game_configs(deps.storage, deps.api, game_config, pending_config)?;
+    PROPOSALS.remove(deps.storage, proposal_id);
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "execute_proposal"),
+            attr("proposal_id", proposal_id.to_string()),
+        ])
+        .add_attributes(config_attributes))
+}
+
+pub fn cancel_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    if !PROPOSALS.has(deps.storage, proposal_id) {
+        return Err(ContractError::ProposalNotFound {});
+    }
+    PROPOSALS.remove(deps.storage, proposal_id);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "cancel_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+    ]))
+}
+
+pub fn set_vote_options(
+    deps: DepsMut,
+    info: MessageInfo,
+    options: Vec<VoteOption>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    // Changing the options would change what the players already voted for
+    if !PLAYER_VOTES.is_empty(deps.storage) {
+        return Err(ContractError::VotingInProgress {});
+    }
+    // The options are applied over the config the next round would start with
+    let (_game_config, pending_config) = load_game_configs(deps.storage)?;
+    validate_vote_options(deps.api, &pending_config, &options)?;
+
+    VOTE_OPTIONS.save(deps.storage, &options)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_vote_options"),
+        attr("options_count", options.len().to_string()),
+    ]))
+}
+
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    option_id: u32,
+) -> Result<Response, ContractError> {
+    validate_not_paused(deps.storage)?;
+    validate_game_time(deps.storage, &env)?;
+
+    let options = VOTE_OPTIONS.may_load(deps.storage)?.unwrap_or_default();
+    if option_id as usize >= options.len() {
+        return Err(ContractError::InvalidVoteOption {});
+    }
+
+    // Only the players committed to the current round can vote, the weight is counted at game end
+    let allocations = PLAYER_ALLOCATIONS
+        .may_load(deps.storage, info.sender.to_string())?
+        .unwrap_or_default();
+    if allocations
+        .iter()
+        .all(|allocation| allocation.amount.is_zero())
+    {
+        return Err(ContractError::NoAllocationsToVote {});
+    }
+
+    PLAYER_VOTES.save(deps.storage, info.sender.to_string(), &option_id)?;
+
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "vote"),
+        attr("round_count", game_state.round_count.to_string()),
+        attr("player", info.sender),
+        attr("option_id", option_id.to_string()),
+    ]))
+}
+
+pub fn start_season(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: SeasonConfig,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    let previous_season = SEASON.may_load(deps.storage)?;
+    if previous_season
+        .as_ref()
+        .is_some_and(|season| !season.finished)
+    {
+        return Err(ContractError::SeasonInProgress {});
+    }
+    validate_season_config(&config)?;
+
+    // A round already played is never counted, so the season starts from the next round once someone allocated
+    let game_state = GAME_STATE.load(deps.storage)?;
+    let start_round = if PLAYER_ALLOCATIONS.is_empty(deps.storage) {
+        game_state.round_count
+    } else {
+        game_state.round_count + 1
+    };
+    let season = Season {
+        id: previous_season.map_or(1, |season| season.id + 1),
+        start_round,
+        end_round: start_round + config.rounds - 1,
+        config,
+        denom: GAME_CONFIG.load(deps.storage)?.game_denom,
+        pool: Uint128::zero(),
+        leaders: vec![],
+        payouts: vec![],
+        finished: false,
+    };
+    SEASON.save(deps.storage, &season)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "start_season"),
+        attr("season_id", season.id.to_string()),
+        attr("start_round", season.start_round.to_string()),
+        attr("end_round", season.end_round.to_string()),
+    ]))
+}
+
+pub fn set_jackpot(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: JackpotConfig,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+    validate_jackpot_config(&config)?;
+
+    // Changing the jackpot settings keeps the pool collected so far
+    let jackpot = match JACKPOT.may_load(deps.storage)? {
+        Some(jackpot) => Jackpot { config, ..jackpot },
+        None => Jackpot {
+            config,
+            denom: GAME_CONFIG.load(deps.storage)?.game_denom,
+            pool: Uint128::zero(),
+            last_payout: None,
+        },
+    };
+    JACKPOT.save(deps.storage, &jackpot)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_jackpot"),
+        attr("jackpot_pool", jackpot.pool),
+    ]))
+}
+
+pub fn set_participation_gate(
+    deps: DepsMut,
+    info: MessageInfo,
+    gate: ParticipationGate,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    if let ParticipationGate::Cw4Group { addr } = &gate {
+        deps.api.addr_validate(addr.as_str())?;
+    }
+    PARTICIPATION_GATE.save(deps.storage, &gate)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_participation_gate"),
+        attr("gate", format!("{:?}", gate)),
+    ]))
+}
+
+pub fn update_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    update_address_list(deps.storage, deps.api, &ALLOWLIST, &add, &remove)?;
+    // Players removed from the allowlist are no longer verified for the rest of the round
+    for address in &remove {
+        PARTICIPATION_VERIFIED.remove(deps.storage, &deps.api.addr_validate(address)?);
+    }
+
+    let mut response_attributes = vec![
+        attr("method", "execute"),
+        attr("action", "update_allowlist"),
+    ];
+    if !add.is_empty() {
+        response_attributes.push(attr("added", add.join(",")));
+    }
+    if !remove.is_empty() {
+        response_attributes.push(attr("removed", remove.join(",")));
+    }
+
+    Ok(Response::new().add_attributes(response_attributes))
+}
+
+pub fn update_blocklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    update_address_list(deps.storage, deps.api, &BLOCKLIST, &add, &remove)?;
+
+    let mut response_attributes = vec![
+        attr("method", "execute"),
+        attr("action", "update_blocklist"),
+    ];
+    if !add.is_empty() {
+        response_attributes.push(attr("added", add.join(",")));
+    }
+    if !remove.is_empty() {
+        response_attributes.push(attr("removed", remove.join(",")));
+    }
+
+    Ok(Response::new().add_attributes(response_attributes))
+}
+
+pub fn set_auto_compound(
+    deps: DepsMut,
+    info: MessageInfo,
+    pot_strategy: Option<AutoCompoundStrategy>,
+) -> Result<Response, ContractError> {
+    match &pot_strategy {
+        Some(strategy) => {
+            if let AutoCompoundStrategy::FixedPot { pot_id } = strategy {
+                if !(1..=5).contains(pot_id) {
+                    return Err(ContractError::InvalidPot {});
+                }
+            }
+            AUTO_COMPOUND.save(deps.storage, &info.sender, strategy)?;
+        }
+        None => AUTO_COMPOUND.remove(deps.storage, &info.sender),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_auto_compound"),
+        attr("player", info.sender),
+        attr("pot_strategy", format!("{:?}", pot_strategy)),
+    ]))
+}
+
+pub fn claim_balance(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+
+    let amount = take_claimable(deps.storage, &info.sender)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), game_config.game_denom)],
+        })
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "claim_balance"),
+            attr("player", info.sender),
+            attr("amount", amount),
+        ]))
+}
+
+pub fn deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+
+    let amount = validate_funds(&info.funds, &game_config.game_denom)?;
+    let balance = add_to_balance(deps.storage, &info.sender, amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "deposit"),
+        attr("player", info.sender),
+        attr("amount", amount),
+        attr("balance", balance),
+    ]))
+}
+
+pub fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+
+    // Withdrawals are never paused nor gated, as the balances are not part of the game
+    let balance = deduct_from_balance(deps.storage, &info.sender, amount)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), game_config.game_denom)],
+        })
+        .add_attributes(vec![
+            attr("method", "execute"),
+            attr("action", "withdraw"),
+            attr("player", info.sender),
+            attr("amount", amount),
+            attr("balance", balance),
+        ]))
+}
+
+pub fn set_balance_payouts(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if enabled {
+        BALANCE_PAYOUTS.save(deps.storage, &info.sender, &Empty {})?;
+    } else {
+        BALANCE_PAYOUTS.remove(deps.storage, &info.sender);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "set_balance_payouts"),
+        attr("player", info.sender),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+pub fn create_table(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: GameConfig,
+    next_game_start: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_is_owner(deps.storage, &info.sender)?;
+
+    // Validate game_config fields and initial funds, as on instantiation
+    validate_game_config(deps.api, &config)?;
+    let total_amount = validate_funds(&info.funds, &config.game_denom)?;
+    validate_pot_initial_amount(&config.min_pot_initial_allocation, &total_amount)?;
+
+    // The default table is the first one, so new tables are counted from 1
+    let table_id = TABLE_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(1)
+        .ok_or(ContractError::InvalidInput {})?;
+    TABLE_COUNT.save(deps.storage, &table_id)?;
+
+    // Initialize the table like a fresh contract, the tables registry entry is saved along with the game
+    let mut table_storage = TableStorage::new(deps.storage, table_id);
+    TABLE_ID.save(&mut table_storage, &table_id)?;
+    GAME_CONFIG.save(&mut table_storage, &config)?;
+    REALLOCATION_FEE_POOL.save(&mut table_storage, &Uint128::zero())?;
+    prepare_next_game(
+        DepsMut {
+            storage: &mut table_storage,
+            api: deps.api,
+            querier: deps.querier,
+        },
+        &env,
+        Uint128::zero(),
+        None,
+        None,
+        None,
+        next_game_start,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "execute"),
+        attr("action", "create_table"),
+        attr("table_id", table_id.to_string()),
+        attr("config", format!("{:?}", config)),
+    ]))
 }
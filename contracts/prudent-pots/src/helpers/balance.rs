@@ -0,0 +1,49 @@
+use cosmwasm_std::{Addr, Storage, Uint128};
+
+use crate::{
+    state::{BALANCES, BALANCES_TOTAL},
+    ContractError,
+};
+
+/// Helper to credit tokens to a player internal balance.
+pub fn add_to_balance(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let total = get_total_balances(storage)?.checked_add(amount)?;
+    BALANCES_TOTAL.save(storage, &total)?;
+    BALANCES.update(storage, player, |current| -> Result<_, ContractError> {
+        Ok(current.unwrap_or_default().checked_add(amount)?)
+    })
+}
+
+/// Helper to debit tokens from a player internal balance, removing it once empty.
+pub fn deduct_from_balance(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidFunds {});
+    }
+    let balance = BALANCES
+        .may_load(storage, player)?
+        .unwrap_or_default()
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientFunds {})?;
+    if balance.is_zero() {
+        BALANCES.remove(storage, player);
+    } else {
+        BALANCES.save(storage, player, &balance)?;
+    }
+    let total = get_total_balances(storage)?.checked_sub(amount)?;
+    BALANCES_TOTAL.save(storage, &total)?;
+    Ok(balance)
+}
+
+/// Helper to get the total of the player balances, which is reserved out of the next rounds pots.
+/// It is kept as a running total so the game end does not range over every player.
+pub fn get_total_balances(storage: &dyn Storage) -> Result<Uint128, ContractError> {
+    Ok(BALANCES_TOTAL.may_load(storage)?.unwrap_or_default())
+}
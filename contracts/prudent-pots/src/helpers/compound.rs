@@ -0,0 +1,197 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Storage, Uint128};
+
+use crate::{
+    state::{
+        AutoCompoundStrategy, AUTO_COMPOUND, CLAIMABLE_BALANCES, CLAIMABLE_TOTAL, GAME_CONFIG,
+        GAME_STATE, PLAYER_ALLOCATIONS, POT_STATES,
+    },
+    ContractError,
+};
+
+use super::{
+    gate::verify_participation,
+    pot::{
+        calculate_max_bid, calculate_min_bid, get_player_exposure, set_first_bidder_if_not_set,
+        update_player_allocation, update_pot_state,
+    },
+    validate::{
+        validate_existing_allocation, validate_player_exposure, validate_pot_limit_not_exceeded,
+    },
+};
+
+/// Winnings of a player opted in to auto-compounding, kept in the contract instead of being sent.
+pub struct CompoundingWinnings {
+    pub player: Addr,
+    pub amount: Uint128,
+    pub last_pots: Vec<u8>, // the pots the player allocated to in the ended round
+}
+
+/// Helper to add a player share to its compounding winnings, the same player can win in more than one pot.
+pub fn add_compounding_winnings(
+    storage: &dyn Storage,
+    compounding_winnings: &mut Vec<CompoundingWinnings>,
+    player: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    match compounding_winnings
+        .iter_mut()
+        .find(|winnings| winnings.player == player)
+    {
+        Some(winnings) => winnings.amount = winnings.amount.checked_add(amount)?,
+        None => compounding_winnings.push(CompoundingWinnings {
+            player: player.clone(),
+            amount,
+            last_pots: PLAYER_ALLOCATIONS
+                .load(storage, player.to_string())?
+                .into_iter()
+                .filter(|allocation| !allocation.amount.is_zero())
+                .map(|allocation| allocation.pot_id)
+                .collect(),
+        }),
+    }
+    Ok(())
+}
+
+/// Helper to allocate the compounding winnings to the pots of the next round, following each player strategy.
+/// Allocations failing the same checks as allocate_tokens are credited to the claimable balance instead.
+/// Returns the total compounded into the pots and the total credited to the claimable balances.
+pub fn process_auto_compound(
+    mut deps: DepsMut,
+    env: &Env,
+    compounding_winnings: Vec<CompoundingWinnings>,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let game_state = GAME_STATE.load(deps.storage)?;
+    let mut total_compounded = Uint128::zero();
+    let mut total_claimable = Uint128::zero();
+
+    for winnings in compounding_winnings {
+        let strategy = AUTO_COMPOUND.load(deps.storage, &winnings.player)?;
+        let pots = get_compound_pots(deps.storage, &strategy, &winnings.last_pots)?;
+
+        // Split the winnings evenly among the pots, the rounding dust going to the first one
+        let pot_amount = winnings
+            .amount
+            .checked_div(Uint128::from(pots.len() as u128))?;
+        let dust = winnings
+            .amount
+            .checked_sub(pot_amount * Uint128::from(pots.len() as u128))?;
+        for (index, pot_id) in pots.into_iter().enumerate() {
+            let amount = if index == 0 {
+                pot_amount.checked_add(dust)?
+            } else {
+                pot_amount
+            };
+            if amount.is_zero() {
+                continue;
+            }
+
+            if verify_participation(&mut deps, &winnings.player).is_ok()
+                && validate_compound_allocation(
+                    &deps.as_ref(),
+                    env,
+                    &winnings.player,
+                    pot_id,
+                    amount,
+                )
+                .is_ok()
+            {
+                update_player_allocation(deps.storage, &winnings.player, pot_id, amount, true)?;
+                update_pot_state(deps.storage, pot_id, amount, true)?;
+                set_first_bidder_if_not_set(
+                    deps.storage,
+                    pot_id,
+                    &winnings.player,
+                    game_state.start_time,
+                )?;
+                total_compounded = total_compounded.checked_add(amount)?;
+            } else {
+                add_to_claimable(deps.storage, &winnings.player, amount)?;
+                total_claimable = total_claimable.checked_add(amount)?;
+            }
+        }
+    }
+
+    Ok((total_compounded, total_claimable))
+}
+
+/// Helper to credit winnings failing the auto-compound checks to a player claimable balance.
+pub fn add_to_claimable(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let total = get_total_claimable(storage)?.checked_add(amount)?;
+    CLAIMABLE_TOTAL.save(storage, &total)?;
+    CLAIMABLE_BALANCES.update(storage, player, |current| -> Result<_, ContractError> {
+        Ok(current.unwrap_or_default().checked_add(amount)?)
+    })
+}
+
+/// Helper to take the whole claimable balance of a player, erroring when there is nothing to claim.
+pub fn take_claimable(storage: &mut dyn Storage, player: &Addr) -> Result<Uint128, ContractError> {
+    let amount = CLAIMABLE_BALANCES
+        .may_load(storage, player)?
+        .unwrap_or_default();
+    if amount.is_zero() {
+        return Err(ContractError::NoClaimableBalance {});
+    }
+    CLAIMABLE_BALANCES.remove(storage, player);
+    let total = get_total_claimable(storage)?.checked_sub(amount)?;
+    CLAIMABLE_TOTAL.save(storage, &total)?;
+    Ok(amount)
+}
+
+/// Helper to get the total of the claimable balances, which is reserved out of the next rounds pots.
+pub fn get_total_claimable(storage: &dyn Storage) -> Result<Uint128, ContractError> {
+    Ok(CLAIMABLE_TOTAL.may_load(storage)?.unwrap_or_default())
+}
+
+fn get_compound_pots(
+    storage: &dyn Storage,
+    strategy: &AutoCompoundStrategy,
+    last_pots: &[u8],
+) -> Result<Vec<u8>, ContractError> {
+    Ok(match strategy {
+        AutoCompoundStrategy::FixedPot { pot_id } => vec![*pot_id],
+        AutoCompoundStrategy::LastPots {} => last_pots.to_vec(),
+        AutoCompoundStrategy::LowestPot {} => {
+            let mut lowest_pot = POT_STATES.load(storage, 1)?;
+            for pot_id in 2..=5 {
+                let pot_state = POT_STATES.load(storage, pot_id)?;
+                if pot_state.amount < lowest_pot.amount {
+                    lowest_pot = pot_state;
+                }
+            }
+            vec![lowest_pot.pot_id]
+        }
+    })
+}
+
+fn validate_compound_allocation(
+    deps: &Deps,
+    env: &Env,
+    player: &Addr,
+    pot_id: u8,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+
+    validate_pot_limit_not_exceeded(deps.storage, pot_id, amount)?;
+    validate_existing_allocation(deps.storage, player, pot_id)?;
+
+    let min_bid = calculate_min_bid(deps, env, Some(player.to_string()))?;
+    let original_min_bid = calculate_min_bid(deps, env, None)?;
+    let max_bid = calculate_max_bid(deps, original_min_bid)?;
+    if amount < min_bid || amount > max_bid {
+        return Err(ContractError::BidOutOfRange {
+            min: min_bid,
+            max: max_bid,
+        });
+    }
+    validate_player_exposure(
+        &game_config,
+        get_player_exposure(deps.storage, player)?.checked_add(amount)?,
+    )?;
+
+    Ok(())
+}
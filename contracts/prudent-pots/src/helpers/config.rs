@@ -0,0 +1,336 @@
+use cosmwasm_std::{attr, Api, Attribute, Decimal, Env, QuerierWrapper, Storage, Uint128};
+
+use super::{
+    balance::get_total_balances, compound::get_total_claimable, table::reserve_table_denoms,
+    voting_power::validate_voting_power_sources,
+};
+use crate::{
+    error::invalid_config_field,
+    state::{
+        BidCurve, GameConfig, TimelockedConfig, GAME_CONFIG, JACKPOT, PENDING_CONFIG,
+        PLAYER_ALLOCATIONS, RAFFLE, REALLOCATION_FEE_POOL, REFUND_CLAIMS_TOTAL, SEASON,
+    },
+    ContractError,
+};
+
+/// Helper to validate the whole game config, shared by instantiate, update_config and migrate so the limits cannot diverge.
+pub fn validate_game_config(api: &dyn Api, config: &GameConfig) -> Result<(), ContractError> {
+    if config.fee > 10 {
+        return Err(invalid_config_field("fee", "must be at most 10"));
+    }
+    if config.fee_reallocation > 50 {
+        return Err(invalid_config_field(
+            "fee_reallocation",
+            "must be at most 50",
+        ));
+    }
+    api.addr_validate(config.fee_address.as_str())
+        .map_err(|err| invalid_config_field("fee_address", &err.to_string()))?;
+    if config.game_denom.is_empty() {
+        return Err(invalid_config_field("game_denom", "cannot be empty"));
+    }
+    for collection in &config.game_cw721_collections {
+        api.addr_validate(collection.addr.as_str())
+            .map_err(|err| invalid_config_field("game_cw721_collections", &err.to_string()))?;
+        if collection.discount_per_token > Decimal::one() {
+            return Err(invalid_config_field(
+                "game_cw721_collections",
+                "discount_per_token must be at most 1",
+            ));
+        }
+        if collection.trait_multipliers.iter().any(|m| m.weight == 0) {
+            return Err(invalid_config_field(
+                "game_cw721_collections",
+                "trait multiplier weights must be greater than 0",
+            ));
+        }
+        if collection
+            .holder_fee_reallocation
+            .is_some_and(|fee| fee > 50)
+        {
+            return Err(invalid_config_field(
+                "game_cw721_collections",
+                "holder_fee_reallocation must be at most 50",
+            ));
+        }
+    }
+    if config.game_duration == 0 {
+        return Err(invalid_config_field(
+            "game_duration",
+            "must be greater than 0",
+        ));
+    }
+    // The epoch divides the elapsed time when calculating the min bid
+    if config.game_duration_epoch == 0 {
+        return Err(invalid_config_field(
+            "game_duration_epoch",
+            "must be greater than 0",
+        ));
+    }
+    if config.game_extend > config.game_duration {
+        return Err(invalid_config_field(
+            "game_extend",
+            "must be at most game_duration",
+        ));
+    }
+    if config.min_pot_initial_allocation.is_zero() {
+        return Err(invalid_config_field(
+            "min_pot_initial_allocation",
+            "must be greater than 0",
+        ));
+    }
+    if config.decay_factor < Decimal::percent(1) || config.decay_factor > Decimal::percent(99) {
+        return Err(invalid_config_field(
+            "decay_factor",
+            "must be between 0.01 and 0.99",
+        ));
+    }
+    validate_voting_power_sources(api, &config.voting_power_sources)?;
+    if config.max_bid_multiplier < Decimal::one()
+        || config.max_bid_multiplier > Decimal::from_ratio(100u128, 1u128)
+    {
+        return Err(invalid_config_field(
+            "max_bid_multiplier",
+            "must be between 1 and 100",
+        ));
+    }
+    match &config.bid_curve {
+        BidCurve::Linear {} => {}
+        BidCurve::Exponential { growth } => {
+            if growth.is_zero() || *growth > Decimal::one() {
+                return Err(invalid_config_field(
+                    "bid_curve",
+                    "growth must be between 0 and 1",
+                ));
+            }
+            // The min bid has to stay representable until the last epoch of a round
+            let max_epochs = config.game_duration.div_ceil(config.game_duration_epoch);
+            let max_min_bid = u32::try_from(max_epochs)
+                .ok()
+                .and_then(|max_epochs| {
+                    Decimal::one()
+                        .checked_add(*growth)
+                        .ok()?
+                        .checked_pow(max_epochs)
+                        .ok()
+                })
+                .and_then(|max_multiplier| {
+                    config
+                        .min_pot_initial_allocation
+                        .checked_mul_floor(max_multiplier)
+                        .ok()
+                });
+            if max_min_bid.is_none() {
+                return Err(invalid_config_field(
+                    "bid_curve",
+                    "growth overflows within the round epochs",
+                ));
+            }
+        }
+        BidCurve::Stepwise { multipliers } => {
+            if multipliers.is_empty() || multipliers.iter().any(|m| *m < Decimal::one()) {
+                return Err(invalid_config_field(
+                    "bid_curve",
+                    "multipliers cannot be empty or lower than 1",
+                ));
+            }
+        }
+        BidCurve::PotValue { ratio } => {
+            if ratio.is_zero() || *ratio > Decimal::one() {
+                return Err(invalid_config_field(
+                    "bid_curve",
+                    "ratio must be between 0 and 1",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the names of the fields set in a game config, destructured without a rest pattern so a new field
+/// fails to compile until it is listed here too.
+macro_rules! game_config_diff {
+    ($config:expr, $other:expr, $($field:ident),+ $(,)?) => {{
+        let GameConfig { $($field),+ } = $other;
+        let mut changed_fields = vec![];
+        $(
+            if $config.$field != *$field {
+                changed_fields.push(stringify!($field).to_string());
+            }
+        )+
+        changed_fields
+    }};
+}
+
+/// Helper to list the fields differing between two game configs, i.e. the diff of a pending config.
+pub fn changed_config_fields(config: &GameConfig, other: &GameConfig) -> Vec<String> {
+    if config == other {
+        return vec![];
+    }
+
+    game_config_diff!(
+        config,
+        other,
+        fee,
+        fee_reallocation,
+        fee_address,
+        game_denom,
+        game_cw721_collections,
+        game_duration,
+        game_duration_epoch,
+        game_extend,
+        game_end_threshold,
+        min_pot_initial_allocation,
+        decay_factor,
+        reallocations_limit,
+        nft_claim_timeout,
+        raffle_expiry_rounds,
+        min_bid_floor,
+        voting_power_sources,
+        bid_curve,
+        max_bid_multiplier,
+        max_player_exposure,
+        timelock_delay,
+    )
+}
+
+/// Helper to tell if the game denom still backs funds kept across rounds: the player balances, the claimables,
+/// the season and jackpot pools, the refund claims, the reallocation fee pool and the next round raffle prize.
+/// None of them is stored with its denom, so the game denom cannot switch until they are all empty.
+pub fn is_game_denom_in_use(
+    storage: &dyn Storage,
+    raffle_denom_amount: Uint128,
+) -> Result<bool, ContractError> {
+    let season_pool = SEASON
+        .may_load(storage)?
+        .map(|season| season.pool)
+        .unwrap_or_default();
+    let jackpot_pool = JACKPOT
+        .may_load(storage)?
+        .map(|jackpot| jackpot.pool)
+        .unwrap_or_default();
+    let refund_claims_total = REFUND_CLAIMS_TOTAL.may_load(storage)?.unwrap_or_default();
+    let reallocation_fee_pool = REALLOCATION_FEE_POOL.may_load(storage)?.unwrap_or_default();
+
+    Ok(!get_total_balances(storage)?.is_zero()
+        || !get_total_claimable(storage)?.is_zero()
+        || !season_pool.is_zero()
+        || !jackpot_pool.is_zero()
+        || !refund_claims_total.is_zero()
+        || !reallocation_fee_pool.is_zero()
+        || !raffle_denom_amount.is_zero())
+}
+
+/// Helper to validate a game_denom change, the contract must already hold funds in the new denom
+/// as the next round pots are seeded from its balance. The switch itself waits for the next round.
+pub fn validate_game_denom_change(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    game_config: &GameConfig,
+    pending_config: &GameConfig,
+) -> Result<(), ContractError> {
+    if pending_config.game_denom == game_config.game_denom {
+        return Ok(());
+    }
+
+    let raffle_denom_amount = RAFFLE
+        .may_load(storage)?
+        .map(|raffle| raffle.denom_amount)
+        .unwrap_or_default();
+    if is_game_denom_in_use(storage, raffle_denom_amount)? {
+        return Err(ContractError::GameDenomInUse {
+            denom: game_config.game_denom.clone(),
+        });
+    }
+
+    let balance = querier.query_balance(&env.contract.address, &pending_config.game_denom)?;
+    if balance.amount.is_zero() {
+        return Err(ContractError::GameDenomNotFunded {
+            denom: pending_config.game_denom.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Helper to load the current game config, along with the pending one changes are staged on top of.
+pub fn load_game_configs(storage: &dyn Storage) -> Result<(GameConfig, GameConfig), ContractError> {
+    let game_config = GAME_CONFIG.load(storage)?;
+    let pending_config = PENDING_CONFIG
+        .may_load(storage)?
+        .unwrap_or_else(|| game_config.clone());
+
+    Ok((game_config, pending_config))
+}
+
+/// Helper to apply the timelocked changes of a config proposal. The fee address and the timelock delay
+/// take effect immediately, while the fields affecting the bid ranges and the payouts wait for the next round.
+pub fn apply_timelocked_config(
+    mut game_config: GameConfig,
+    mut pending_config: GameConfig,
+    changes: &TimelockedConfig,
+) -> Result<(GameConfig, GameConfig), ContractError> {
+    if changes.fee.is_none()
+        && changes.fee_address.is_none()
+        && changes.game_denom.is_none()
+        && changes.reallocations_limit.is_none()
+        && changes.timelock_delay.is_none()
+    {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    if let Some(fee_address) = &changes.fee_address {
+        game_config.fee_address = fee_address.clone();
+        pending_config.fee_address = fee_address.clone();
+    }
+    if let Some(timelock_delay) = changes.timelock_delay {
+        game_config.timelock_delay = timelock_delay;
+        pending_config.timelock_delay = timelock_delay;
+    }
+    if let Some(fee) = changes.fee {
+        pending_config.fee = fee;
+    }
+    if let Some(game_denom) = &changes.game_denom {
+        pending_config.game_denom = game_denom.clone();
+    }
+    if let Some(reallocations_limit) = changes.reallocations_limit {
+        pending_config.reallocations_limit = reallocations_limit;
+    }
+
+    Ok((game_config, pending_config))
+}
+
+/// Helper to validate and save the game config and the pending one, returning the response attributes.
+/// Without any player committed to the current round there is no one to protect, so everything applies immediately.
+pub fn save_game_configs(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    mut game_config: GameConfig,
+    pending_config: GameConfig,
+) -> Result<Vec<Attribute>, ContractError> {
+    // Validate the resulting configs as a whole, so fields depending on each other are checked together
+    validate_game_config(api, &game_config)?;
+    validate_game_config(api, &pending_config)?;
+
+    if PLAYER_ALLOCATIONS.is_empty(storage) {
+        // The current pots were seeded in the game denom, so a new one waits for the next round to reseed them
+        game_config = GameConfig {
+            game_denom: game_config.game_denom,
+            ..pending_config.clone()
+        };
+    }
+    GAME_CONFIG.save(storage, &game_config)?;
+
+    let mut attributes = vec![attr("config", format!("{:?}", game_config))];
+    if pending_config != game_config {
+        PENDING_CONFIG.save(storage, &pending_config)?;
+        attributes.push(attr("pending_config", format!("{:?}", pending_config)));
+    } else {
+        PENDING_CONFIG.remove(storage);
+    }
+    reserve_table_denoms(storage)?;
+
+    Ok(attributes)
+}
@@ -1,18 +1,31 @@
 use cosmwasm_std::{
-    attr, coins, to_json_binary, Attribute, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    Storage, SubMsg, Uint128, WasmMsg,
+    attr, coins, to_json_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Storage, SubMsg, Uint128, WasmMsg,
 };
 
 use crate::{
     msg::ReplyMsg,
     state::{
-        GameConfig, GameState, Raffle, TokenAllocation, FIRST_BIDDER, GAME_CONFIG, GAME_STATE,
-        PLAYER_ALLOCATIONS, PLAYER_REALLOCATIONS, POT_STATES, RAFFLE, REALLOCATION_FEE_POOL,
+        GameState, JackpotSource, Raffle, SeasonPoolSource, TokenAllocation, UnclaimedNft,
+        AUTO_COMPOUND, BALANCE_PAYOUTS, FIRST_BIDDER, GAME_CONFIG, GAME_STATE, JACKPOT,
+        PARTICIPATION_VERIFIED, PENDING_CONFIG, PLAYER_ALLOCATIONS, PLAYER_REALLOCATIONS,
+        PLAYER_REALLOCATION_FEES, PLAYER_VOTES, POT_STATES, RAFFLE, REALLOCATION_FEE_POOL,
+        REFUND_CLAIMS_TOTAL, SEASON, VOTE_OPTIONS, VOTE_TALLIES,
     },
     ContractError,
 };
 
-use super::validate::{validate_funds, validate_pot_initial_amount};
+use super::{
+    balance::get_total_balances,
+    compound::{add_compounding_winnings, get_total_claimable, CompoundingWinnings},
+    config::{is_game_denom_in_use, validate_game_config},
+    gate::is_participation_verified,
+    reply::encode_reply_id,
+    season::{add_to_season_pool, get_active_season},
+    table::reserve_table_denoms,
+    validate::{validate_funds, validate_pot_initial_amount},
+    vote::{apply_vote_option, tally_votes},
+};
 
 pub struct ProcessRaffleWinnerResponse {
     pub msgs: Vec<CosmosMsg>,
@@ -21,6 +34,18 @@ pub struct ProcessRaffleWinnerResponse {
     pub new_raffle_denom_amount: Uint128,
     pub new_raffle_cw721_id: Option<String>,
     pub new_raffle_cw721_addr: Option<String>,
+    pub outgoing_nft: Option<UnclaimedNft>, // the NFT sent to the raffle winner, escrowed if the transfer fails
+    pub raffle_winner: Option<String>,
+}
+
+pub struct DistributionResponse {
+    pub msgs: Vec<CosmosMsg>,
+    pub treasury_outgoing_tokens: Uint128,
+    pub season_pool_tokens: Uint128, // the slice of the fee kept in the contract for the season pool
+    pub jackpot_pool_tokens: Uint128, // the slice of the fees kept in the contract for the jackpot pool
+    pub player_winnings: Vec<(String, Uint128)>,
+    pub compounding_winnings: Vec<CompoundingWinnings>, // kept in the contract for the next round pots
+    pub balance_winnings: Vec<(String, Uint128)>, // kept in the contract, credited to the player balances
 }
 
 // Helper to prepare for the next game
@@ -33,9 +58,71 @@ pub fn prepare_next_game(
     raffle_denom_amount: Option<Uint128>,
     next_game_start: Option<u64>, // this is intended as a unix timestamp in seconds that should replace the current timestamp, but should also be higher than the current timestamp
 ) -> Result<(u64, u64, u32), ContractError> {
-    let config = GAME_CONFIG.load(deps.storage)?;
+    let game_denom = GAME_CONFIG
+        .may_load(deps.storage)?
+        .map(|config| config.game_denom);
+    // Pending config changes take effect from the next round, so they never affect the committed players
+    let mut config = match PENDING_CONFIG.may_load(deps.storage)? {
+        Some(pending_config) => {
+            PENDING_CONFIG.remove(deps.storage);
+            pending_config
+        }
+        None => GAME_CONFIG.load(deps.storage)?,
+    };
     let game_state = GAME_STATE.may_load(deps.storage)?.unwrap_or_default(); // may load due instantiate invoke
 
+    // Apply the next round settings voted by the players, archiving the tally with the ended round
+    if VOTE_OPTIONS
+        .may_load(deps.storage)?
+        .is_some_and(|options| !options.is_empty())
+    {
+        let mut vote_tally = tally_votes(deps.storage, game_state.round_count)?;
+        if let Some(winning_option) = vote_tally.winning_option {
+            let voted_config =
+                apply_vote_option(config.clone(), &vote_tally.options[winning_option as usize]);
+            // An invalid voted config must not block the game end, the vote is archived as not applied
+            if validate_game_config(deps.api, &voted_config).is_ok() {
+                config = voted_config;
+                vote_tally.applied = true;
+            }
+        }
+        VOTE_TALLIES.save(deps.storage, game_state.round_count, &vote_tally)?;
+    }
+
+    // A new game denom switches once nothing is reserved in the old one and its balance can seed the pots, otherwise it stays pending
+    let mut switch_denom = false;
+    if let Some(game_denom) = game_denom.filter(|game_denom| *game_denom != config.game_denom) {
+        let new_denom_balance = deps
+            .querier
+            .query_balance(&env.contract.address, &config.game_denom)?
+            .amount;
+        if is_game_denom_in_use(deps.storage, raffle_denom_amount.unwrap_or_default())?
+            || validate_pot_initial_amount(&config.min_pot_initial_allocation, &new_denom_balance)
+                .is_err()
+        {
+            PENDING_CONFIG.save(deps.storage, &config)?;
+            config.game_denom = game_denom;
+        } else {
+            switch_denom = true;
+        }
+    }
+    // The season and jackpot pools are empty on a switch, so they are fed and paid out in the new denom from now on
+    if switch_denom {
+        if let Some(mut season) = SEASON
+            .may_load(deps.storage)?
+            .filter(|season| !season.finished)
+        {
+            season.denom = config.game_denom.clone();
+            SEASON.save(deps.storage, &season)?;
+        }
+        if let Some(mut jackpot) = JACKPOT.may_load(deps.storage)? {
+            jackpot.denom = config.game_denom.clone();
+            JACKPOT.save(deps.storage, &jackpot)?;
+        }
+    }
+    GAME_CONFIG.save(deps.storage, &config)?;
+    reserve_table_denoms(deps.storage)?;
+
     // TODO: finish the impl
     // Start the next game 1 second in the future
     let game_duration = config.game_duration;
@@ -65,15 +152,52 @@ pub fn prepare_next_game(
     // Reset player allocations, player reallocations and first bidder states for the next game
     PLAYER_ALLOCATIONS.clear(deps.storage);
     PLAYER_REALLOCATIONS.clear(deps.storage);
+    PLAYER_REALLOCATION_FEES.clear(deps.storage);
+    PLAYER_VOTES.clear(deps.storage);
+    PARTICIPATION_VERIFIED.clear(deps.storage);
     FIRST_BIDDER.clear(deps.storage);
 
     // Start initial tokens allocation workflow by querying the contract balance
-    let net_contract_balance = deps
+    let season_pool = SEASON
+        .may_load(deps.storage)?
+        .map(|season| season.pool)
+        .unwrap_or_default();
+    let jackpot_pool = JACKPOT
+        .may_load(deps.storage)?
+        .map(|jackpot| jackpot.pool)
+        .unwrap_or_default();
+    let total_balances = get_total_balances(deps.storage)?;
+    let total_claimable = get_total_claimable(deps.storage)?;
+    let refund_claims_total = REFUND_CLAIMS_TOTAL
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    // The outgoing tokens of the ended round are in the old denom when it switched
+    let total_outgoing_tokens = if switch_denom {
+        Uint128::zero()
+    } else {
+        total_outgoing_tokens
+    };
+    let mut net_contract_balance = deps
         .querier
         .query_balance(&env.contract.address, &config.game_denom)?
         .amount
         .checked_sub(total_outgoing_tokens)? // Subtract outgoing tokens from the total tokens
-        .checked_sub(raffle_denom_amount.unwrap_or_default())?; // Subtract the new amount sent in this tx as info.funds reserved for next round denom raffle prize
+        .checked_sub(raffle_denom_amount.unwrap_or_default())? // Subtract the new amount sent in this tx as info.funds reserved for next round denom raffle prize
+        .checked_sub(season_pool)? // Subtract the season pool, reserved until paid out
+        .checked_sub(jackpot_pool)? // Subtract the jackpot pool, reserved until paid out
+        .checked_sub(total_balances)? // Subtract the player balances, reserved until withdrawn
+        .checked_sub(total_claimable)? // Subtract the claimable balances, reserved until claimed
+        .checked_sub(refund_claims_total)?; // Subtract the refunds of a cancelled round, reserved until claimed
+
+    // A slice of the rollover feeds the season pool, when the season is funded by the rollover
+    if let Some(season) = get_active_season(deps.storage, game_state.round_count)? {
+        if season.config.pool_source == (SeasonPoolSource::Rollover {}) {
+            let season_pool_tokens =
+                net_contract_balance.multiply_ratio(season.config.pool_share, 100u128);
+            net_contract_balance = net_contract_balance.checked_sub(season_pool_tokens)?;
+            add_to_season_pool(deps.storage, season_pool_tokens)?;
+        }
+    }
 
     // Calculate the initial tokens for each pot after subtracting outgoing tokens and next round game_denom raffle prize amount.
     let initial_tokens_per_pot = net_contract_balance.checked_div(Uint128::from(5u128))?; // Divide the result by 5 to get tokens per pot
@@ -112,9 +236,10 @@ pub fn prepare_next_game(
 
 /// Compute the raffle winner based on the total tokens allocated among the winning pots.
 pub fn get_raffle_winner(
-    storage: &dyn Storage,
+    deps: &Deps,
     winning_pots: &[u8],
 ) -> Result<Option<String>, ContractError> {
+    let storage = deps.storage;
     let mut max_total = Uint128::zero();
     let mut winner: Option<String> = None;
 
@@ -126,6 +251,11 @@ pub fn get_raffle_winner(
     for item in all_allocations {
         let (addr, player_allocations) = item?;
 
+        // Players blocked or no longer allowed since they allocated cannot win the raffle
+        if !is_participation_verified(storage, &Addr::unchecked(&addr)) {
+            continue;
+        }
+
         let total_in_winning_pots: Uint128 = player_allocations
             .iter()
             .filter(|allocation| winning_pots.contains(&allocation.pot_id))
@@ -181,8 +311,6 @@ pub fn process_raffle_winner(
     env: &Env,
     funds: &[Coin],
     winning_pots: &[u8],
-    mut new_raffle_cw721_id: Option<String>,
-    mut new_raffle_cw721_addr: Option<String>,
 ) -> Result<ProcessRaffleWinnerResponse, ContractError> {
     let game_config = GAME_CONFIG.load(deps.storage)?;
     let raffle = RAFFLE.load(deps.storage)?;
@@ -193,14 +321,18 @@ pub fn process_raffle_winner(
     let mut msgs = vec![];
     let mut submsgs = vec![];
     let mut raffle_response_attributes = vec![];
+    let mut outgoing_nft = None;
+    // The next raffle NFT is sent to the contract via ReceiveNft, only an unwon one is carried over
+    let mut new_raffle_cw721_id = None;
+    let mut new_raffle_cw721_addr = None;
 
     // this is common for yes_raffle and no_raffle scenarios
     let mut new_raffle_denom_amount =
         validate_funds(funds, &game_config.game_denom).unwrap_or_default();
 
-    let raffle_winner = get_raffle_winner(deps.storage, winning_pots)?;
+    let raffle_winner = get_raffle_winner(deps, winning_pots)?;
 
-    match raffle_winner {
+    match raffle_winner.clone() {
         Some(recipient) => {
             if let Some(token_id) = &raffle.cw721_token_id {
                 let cw721_addr = raffle.cw721_addr.unwrap();
@@ -213,9 +345,20 @@ pub fn process_raffle_winner(
                         })?,
                         funds: vec![],
                     },
-                    ReplyMsg::TransferNft as u64,
+                    encode_reply_id(
+                        deps.storage,
+                        ReplyMsg::TransferNftToWinner,
+                        GAME_STATE.load(deps.storage)?.round_count,
+                    )?,
                 );
                 submsgs.push(transfer_nft_msg);
+                outgoing_nft = Some(UnclaimedNft {
+                    cw721_addr: cw721_addr.to_string(),
+                    token_id: token_id.to_string(),
+                    recipient: deps.api.addr_validate(&recipient)?,
+                    round_count: GAME_STATE.load(deps.storage)?.round_count,
+                    escrow_time: env.block.time.seconds(),
+                });
                 // Append attributes
                 raffle_response_attributes.extend(vec![
                     attr("raffle_outgoing_nft_addr", cw721_addr),
@@ -251,48 +394,17 @@ pub fn process_raffle_winner(
             )]);
         }
         None => {
-            // Scenarios are:
-            // - Admin sends new NFT, but old is unsent (ok)
-            // - Admin sends new NFT, but old is sent (ok)
-            // - Admin do not send new NFT, old is sent (ok)
-            // - Admin do not send new NFT, but old is unsent (ok)
-            // If there was a not-won Raffle NFT, and admin is trying to send a new one, throw error.
-            if raffle.cw721_token_id.is_some() && new_raffle_cw721_id.is_some() {
-                // we dont want any new NFT from admin if the previous one is unwon
-                return Err(ContractError::PreviousRaffleNftIsUnwon {});
-            }
-            // if there is still the previous one, override the value
-
-            // if there is still the previous one
+            // if there is still the previous one, carry it over to the next raffle
             if raffle.cw721_token_id.is_some() {
                 new_raffle_cw721_id = raffle.cw721_token_id;
                 new_raffle_cw721_addr = raffle.cw721_addr;
             }
-            // else we set either the new one already passed from above, or None
 
             // here for denom prize we increment the old value (possibly 0 or positive) with the new info.funds sent
             new_raffle_denom_amount = new_raffle_denom_amount.checked_add(raffle.denom_amount)?;
         }
     }
 
-    // Send the new NFTs for the next raffle prize if any to process
-    if let (Some(cw721_id), Some(cw721_addr)) =
-        (new_raffle_cw721_id.clone(), new_raffle_cw721_addr.clone())
-    {
-        let transfer_nft_msg = SubMsg::reply_always(
-            WasmMsg::Execute {
-                contract_addr: cw721_addr,
-                msg: to_json_binary(&cw721::Cw721ExecuteMsg::TransferNft {
-                    recipient: env.contract.address.to_string(),
-                    token_id: cw721_id,
-                })?,
-                funds: vec![],
-            },
-            ReplyMsg::TransferNft as u64,
-        );
-        submsgs.push(transfer_nft_msg);
-    }
-
     Ok(ProcessRaffleWinnerResponse {
         msgs,
         submsgs,
@@ -300,6 +412,8 @@ pub fn process_raffle_winner(
         new_raffle_denom_amount,
         new_raffle_cw721_id,
         new_raffle_cw721_addr,
+        outgoing_nft,
+        raffle_winner,
     })
 }
 
@@ -326,13 +440,17 @@ pub fn get_distribution_send_msgs(
     deps: &Deps,
     winning_pots: &[u8],
     total_losing_tokens: Uint128,
-) -> Result<(Vec<CosmosMsg>, Uint128), ContractError> {
+) -> Result<DistributionResponse, ContractError> {
     let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
     let total_distribution_amount = total_losing_tokens.multiply_ratio(1u128, 2u128);
 
     let mut pot_contributions: Vec<Uint128> = vec![Uint128::zero(); 5]; // Assumes 5 pots
     let mut total_winning_tokens = Uint128::zero();
     let mut messages: Vec<CosmosMsg> = Vec::new();
+    let mut player_winnings: Vec<(String, Uint128)> = Vec::new();
+    let mut compounding_winnings: Vec<CompoundingWinnings> = Vec::new();
+    let mut balance_winnings: Vec<(String, Uint128)> = Vec::new();
     let mut total_fee = Uint128::zero();
 
     // Calculate total token amounts for each winning pot and store them
@@ -360,15 +478,63 @@ pub fn get_distribution_send_msgs(
 
         distribute_tokens_to_players(
             deps.storage,
-            &game_config,
             pot_id,
             net_distribution_amount,
-            &mut messages,
+            &mut player_winnings,
         )?;
     }
 
+    // Send the player shares, unless the players opted in to auto-compounding or to balance payouts
+    for (player, player_share) in &player_winnings {
+        let player_addr = Addr::unchecked(player);
+        if AUTO_COMPOUND.has(deps.storage, &player_addr) {
+            add_compounding_winnings(
+                deps.storage,
+                &mut compounding_winnings,
+                &player_addr,
+                *player_share,
+            )?;
+        } else if BALANCE_PAYOUTS.has(deps.storage, &player_addr) {
+            balance_winnings.push((player.to_string(), *player_share));
+        } else {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: player.to_string(),
+                amount: vec![Coin {
+                    denom: game_config.game_denom.clone(),
+                    amount: *player_share,
+                }],
+            }));
+        }
+    }
+
+    // A slice of the total fee feeds the season pool, when the season is funded by the fee
+    let season_pool_tokens = match get_active_season(deps.storage, game_state.round_count)? {
+        Some(season) if season.config.pool_source == SeasonPoolSource::Fee {} => {
+            total_fee.multiply_ratio(season.config.pool_share, 100u128)
+        }
+        _ => Uint128::zero(),
+    };
+    let mut treasury_fee = total_fee.checked_sub(season_pool_tokens)?;
+
+    // A slice of either the reallocation fee pool or the remaining fee feeds the jackpot pool
+    let mut reallocation_fee_pool = REALLOCATION_FEE_POOL.load(deps.storage)?;
+    let jackpot_pool_tokens = match JACKPOT.may_load(deps.storage)? {
+        Some(jackpot) => match jackpot.config.source {
+            JackpotSource::ReallocationFee {} => {
+                let tokens = reallocation_fee_pool.multiply_ratio(jackpot.config.share, 100u128);
+                reallocation_fee_pool = reallocation_fee_pool.checked_sub(tokens)?;
+                tokens
+            }
+            JackpotSource::WinningFee {} => {
+                let tokens = treasury_fee.multiply_ratio(jackpot.config.share, 100u128);
+                treasury_fee = treasury_fee.checked_sub(tokens)?;
+                tokens
+            }
+        },
+        None => Uint128::zero(),
+    };
+
     // Send reallocation_fee_pool amount to treasury.
-    let reallocation_fee_pool = REALLOCATION_FEE_POOL.load(deps.storage)?;
     if !reallocation_fee_pool.is_zero() {
         messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: game_config.fee_address.to_string(),
@@ -377,20 +543,25 @@ pub fn get_distribution_send_msgs(
     }
 
     // Deduct the total fee and add to messages
-    if !total_fee.is_zero() {
+    if !treasury_fee.is_zero() {
         messages.push(CosmosMsg::Bank(BankMsg::Send {
             to_address: game_config.fee_address.to_string(),
             amount: vec![Coin {
                 denom: game_config.game_denom,
-                amount: total_fee,
+                amount: treasury_fee,
             }],
         }));
     }
 
-    Ok((
-        messages,
-        total_fee.checked_add(reallocation_fee_pool)?, // treasury_outgoing_tokens
-    ))
+    Ok(DistributionResponse {
+        msgs: messages,
+        treasury_outgoing_tokens: treasury_fee.checked_add(reallocation_fee_pool)?,
+        season_pool_tokens,
+        jackpot_pool_tokens,
+        player_winnings,
+        compounding_winnings,
+        balance_winnings,
+    })
 }
 
 // Helper to calculate the total tokens in losing pots and winning pots without allocations
@@ -434,10 +605,9 @@ fn has_player_allocations(storage: &dyn Storage, pot_id: u8) -> Result<bool, Con
 
 fn distribute_tokens_to_players(
     storage: &dyn Storage,
-    config: &GameConfig,
     pot_id: u8,
     net_distribution_amount: Uint128,
-    messages: &mut Vec<CosmosMsg>,
+    player_winnings: &mut Vec<(String, Uint128)>,
 ) -> Result<(), ContractError> {
     // Retrieve all player allocations from storage. This pulls the entire list of allocations, filtering out any errors.
     let player_allocations: Vec<_> = PLAYER_ALLOCATIONS
@@ -466,14 +636,8 @@ fn distribute_tokens_to_players(
                 // Calculate the share for this player based on their contribution relative to the total contributions.
                 let player_share = net_distribution_amount
                     .multiply_ratio(allocation.amount, total_player_contributions);
-                // Create a bank message to send the player's share of tokens and push it to the messages vector.
-                messages.push(CosmosMsg::Bank(BankMsg::Send {
-                    to_address: addr.to_string(),
-                    amount: vec![Coin {
-                        denom: config.game_denom.clone(),
-                        amount: player_share,
-                    }],
-                }));
+                // Push the player's share, sent or kept in the contract by the caller.
+                player_winnings.push((addr.to_string(), player_share));
             }
         }
     }
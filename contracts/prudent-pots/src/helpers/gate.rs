@@ -0,0 +1,69 @@
+use cosmwasm_std::{Addr, Api, Deps, DepsMut, Empty, Storage};
+use cw_storage_plus::Map;
+
+use crate::{
+    state::{ParticipationGate, ALLOWLIST, BLOCKLIST, PARTICIPATION_GATE, PARTICIPATION_VERIFIED},
+    ContractError,
+};
+
+use super::voting_power::is_cw4_member;
+
+/// Helper to validate an address can play on the table, the blocklist being checked before the gate.
+/// Refunds are never gated, so blocked players can always get their funds back from a cancelled round.
+pub fn validate_participation(deps: &Deps, player: &Addr) -> Result<(), ContractError> {
+    if BLOCKLIST.has(deps.storage, player) {
+        return Err(ContractError::Blocked {});
+    }
+
+    let allowed = match PARTICIPATION_GATE
+        .may_load(deps.storage)?
+        .unwrap_or(ParticipationGate::Open {})
+    {
+        ParticipationGate::Open {} => true,
+        ParticipationGate::Allowlist {} => ALLOWLIST.has(deps.storage, player),
+        ParticipationGate::Cw4Group { addr } => is_cw4_member(&deps.querier, &addr, player)?,
+    };
+    if !allowed {
+        return Err(ContractError::NotAllowed {});
+    }
+
+    Ok(())
+}
+
+/// Helper to validate an address can play when it allocates, storing the result for the rest of the round.
+/// The gate is checked once per round, so the game end never queries the cw4 group again.
+pub fn verify_participation(deps: &mut DepsMut, player: &Addr) -> Result<(), ContractError> {
+    if PARTICIPATION_VERIFIED.has(deps.storage, player) {
+        if BLOCKLIST.has(deps.storage, player) {
+            return Err(ContractError::Blocked {});
+        }
+        return Ok(());
+    }
+
+    validate_participation(&deps.as_ref(), player)?;
+    PARTICIPATION_VERIFIED.save(deps.storage, player, &Empty {})?;
+    Ok(())
+}
+
+/// Helper to tell if a player verified when allocating is still eligible, i.e. to win the raffle.
+/// Players blocked or removed from the allowlist since they allocated are not.
+pub fn is_participation_verified(storage: &dyn Storage, player: &Addr) -> bool {
+    PARTICIPATION_VERIFIED.has(storage, player) && !BLOCKLIST.has(storage, player)
+}
+
+/// Helper to add and remove addresses from the allowlist or the blocklist.
+pub fn update_address_list(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    list: &Map<&Addr, Empty>,
+    add: &[String],
+    remove: &[String],
+) -> Result<(), ContractError> {
+    for address in add {
+        list.save(storage, &api.addr_validate(address)?, &Empty {})?;
+    }
+    for address in remove {
+        list.remove(storage, &api.addr_validate(address)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{coins, BankMsg, CosmosMsg, Order, Storage, Uint128};
+
+use crate::{
+    error::invalid_config_field,
+    state::{
+        JackpotConfig, JackpotPayout, JackpotTrigger, JACKPOT, PLAYER_ALLOCATIONS, POT_STATES,
+    },
+    ContractError,
+};
+
+/// Helper to validate a jackpot config before it is set.
+pub fn validate_jackpot_config(config: &JackpotConfig) -> Result<(), ContractError> {
+    if config.share == 0 || config.share > 100 {
+        return Err(invalid_config_field("share", "must be between 1 and 100"));
+    }
+    if config.triggers.is_empty() {
+        return Err(invalid_config_field(
+            "triggers",
+            "must contain at least one trigger",
+        ));
+    }
+    Ok(())
+}
+
+/// Helper to find the first configured trigger met by the current board, if any.
+pub fn get_jackpot_trigger(
+    storage: &dyn Storage,
+    winning_pots: &[u8],
+) -> Result<Option<JackpotTrigger>, ContractError> {
+    let jackpot = match JACKPOT.may_load(storage)? {
+        Some(jackpot) => jackpot,
+        None => return Ok(None),
+    };
+
+    let mut token_counts = (1..=5)
+        .map(|pot_id| Ok(POT_STATES.load(storage, pot_id)?.amount))
+        .collect::<Result<Vec<Uint128>, ContractError>>()?;
+    token_counts.sort_unstable();
+    let total_tokens = token_counts
+        .iter()
+        .try_fold(Uint128::zero(), |total, count| total.checked_add(*count))?;
+    let median_is_average = token_counts[2].checked_mul(Uint128::from(5u128))? == total_tokens;
+
+    Ok(jackpot
+        .config
+        .triggers
+        .into_iter()
+        .find(|trigger| match trigger {
+            JackpotTrigger::AllPotsWinning {} => winning_pots.len() == 5,
+            JackpotTrigger::MedianIsAverage {} => median_is_average,
+        }))
+}
+
+/// Helper to add tokens to the jackpot pool, reserved out of the next rounds pots until paid out.
+pub fn add_to_jackpot_pool(
+    storage: &mut dyn Storage,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let mut jackpot = JACKPOT.load(storage)?;
+    jackpot.pool = jackpot.pool.checked_add(amount)?;
+    JACKPOT.save(storage, &jackpot)?;
+    Ok(())
+}
+
+/// Helper to split the jackpot pool among the players of the winning pots, by their allocations to those pots.
+/// Without any player in the winning pots the jackpot keeps growing.
+pub fn process_jackpot_payout(
+    storage: &mut dyn Storage,
+    round_count: u64,
+    winning_pots: &[u8],
+    trigger: JackpotTrigger,
+) -> Result<(Vec<CosmosMsg>, Uint128), ContractError> {
+    let mut jackpot = JACKPOT.load(storage)?;
+
+    let mut player_weights: BTreeMap<String, Uint128> = BTreeMap::new();
+    for item in PLAYER_ALLOCATIONS.range(storage, None, None, Order::Ascending) {
+        let (player, allocations) = item?;
+        for allocation in allocations {
+            if winning_pots.contains(&allocation.pot_id) && !allocation.amount.is_zero() {
+                let weight = player_weights.entry(player.clone()).or_default();
+                *weight = weight.checked_add(allocation.amount)?;
+            }
+        }
+    }
+    let total_weight: Uint128 = player_weights.values().sum();
+    if jackpot.pool.is_zero() || total_weight.is_zero() {
+        return Ok((vec![], Uint128::zero()));
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut total_paid = Uint128::zero();
+    for (player, weight) in &player_weights {
+        let amount = jackpot.pool.multiply_ratio(*weight, total_weight);
+        if amount.is_zero() {
+            continue;
+        }
+        total_paid = total_paid.checked_add(amount)?;
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: player.to_string(),
+            amount: coins(amount.u128(), &jackpot.denom),
+        }));
+    }
+
+    // The rounding dust stays in the pool for the next jackpot
+    jackpot.pool = jackpot.pool.checked_sub(total_paid)?;
+    jackpot.last_payout = Some(JackpotPayout {
+        round_count,
+        trigger,
+        amount: total_paid,
+        winners: player_weights.len() as u32,
+    });
+    JACKPOT.save(storage, &jackpot)?;
+
+    Ok((msgs, total_paid))
+}
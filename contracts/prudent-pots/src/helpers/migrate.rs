@@ -0,0 +1,178 @@
+use cosmwasm_std::{Addr, Decimal, Env, QuerierWrapper, Storage, Uint128};
+
+use crate::{
+    error::invalid_config_field,
+    msg::MigrateMsg,
+    state::{
+        default_max_bid_multiplier, BidCurve, Cw721Collection, GameConfig, GameConfigV2,
+        GameConfigV2_1, Ownership, GAME_CONFIG, GAME_CONFIG_V2, GAME_CONFIG_V2_1, OLD_GAME_CONFIG,
+        OPERATORS, OWNERSHIP,
+    },
+    ContractError,
+};
+
+fn missing_field(field: &str) -> ContractError {
+    ContractError::MissingMigrateField {
+        field: field.to_string(),
+    }
+}
+
+/// Migrates the v1 game config to the v2.0.0 layout, adding the epochs and converting the decay factor
+/// from the percent of the min bid kept to the percent of decay, i.e. 95 as 95% becomes 0.05 as 5%.
+pub fn migrate_v1_config(storage: &mut dyn Storage, msg: &MigrateMsg) -> Result<(), ContractError> {
+    let old_config = OLD_GAME_CONFIG.load(storage)?;
+
+    let game_duration_epoch = msg
+        .game_duration_epoch
+        .ok_or_else(|| missing_field("game_duration_epoch"))?;
+    if old_config.decay_factor > Uint128::new(100u128) {
+        return Err(invalid_config_field("decay_factor", "must be at most 100"));
+    }
+    let decay_factor = Decimal::from_ratio(
+        Uint128::new(100u128).checked_sub(old_config.decay_factor)?,
+        100u128,
+    );
+
+    GAME_CONFIG_V2.save(
+        storage,
+        &GameConfigV2 {
+            fee: old_config.fee,
+            fee_reallocation: old_config.fee_reallocation,
+            fee_address: old_config.fee_address,
+            game_denom: old_config.game_denom,
+            game_cw721_addrs: old_config.game_cw721_addrs,
+            game_duration: old_config.game_duration,
+            game_duration_epoch,
+            game_extend: old_config.game_extend,
+            game_end_threshold: old_config.game_end_threshold,
+            min_pot_initial_allocation: old_config.min_pot_initial_allocation,
+            decay_factor,
+            reallocations_limit: old_config.reallocations_limit,
+        },
+    )?;
+    OLD_GAME_CONFIG.remove(storage);
+
+    Ok(())
+}
+
+/// Migrates the v2.0.0 game config to the v2.1.0 layout. The whitelisted collections keep
+/// discounting the min bid by the decay factor for each token held, as they used to.
+pub fn migrate_v2_0_config(
+    storage: &mut dyn Storage,
+    msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let config_v2 = GAME_CONFIG_V2.load(storage)?;
+
+    let max_tokens_counted = msg
+        .max_tokens_counted
+        .ok_or_else(|| missing_field("max_tokens_counted"))?;
+    let nft_claim_timeout = msg
+        .nft_claim_timeout
+        .ok_or_else(|| missing_field("nft_claim_timeout"))?;
+    let raffle_expiry_rounds = msg
+        .raffle_expiry_rounds
+        .ok_or_else(|| missing_field("raffle_expiry_rounds"))?;
+
+    let game_cw721_collections = config_v2
+        .game_cw721_addrs
+        .into_iter()
+        .map(|addr| Cw721Collection {
+            addr,
+            discount_per_token: config_v2.decay_factor,
+            max_tokens_counted,
+            trait_multipliers: vec![],
+            extra_reallocations_per_token: 0,
+            max_extra_reallocations: 0,
+            holder_fee_reallocation: None,
+        })
+        .collect();
+
+    GAME_CONFIG_V2_1.save(
+        storage,
+        &GameConfigV2_1 {
+            fee: config_v2.fee,
+            fee_reallocation: config_v2.fee_reallocation,
+            fee_address: config_v2.fee_address,
+            game_denom: config_v2.game_denom,
+            game_cw721_collections,
+            game_duration: config_v2.game_duration,
+            game_duration_epoch: config_v2.game_duration_epoch,
+            game_extend: config_v2.game_extend,
+            game_end_threshold: config_v2.game_end_threshold,
+            min_pot_initial_allocation: config_v2.min_pot_initial_allocation,
+            decay_factor: config_v2.decay_factor,
+            reallocations_limit: config_v2.reallocations_limit,
+            nft_claim_timeout,
+            raffle_expiry_rounds,
+            min_bid_floor: msg.min_bid_floor.unwrap_or_default(),
+            voting_power_sources: vec![],
+        },
+    )?;
+    GAME_CONFIG_V2.remove(storage);
+
+    Ok(())
+}
+
+/// Migrates the v2.1.0 game config to the current layout. Bids keep following the linear curve,
+/// capped at the default multiplier and without any exposure cap.
+pub fn migrate_v2_1_config(
+    storage: &mut dyn Storage,
+    msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    let config_v2_1 = GAME_CONFIG_V2_1.load(storage)?;
+
+    let timelock_delay = msg
+        .timelock_delay
+        .ok_or_else(|| missing_field("timelock_delay"))?;
+
+    GAME_CONFIG.save(
+        storage,
+        &GameConfig {
+            fee: config_v2_1.fee,
+            fee_reallocation: config_v2_1.fee_reallocation,
+            fee_address: config_v2_1.fee_address,
+            game_denom: config_v2_1.game_denom,
+            game_cw721_collections: config_v2_1.game_cw721_collections,
+            game_duration: config_v2_1.game_duration,
+            game_duration_epoch: config_v2_1.game_duration_epoch,
+            game_extend: config_v2_1.game_extend,
+            game_end_threshold: config_v2_1.game_end_threshold,
+            min_pot_initial_allocation: config_v2_1.min_pot_initial_allocation,
+            decay_factor: config_v2_1.decay_factor,
+            reallocations_limit: config_v2_1.reallocations_limit,
+            nft_claim_timeout: config_v2_1.nft_claim_timeout,
+            raffle_expiry_rounds: config_v2_1.raffle_expiry_rounds,
+            min_bid_floor: config_v2_1.min_bid_floor,
+            voting_power_sources: config_v2_1.voting_power_sources,
+            timelock_delay,
+            bid_curve: BidCurve::Linear {},
+            max_bid_multiplier: default_max_bid_multiplier(),
+            max_player_exposure: Uint128::zero(),
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Contracts instantiated before the ownership existed keep the wasm admin as their owner.
+pub fn migrate_v2_0_ownership(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+) -> Result<(), ContractError> {
+    if OWNERSHIP.may_load(storage)?.is_none() {
+        let contract_admin = querier
+            .query_wasm_contract_info(&env.contract.address)?
+            .admin;
+        OWNERSHIP.save(
+            storage,
+            &Ownership {
+                owner: contract_admin.map(Addr::unchecked),
+                pending_owner: None,
+            },
+        )?;
+        OPERATORS.save(storage, &vec![])?;
+    }
+
+    Ok(())
+}
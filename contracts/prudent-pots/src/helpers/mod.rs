@@ -1,3 +1,17 @@
+pub mod balance;
+pub mod compound;
+pub mod config;
 pub mod game_end;
+pub mod gate;
+pub mod jackpot;
+pub mod migrate;
+pub mod nft;
 pub mod pot;
+pub mod raffle;
+pub mod refund;
+pub mod reply;
+pub mod season;
+pub mod table;
 pub mod validate;
+pub mod vote;
+pub mod voting_power;
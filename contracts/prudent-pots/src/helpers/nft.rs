@@ -0,0 +1,245 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, QuerierWrapper, Storage};
+use cw721::{NftInfoResponse, OwnerOfResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    state::{
+        Cw721Collection, NftRegistration, NftWeight, GAME_CONFIG, GAME_STATE, NFT_REGISTRATIONS,
+        NFT_WEIGHTS,
+    },
+    ContractError,
+};
+
+/// The on-chain metadata extension of cw721-base, as defined by cw721-metadata-onchain.
+/// Unknown fields are ignored rather than denied, as collections often extend it with their own keys.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct Metadata {
+    pub image: Option<String>,
+    pub image_data: Option<String>,
+    pub external_url: Option<String>,
+    pub description: Option<String>,
+    pub name: Option<String>,
+    pub attributes: Option<Vec<Trait>>,
+    pub background_color: Option<String>,
+    pub animation_url: Option<String>,
+    pub youtube_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trait {
+    #[serde(default)]
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
+
+pub type Extension = Option<Metadata>;
+
+/// Helper to validate the token ids a player registers for a whitelisted collection.
+/// Token ids must be unique, and no more than the collection max_tokens_counted can be registered.
+pub fn validate_register_nfts(
+    storage: &dyn Storage,
+    collection: &Addr,
+    token_ids: &[String],
+) -> Result<Cw721Collection, ContractError> {
+    let game_config = GAME_CONFIG.load(storage)?;
+    let cw721_collection = game_config
+        .game_cw721_collections
+        .into_iter()
+        .find(|c| c.addr == *collection)
+        .ok_or(ContractError::InvalidCw721Collection {})?;
+
+    if token_ids.is_empty() || token_ids.len() > cw721_collection.max_tokens_counted as usize {
+        return Err(ContractError::InvalidInput {});
+    }
+    let mut sorted_token_ids = token_ids.to_vec();
+    sorted_token_ids.sort();
+    sorted_token_ids.dedup();
+    if sorted_token_ids.len() != token_ids.len() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    Ok(cw721_collection)
+}
+
+/// Helper to check whether a cw721 token is currently owned by the given player.
+pub fn is_nft_owner(
+    querier: &QuerierWrapper,
+    collection: &Addr,
+    token_id: &str,
+    player: &Addr,
+) -> Result<bool, ContractError> {
+    let owner_resp: OwnerOfResponse = querier.query_wasm_smart(
+        collection,
+        &cw721::Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+    Ok(owner_resp.owner == player.as_str())
+}
+
+/// Helper to lazily re-verify the player's NFT registrations right before they are used for a bid.
+/// Tokens transferred away since the registration are dropped, and registrations from previous
+/// rounds are removed, so the snapshot can be trusted by calculate_min_bid afterwards.
+pub fn reverify_nft_registrations(deps: &mut DepsMut, player: &Addr) -> Result<(), ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    for collection in &game_config.game_cw721_collections {
+        let Some(mut registration) =
+            NFT_REGISTRATIONS.may_load(deps.storage, (player, &collection.addr))?
+        else {
+            continue;
+        };
+        if registration.round_count != game_state.round_count {
+            NFT_REGISTRATIONS.remove(deps.storage, (player, &collection.addr));
+            continue;
+        }
+
+        let mut owned_tokens = vec![];
+        for token in registration.tokens {
+            // A burnt token makes the OwnerOf query fail, so we treat it as not owned
+            if is_nft_owner(&deps.querier, &collection.addr, &token.token_id, player)
+                .unwrap_or(false)
+            {
+                owned_tokens.push(token);
+            }
+        }
+
+        if owned_tokens.is_empty() {
+            NFT_REGISTRATIONS.remove(deps.storage, (player, &collection.addr));
+        } else {
+            registration.tokens = owned_tokens;
+            NFT_REGISTRATIONS.save(deps.storage, (player, &collection.addr), &registration)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Helper to count the registered tokens of a collection eligible for the bid discount, by their cached weight.
+/// Only registrations made for the current round, and in a previous block, are counted.
+/// Given a querier, the tokens are also checked to still be owned, as queries cannot re-verify the snapshot upfront.
+pub fn get_registered_tokens_count(
+    storage: &dyn Storage,
+    querier: Option<&QuerierWrapper>,
+    env: &Env,
+    player: &Addr,
+    collection: &Cw721Collection,
+    round_count: u64,
+) -> Result<u32, ContractError> {
+    let registration: Option<NftRegistration> =
+        NFT_REGISTRATIONS.may_load(storage, (player, &collection.addr))?;
+
+    Ok(registration
+        .filter(|r| r.round_count == round_count && r.block_height < env.block.height)
+        .map_or(0, |r| {
+            r.tokens
+                .iter()
+                .filter(|token| {
+                    // A burnt token makes the OwnerOf query fail, so we treat it as not owned
+                    querier.is_none_or(|querier| {
+                        is_nft_owner(querier, &collection.addr, &token.token_id, player)
+                            .unwrap_or(false)
+                    })
+                })
+                .fold(0u32, |count, token| count.saturating_add(token.weight))
+                .min(collection.max_tokens_counted)
+        }))
+}
+
+/// Helper to calculate the holder perks of a player, from the same registered tokens count used by calculate_min_bid.
+/// Returns the player reallocations limit including the extra reallocations, and the lowest reallocation fee granted.
+/// The tokens ownership is verified again only if asked, i.e. by queries reading a snapshot not re-verified.
+pub fn calculate_player_limits(
+    deps: &Deps,
+    env: &Env,
+    player: &Addr,
+    verify_ownership: bool,
+) -> Result<(u64, u64), ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    let mut reallocations_limit = game_config.reallocations_limit;
+    let mut fee_reallocation = game_config.fee_reallocation;
+    for collection in &game_config.game_cw721_collections {
+        let tokens_counted = get_registered_tokens_count(
+            deps.storage,
+            Some(&deps.querier).filter(|_| verify_ownership),
+            env,
+            player,
+            collection,
+            game_state.round_count,
+        )?;
+        if tokens_counted == 0 {
+            continue;
+        }
+
+        let extra_reallocations = collection
+            .extra_reallocations_per_token
+            .saturating_mul(tokens_counted as u64)
+            .min(collection.max_extra_reallocations);
+        reallocations_limit = reallocations_limit.saturating_add(extra_reallocations);
+        if let Some(holder_fee_reallocation) = collection.holder_fee_reallocation {
+            fee_reallocation = fee_reallocation.min(holder_fee_reallocation);
+        }
+    }
+
+    Ok((reallocations_limit, fee_reallocation))
+}
+
+/// Helper to get the weight of a cw721 token, as the highest trait multiplier matching its metadata attributes.
+/// Tokens without metadata, or without any matching trait, count as a single token.
+/// The weight is cached per token, and only looked up again once the collection trait multipliers change.
+pub fn get_nft_weight(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    collection: &Cw721Collection,
+    token_id: &str,
+) -> Result<u32, ContractError> {
+    if collection.trait_multipliers.is_empty() {
+        return Ok(1);
+    }
+    if let Some(nft_weight) = NFT_WEIGHTS.may_load(storage, (&collection.addr, token_id))? {
+        if nft_weight.trait_multipliers == collection.trait_multipliers {
+            return Ok(nft_weight.weight);
+        }
+    }
+
+    // Collections with trait multipliers are expected to use the metadata extension, any other one fails here
+    let nft_info: NftInfoResponse<Extension> = querier.query_wasm_smart(
+        &collection.addr,
+        &cw721::Cw721QueryMsg::NftInfo {
+            token_id: token_id.to_string(),
+        },
+    )?;
+    let attributes = nft_info
+        .extension
+        .and_then(|metadata| metadata.attributes)
+        .unwrap_or_default();
+
+    let weight = collection
+        .trait_multipliers
+        .iter()
+        .filter(|multiplier| {
+            attributes.iter().any(|attribute| {
+                attribute.trait_type == multiplier.trait_type && attribute.value == multiplier.value
+            })
+        })
+        .map(|multiplier| multiplier.weight)
+        .fold(1, u32::max);
+
+    NFT_WEIGHTS.save(
+        storage,
+        (&collection.addr, token_id),
+        &NftWeight {
+            weight,
+            trait_multipliers: collection.trait_multipliers.clone(),
+        },
+    )?;
+
+    Ok(weight)
+}
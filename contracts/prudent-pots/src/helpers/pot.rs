@@ -1,10 +1,14 @@
-use cosmwasm_std::{Addr, Decimal, Deps, Env, Storage, Uint128};
-use cw721::TokensResponse;
+use cosmwasm_std::{Addr, Decimal, Deps, DivideByZeroError, Env, Storage, Uint128};
 
 use crate::{
+    helpers::{
+        nft::get_registered_tokens_count,
+        voting_power::{get_voting_power_steps, query_voting_power},
+    },
+    msg::{CollectionDiscount, VotingPowerDiscount},
     state::{
-        FirstBidder, TokenAllocation, FIRST_BIDDER, GAME_CONFIG, GAME_STATE, PLAYER_ALLOCATIONS,
-        POT_STATES,
+        BidCurve, FirstBidder, GameConfig, TokenAllocation, FIRST_BIDDER, GAME_CONFIG, GAME_STATE,
+        PLAYER_ALLOCATIONS, POT_STATES,
     },
     ContractError,
 };
@@ -88,55 +92,139 @@ pub fn calculate_min_bid(
     env: &Env,
     address: Option<String>,
 ) -> Result<Uint128, ContractError> {
+    let (min_bid, _discounts, _voting_power_discounts) =
+        calculate_min_bid_with_discounts(deps, env, address, false)?;
+    Ok(min_bid)
+}
+
+// Helper to calculate the minimum bid along with the discount granted by each whitelisted cw721 collection and voting power source
+// The registered tokens ownership is verified again only if asked, as executions re-verify the snapshot beforehand
+pub fn calculate_min_bid_with_discounts(
+    deps: &Deps,
+    env: &Env,
+    address: Option<String>,
+    verify_ownership: bool,
+) -> Result<(Uint128, Vec<CollectionDiscount>, Vec<VotingPowerDiscount>), ContractError> {
     let game_config = GAME_CONFIG.load(deps.storage)?;
     let game_state = GAME_STATE.load(deps.storage)?;
     let current_timestamp = env.block.time.seconds();
 
     // Calculate the current epoch based on the game's start time and duration
-    let elapsed_time = current_timestamp
-        .checked_sub(game_state.start_time)
-        .unwrap_or_default(); // this could underflow due to a round scheduled in the future, so we unwrap default 0
+    let elapsed_time = current_timestamp.saturating_sub(game_state.start_time); // this could underflow due to a round scheduled in the future, so we saturate to 0
     let current_epoch_count = elapsed_time
         .checked_div(game_config.game_duration_epoch)
-        .unwrap();
-
-    // Calculate the adjusted decay factor considering the extend count
-    let adjusted_decay_factor = game_config
-        .decay_factor
-        .checked_mul(Decimal::from_ratio(current_epoch_count, 1u64))?
-        .checked_mul(Decimal::from_ratio(
-            game_state.extend_count as u64 + 1,
-            1u64,
-        ))?;
+        .ok_or_else(|| DivideByZeroError::new(elapsed_time))?;
 
-    // Calculate the base multiplier based on the adjusted decay factor
-    let base_multiplier = Decimal::one().checked_add(adjusted_decay_factor)?;
-
-    // Calculate min_bid as an integer multiplication of the initial allocation and the multiplier
-    let min_bid = game_config.min_pot_initial_allocation * base_multiplier;
+    let min_bid = calculate_curve_min_bid(
+        deps.storage,
+        &game_config,
+        current_epoch_count,
+        game_state.extend_count,
+    )?;
 
     // Only proceed with querying cw721 tokens if an address is provided
-    let mut cw721_count = 0;
+    let mut discounted_min_bid = min_bid;
+    let mut discounts = vec![];
+    let mut voting_power_discounts = vec![];
     if let Some(owner) = address {
-        // Query multiple cw721 addresses and count the total number of tokens
-        for addr in &game_config.game_cw721_addrs {
-            let tokens_resp: TokensResponse = deps.querier.query_wasm_smart(
-                addr,
-                &cw721::Cw721QueryMsg::Tokens {
-                    owner: owner.clone(), // Pass the owner directly since it's now available
-                    start_after: None,
-                    limit: None,
-                },
+        let owner = deps.api.addr_validate(&owner)?;
+        // Use each cw721 collection registration snapshot and apply its own discount, up to its max tokens counted
+        for collection in &game_config.game_cw721_collections {
+            if collection.max_tokens_counted == 0 || collection.discount_per_token.is_zero() {
+                continue;
+            }
+            let tokens_counted = get_registered_tokens_count(
+                deps.storage,
+                Some(&deps.querier).filter(|_| verify_ownership),
+                env,
+                &owner,
+                collection,
+                game_state.round_count,
             )?;
-            cw721_count += tokens_resp.tokens.len();
+            if tokens_counted == 0 {
+                continue;
+            }
+
+            // Apply discount based on the number of whitelisted NFT tokens owned
+            let previous_min_bid = discounted_min_bid;
+            discounted_min_bid = calculate_discounted_bid(
+                previous_min_bid,
+                tokens_counted,
+                collection.discount_per_token,
+            );
+            discounts.push(CollectionDiscount {
+                addr: collection.addr.clone(),
+                tokens_counted,
+                discount: previous_min_bid.checked_sub(discounted_min_bid)?,
+            });
+        }
+
+        // Apply the same decay_factor discount steps for each voting power tier reached
+        for source in &game_config.voting_power_sources {
+            let voting_power = query_voting_power(&deps.querier, env, source, &owner);
+            let steps = get_voting_power_steps(source, voting_power);
+            if steps == 0 {
+                continue;
+            }
+
+            let previous_min_bid = discounted_min_bid;
+            discounted_min_bid =
+                calculate_discounted_bid(previous_min_bid, steps, game_config.decay_factor);
+            voting_power_discounts.push(VotingPowerDiscount {
+                addr: source.addr.clone(),
+                voting_power,
+                steps,
+                discount: previous_min_bid.checked_sub(discounted_min_bid)?,
+            });
         }
     }
 
-    // Apply discount based on the number of whitelisted NFT tokens owned
-    let discounted_min_bid =
-        calculate_discounted_bid(min_bid, cw721_count, game_config.decay_factor);
+    // Never discount below the floor, which in turn cannot raise the min bid above the not discounted one
+    let min_bid_floor = game_config.min_bid_floor.min(min_bid);
 
-    Ok(discounted_min_bid)
+    Ok((
+        discounted_min_bid.max(min_bid_floor),
+        discounts,
+        voting_power_discounts,
+    ))
+}
+
+// Helper to calculate the not discounted min bid at an epoch of the round, following the configured bid curve
+pub fn calculate_curve_min_bid(
+    storage: &dyn Storage,
+    game_config: &GameConfig,
+    epoch_count: u64,
+    extend_count: u32,
+) -> Result<Uint128, ContractError> {
+    // Each extension makes the curve move faster along the epochs
+    let position = epoch_count.saturating_mul(extend_count as u64 + 1);
+
+    let multiplier = match &game_config.bid_curve {
+        BidCurve::Linear {} => Decimal::one().checked_add(
+            game_config
+                .decay_factor
+                .checked_mul(Decimal::from_ratio(position, 1u64))?,
+        )?,
+        // The growth is validated against the epochs of a round, extensions past it saturate the curve
+        BidCurve::Exponential { growth } => Decimal::one()
+            .checked_add(*growth)?
+            .checked_pow(u32::try_from(position).unwrap_or(u32::MAX))
+            .unwrap_or(Decimal::MAX),
+        BidCurve::Stepwise { multipliers } => {
+            let step = (position as usize).min(multipliers.len().saturating_sub(1));
+            multipliers.get(step).copied().unwrap_or(Decimal::one())
+        }
+        BidCurve::PotValue { ratio } => {
+            let total_tokens: Uint128 = get_all_token_counts(storage)?.iter().sum();
+            return Ok((total_tokens * *ratio).max(game_config.min_pot_initial_allocation));
+        }
+    };
+
+    // Calculate min_bid as an integer multiplication of the initial allocation and the multiplier
+    Ok(game_config
+        .min_pot_initial_allocation
+        .checked_mul_floor(multiplier)
+        .unwrap_or(Uint128::MAX))
 }
 
 // Helper to calculate the average tokens across all pots
@@ -154,12 +242,12 @@ fn calculate_average_tokens(storage: &dyn Storage) -> Result<Uint128, ContractEr
 
 fn calculate_discounted_bid(
     mut min_bid: Uint128,
-    token_amount: usize,
-    decay_factor: Decimal,
+    token_amount: u32,
+    discount_per_token: Decimal,
 ) -> Uint128 {
     for _ in 0..token_amount {
         // Calculate the discount amount to subtract from the current min bid
-        let discount_amount = min_bid * decay_factor;
+        let discount_amount = min_bid * discount_per_token;
         // Subtract the discount amount from the current min bid amount
         min_bid = min_bid.checked_sub(discount_amount).unwrap_or(min_bid);
     }
@@ -169,19 +257,32 @@ fn calculate_discounted_bid(
 
 // Helper to calculate the maximum bid based on the game's current state
 pub fn calculate_max_bid(deps: &Deps, original_min_bid: Uint128) -> Result<Uint128, ContractError> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
     let average_tokens = calculate_average_tokens(deps.storage)?;
 
-    // Set the maximum bid as double the average of tokens in the pots, whichever is higher
-    let max_bid = average_tokens.checked_mul(Uint128::from(2u128))?;
+    // Set the maximum bid as the multiple of the average of tokens in the pots, whichever is higher
+    let max_bid = average_tokens.checked_mul_floor(game_config.max_bid_multiplier)?;
 
-    // if the max bid calculated by average amount among pots results in a lower value than the original min bid, return the original min bid times 2
+    // if the max bid calculated by average amount among pots results in a lower value than the original min bid, return the original min bid times the multiplier
     if max_bid.le(&original_min_bid) {
-        return Ok(original_min_bid.checked_mul(Uint128::from(2u128))?);
+        return Ok(original_min_bid
+            .checked_mul_floor(game_config.max_bid_multiplier)
+            .unwrap_or(Uint128::MAX));
     }
 
     Ok(max_bid)
 }
 
+// Helper to sum the tokens a player allocated across all the pots in the round
+pub fn get_player_exposure(storage: &dyn Storage, player: &Addr) -> Result<Uint128, ContractError> {
+    Ok(PLAYER_ALLOCATIONS
+        .may_load(storage, player.to_string())?
+        .unwrap_or_default()
+        .iter()
+        .map(|allocation| allocation.amount)
+        .sum())
+}
+
 // Helper to determine if a pot is a winning pot based on its unique rules
 pub fn is_winning_pot(storage: &dyn Storage, pot_id: u8) -> Result<bool, ContractError> {
     let pot_state = POT_STATES.load(storage, pot_id)?;
@@ -238,7 +339,7 @@ fn is_median(token_counts: &[Uint128], value: Uint128) -> bool {
     sorted_counts.sort_unstable();
     let mid = sorted_counts.len() / 2;
 
-    if sorted_counts.len() % 2 == 0 {
+    if sorted_counts.len().is_multiple_of(2) {
         (sorted_counts[mid - 1] <= value) && (value <= sorted_counts[mid])
     } else {
         value == sorted_counts[mid]
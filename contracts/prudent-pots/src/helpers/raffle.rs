@@ -0,0 +1,164 @@
+use cosmwasm_std::{Addr, Order, Storage, Uint128};
+use cw_storage_plus::Bound;
+
+use crate::{
+    state::{
+        Cw721Token, RaffleSponsor, GAME_CONFIG, RAFFLE, RAFFLE_ROUND_SPONSORSHIPS, RAFFLE_SPONSORS,
+        RAFFLE_UNWON_ROUNDS, UNCLAIMED_NFTS,
+    },
+    ContractError,
+};
+
+use super::table::any_table;
+
+pub const MAX_SPONSOR_MESSAGE_LENGTH: usize = 256;
+
+/// Helper to validate the optional attribution message attached to a raffle sponsorship.
+pub fn validate_sponsor_message(message: &Option<String>) -> Result<(), ContractError> {
+    if let Some(message) = message {
+        if message.chars().count() > MAX_SPONSOR_MESSAGE_LENGTH {
+            return Err(ContractError::SponsorMessageTooLong {
+                max: MAX_SPONSOR_MESSAGE_LENGTH,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Helper to set the raffle NFT prize for the current raffle, only if there is no one yet.
+/// The cw721 collection has to be one of the whitelisted game_config.game_cw721_collections.
+pub fn set_raffle_nft(
+    storage: &mut dyn Storage,
+    cw721_addr: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let game_config = GAME_CONFIG.load(storage)?;
+    if !game_config
+        .game_cw721_collections
+        .iter()
+        .any(|collection| collection.addr == *cw721_addr)
+    {
+        return Err(ContractError::InvalidRaffleNft {});
+    }
+
+    RAFFLE.update(storage, |mut raffle| -> Result<_, ContractError> {
+        if raffle.cw721_token_id.is_some() {
+            return Err(ContractError::InvalidRaffleNft {});
+        }
+        raffle.cw721_token_id = Some(token_id.to_string());
+        raffle.cw721_addr = Some(cw721_addr.to_string());
+        Ok(raffle)
+    })?;
+
+    Ok(())
+}
+
+/// Helper to append a sponsor to the sponsors of the given round, under the next free index.
+/// The prizes are also summed up per round, so they can be told apart from the ones of previous rounds.
+pub fn add_raffle_sponsor(
+    storage: &mut dyn Storage,
+    round_count: u64,
+    sponsor: RaffleSponsor,
+) -> Result<(), ContractError> {
+    RAFFLE_ROUND_SPONSORSHIPS.update(
+        storage,
+        round_count,
+        |sponsorship| -> Result<_, ContractError> {
+            let mut sponsorship = sponsorship.unwrap_or_default();
+            sponsorship.denom_amount =
+                sponsorship.denom_amount.checked_add(sponsor.denom_amount)?;
+            if let (Some(cw721_addr), Some(token_id)) =
+                (sponsor.cw721_addr.clone(), sponsor.cw721_token_id.clone())
+            {
+                sponsorship.cw721_token = Some(Cw721Token {
+                    cw721_addr,
+                    token_id,
+                });
+            }
+            Ok(sponsorship)
+        },
+    )?;
+
+    let index = match RAFFLE_SPONSORS
+        .prefix(round_count)
+        .keys(storage, None, None, Order::Descending)
+        .next()
+    {
+        Some(last_index) => last_index? + 1,
+        None => 0,
+    };
+    RAFFLE_SPONSORS.save(storage, (round_count, index), &sponsor)?;
+    Ok(())
+}
+
+/// Helper to sum up the sponsorships not expired yet, i.e. made in the current round or in the last raffle_expiry_rounds ones.
+/// A prize sponsored in round N has only been unwon since, so it expires on its own schedule whatever the raffle counter is.
+pub fn get_unexpired_sponsorships(
+    storage: &dyn Storage,
+    round_count: u64,
+    raffle_expiry_rounds: u64,
+) -> Result<(Uint128, Vec<Cw721Token>), ContractError> {
+    let min_round = round_count.saturating_sub(raffle_expiry_rounds);
+    RAFFLE_ROUND_SPONSORSHIPS
+        .range(
+            storage,
+            Some(Bound::inclusive(min_round)),
+            None,
+            Order::Ascending,
+        )
+        .try_fold(
+            (Uint128::zero(), vec![]),
+            |(denom_amount, mut cw721_tokens), item| {
+                let (_, sponsorship) = item?;
+                cw721_tokens.extend(sponsorship.cw721_token);
+                Ok((
+                    denom_amount.checked_add(sponsorship.denom_amount)?,
+                    cw721_tokens,
+                ))
+            },
+        )
+}
+
+/// Helper to keep track of how many consecutive rounds the raffle prize has been rolled over unwon.
+/// This should be called during game_end before the raffle for the next round is saved.
+pub fn update_raffle_unwon_rounds(
+    storage: &mut dyn Storage,
+    raffle_winner: &Option<String>,
+) -> Result<u64, ContractError> {
+    let raffle = RAFFLE.load(storage)?;
+    let has_prize = raffle.cw721_token_id.is_some() || !raffle.denom_amount.is_zero();
+
+    let rounds_unwon = if raffle_winner.is_none() && has_prize {
+        RAFFLE_UNWON_ROUNDS
+            .may_load(storage)?
+            .unwrap_or_default()
+            .checked_add(1)
+            .ok_or(ContractError::InvalidInput {})?
+    } else {
+        0
+    };
+    RAFFLE_UNWON_ROUNDS.save(storage, &rounds_unwon)?;
+
+    Ok(rounds_unwon)
+}
+
+/// Helper to ensure a cw721 token held by the contract is neither the raffle prize nor escrowed for a winner of any table.
+pub fn validate_cw721_token_recoverable(
+    storage: &dyn Storage,
+    cw721_token: &Cw721Token,
+) -> Result<(), ContractError> {
+    let is_escrowed = any_table(storage, |storage| {
+        let is_raffle_prize = RAFFLE.may_load(storage)?.is_some_and(|raffle| {
+            raffle.cw721_addr.as_deref() == Some(cw721_token.cw721_addr.as_str())
+                && raffle.cw721_token_id.as_deref() == Some(cw721_token.token_id.as_str())
+        });
+        let is_unclaimed =
+            UNCLAIMED_NFTS.has(storage, (&cw721_token.cw721_addr, &cw721_token.token_id));
+        Ok(is_raffle_prize || is_unclaimed)
+    })?;
+
+    if is_escrowed {
+        return Err(ContractError::Cw721TokenNotRecoverable {});
+    }
+    Ok(())
+}
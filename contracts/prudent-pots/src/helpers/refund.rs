@@ -0,0 +1,39 @@
+use cosmwasm_std::{Addr, Order, Storage, Uint128};
+
+use crate::{
+    state::{PLAYER_ALLOCATIONS, PLAYER_REALLOCATION_FEES, REFUND_CLAIMS, REFUND_CLAIMS_TOTAL},
+    ContractError,
+};
+
+/// Helper to keep the refunds of a cancelled round that have not been claimed yet, so the game can be unpaused without them.
+/// The player allocations and reallocation fees are moved to the refund claims, returning the total moved.
+pub fn move_pending_refunds_to_claims(storage: &mut dyn Storage) -> Result<Uint128, ContractError> {
+    let mut pending_refunds: Vec<(Addr, Uint128)> = vec![];
+    for item in PLAYER_ALLOCATIONS.range(storage, None, None, Order::Ascending) {
+        let (player, allocations) = item?;
+        let mut amount = PLAYER_REALLOCATION_FEES
+            .may_load(storage, player.clone())?
+            .unwrap_or_default();
+        for allocation in allocations {
+            amount = amount.checked_add(allocation.amount)?;
+        }
+        if !amount.is_zero() {
+            pending_refunds.push((Addr::unchecked(player), amount));
+        }
+    }
+
+    let mut total = Uint128::zero();
+    for (player, amount) in pending_refunds {
+        REFUND_CLAIMS.update(storage, &player, |claim| -> Result<_, ContractError> {
+            Ok(claim.unwrap_or_default().checked_add(amount)?)
+        })?;
+        total = total.checked_add(amount)?;
+    }
+    let claims_total = REFUND_CLAIMS_TOTAL
+        .may_load(storage)?
+        .unwrap_or_default()
+        .checked_add(total)?;
+    REFUND_CLAIMS_TOTAL.save(storage, &claims_total)?;
+
+    Ok(total)
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::Storage;
+
+use crate::{msg::ReplyMsg, state::TABLE_ID, ContractError};
+
+use super::table::DEFAULT_TABLE_ID;
+
+const ROUND_COUNT_BITS: u32 = 32;
+const TABLE_ID_BITS: u32 = 24;
+
+/// Helper to build the id of a submessage, packing the reply kind in the lowest byte, the round it belongs to above it
+/// and the table in the highest bits. This way the reply knows which table and pending state to load without relying on a singleton.
+pub fn encode_reply_id(
+    storage: &dyn Storage,
+    reply_msg: ReplyMsg,
+    round_count: u64,
+) -> Result<u64, ContractError> {
+    let table_id = TABLE_ID.may_load(storage)?.unwrap_or(DEFAULT_TABLE_ID);
+    if round_count >> ROUND_COUNT_BITS != 0 || table_id >> TABLE_ID_BITS != 0 {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    Ok((table_id << (ROUND_COUNT_BITS + 8)) | (round_count << 8) | u64::from(reply_msg))
+}
+
+/// Helper to unpack the reply kind, the table and the round from the id of a submessage.
+pub fn decode_reply_id(id: u64) -> (ReplyMsg, u64, u64) {
+    let round_count = (id >> 8) & ((1 << ROUND_COUNT_BITS) - 1);
+    (
+        ReplyMsg::from(id & 0xff),
+        id >> (ROUND_COUNT_BITS + 8),
+        round_count,
+    )
+}
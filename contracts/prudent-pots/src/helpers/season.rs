@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{coins, BankMsg, CosmosMsg, Storage, Uint128};
+
+use crate::{
+    error::invalid_config_field,
+    state::{
+        Season, SeasonConfig, SeasonPayout, SeasonPointsMode, SeasonStanding, SEASON, SEASON_POINTS,
+    },
+    ContractError,
+};
+
+/// Number of leaderboard positions kept up to date, at least the paid out ones.
+pub const MAX_SEASON_LEADERS: usize = 30;
+
+/// Helper to validate a season config before the season is started.
+pub fn validate_season_config(config: &SeasonConfig) -> Result<(), ContractError> {
+    if config.rounds == 0 {
+        return Err(invalid_config_field("rounds", "must be greater than 0"));
+    }
+    if let SeasonPointsMode::Placements { points } = &config.points_mode {
+        if points.is_empty() || points.contains(&0) {
+            return Err(invalid_config_field(
+                "points_mode",
+                "placements must award at least one point each",
+            ));
+        }
+    }
+    if config.pool_share == 0 || config.pool_share > 100 {
+        return Err(invalid_config_field(
+            "pool_share",
+            "must be between 1 and 100",
+        ));
+    }
+    if config.payout_shares.contains(&0) || config.payout_shares.iter().sum::<u64>() != 100 {
+        return Err(invalid_config_field(
+            "payout_shares",
+            "must be greater than 0 and add up to 100",
+        ));
+    }
+    Ok(())
+}
+
+/// Helper to load the season counting the given round, if any.
+pub fn get_active_season(
+    storage: &dyn Storage,
+    round_count: u64,
+) -> Result<Option<Season>, ContractError> {
+    Ok(SEASON.may_load(storage)?.filter(|season| {
+        !season.finished && season.start_round <= round_count && round_count <= season.end_round
+    }))
+}
+
+/// Helper to add tokens to the season pool, reserved out of the next rounds pots until paid out.
+pub fn add_to_season_pool(storage: &mut dyn Storage, amount: Uint128) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let mut season = SEASON.load(storage)?;
+    season.pool = season.pool.checked_add(amount)?;
+    SEASON.save(storage, &season)?;
+    Ok(())
+}
+
+/// Helper to award the season points of a round, from the tokens won by each player.
+pub fn award_season_points(
+    storage: &mut dyn Storage,
+    player_winnings: &[(String, Uint128)],
+) -> Result<(), ContractError> {
+    let mut season = SEASON.load(storage)?;
+
+    // Players can win from more than one pot, so their winnings are summed first
+    let mut round_winnings: BTreeMap<String, Uint128> = BTreeMap::new();
+    for (player, amount) in player_winnings {
+        let winnings = round_winnings.entry(player.clone()).or_default();
+        *winnings = winnings.checked_add(*amount)?;
+    }
+    let mut ranking: Vec<(String, Uint128)> = round_winnings
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect();
+    ranking.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let round_points: Vec<(String, Uint128)> = match &season.config.points_mode {
+        SeasonPointsMode::Winnings {} => ranking,
+        SeasonPointsMode::Placements { points } => ranking
+            .into_iter()
+            .zip(points)
+            .map(|((player, _), points)| (player, Uint128::from(*points)))
+            .collect(),
+    };
+
+    for (player, points) in round_points {
+        let points = SEASON_POINTS.update(
+            storage,
+            (season.id, &player),
+            |season_points| -> Result<_, ContractError> {
+                Ok(season_points.unwrap_or_default().checked_add(points)?)
+            },
+        )?;
+        update_season_leaders(&mut season, player, points);
+    }
+    SEASON.save(storage, &season)?;
+
+    Ok(())
+}
+
+/// Helper to move a player to its new rank in the top of the leaderboard, ties are ranked by address.
+/// Points only grow, so a player dropped from the top can only come back with its new total.
+fn update_season_leaders(season: &mut Season, player: String, points: Uint128) {
+    season.leaders.retain(|standing| standing.player != player);
+    season.leaders.push(SeasonStanding { player, points });
+    season
+        .leaders
+        .sort_by(|a, b| b.points.cmp(&a.points).then(a.player.cmp(&b.player)));
+    season
+        .leaders
+        .truncate(season.config.payout_shares.len().max(MAX_SEASON_LEADERS));
+}
+
+/// Helper to pay out the season pool to the top of the leaderboard once its last round is ended.
+/// The shares of the positions without a player are left in the contract, rolling over to the next round.
+pub fn process_season_end(
+    storage: &mut dyn Storage,
+    round_count: u64,
+) -> Result<(Vec<CosmosMsg>, Vec<SeasonPayout>), ContractError> {
+    let mut season = match SEASON.may_load(storage)? {
+        Some(season) if !season.finished && round_count >= season.end_round => season,
+        _ => return Ok((vec![], vec![])),
+    };
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for (standing, share) in season
+        .leaders
+        .clone()
+        .into_iter()
+        .zip(&season.config.payout_shares)
+    {
+        let amount = season.pool.multiply_ratio(*share, 100u128);
+        if !amount.is_zero() {
+            msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: standing.player.clone(),
+                amount: coins(amount.u128(), &season.denom),
+            }));
+        }
+        season.payouts.push(SeasonPayout {
+            player: standing.player,
+            points: standing.points,
+            amount,
+        });
+    }
+
+    season.pool = Uint128::zero();
+    season.finished = true;
+    SEASON.save(storage, &season)?;
+
+    Ok((msgs, season.payouts))
+}
@@ -0,0 +1,301 @@
+use cosmwasm_std::{
+    storage_keys::to_length_prefixed, Deps, DepsMut, Order, Record, StdError, StdResult, Storage,
+};
+
+use crate::{
+    state::{Table, OPERATORS, OWNERSHIP, TABLES, TABLE_COUNT, TABLE_ID},
+    ContractError,
+};
+
+use super::config::load_game_configs;
+
+/// The table living in the contract storage root, kept for the deployments prior to the tables.
+pub const DEFAULT_TABLE_ID: u64 = 0;
+
+/// Storage of a single table. Every game-scoped item is stored under the table namespace,
+/// while the ownership, the operators and the tables registry are shared by all the tables.
+pub struct TableStorage<'a> {
+    storage: &'a mut dyn Storage,
+    namespace: Vec<u8>,
+}
+
+impl<'a> TableStorage<'a> {
+    pub fn new(storage: &'a mut dyn Storage, table_id: u64) -> Self {
+        TableStorage {
+            storage,
+            namespace: table_namespace(table_id),
+        }
+    }
+}
+
+impl<'a> Storage for TableStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        get_with_namespace(self.storage, &self.namespace, key)
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        range_with_namespace(self.storage, &self.namespace, start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        if is_global_key(key) {
+            self.storage.set(key, value);
+        } else {
+            self.storage.set(&concat(&self.namespace, key), value);
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        if is_global_key(key) {
+            self.storage.remove(key);
+        } else {
+            self.storage.remove(&concat(&self.namespace, key));
+        }
+    }
+}
+
+/// Read-only storage of a single table, used by the queries. It is private and only ever lent as a shared
+/// `&dyn Storage` reference, so the writes below cannot be reached by any caller.
+struct ReadonlyTableStorage<'a> {
+    storage: &'a dyn Storage,
+    namespace: Vec<u8>,
+}
+
+impl<'a> Storage for ReadonlyTableStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        get_with_namespace(self.storage, &self.namespace, key)
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        range_with_namespace(self.storage, &self.namespace, start, end, order)
+    }
+
+    fn set(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("read-only table storage is never lent mutably");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("read-only table storage is never lent mutably");
+    }
+}
+
+fn table_namespace(table_id: u64) -> Vec<u8> {
+    concat(&to_length_prefixed(b"table"), &table_id.to_be_bytes())
+}
+
+/// Namespaces of the items and maps shared by all the tables, which are never stored under a table namespace.
+fn global_namespaces() -> [&'static [u8]; 4] {
+    [
+        TABLES.namespace(),
+        TABLE_COUNT.as_slice(),
+        OWNERSHIP.as_slice(),
+        OPERATORS.as_slice(),
+    ]
+}
+
+// Items are stored under their namespace, while map entries are stored under the length prefixed one
+fn is_global_key(key: &[u8]) -> bool {
+    global_namespaces()
+        .iter()
+        .any(|namespace| key == *namespace || key.starts_with(&to_length_prefixed(namespace)))
+}
+
+// A range is global when it stays within the entries of one of the global maps
+fn is_global_range(start: Option<&[u8]>, end: Option<&[u8]>) -> bool {
+    global_namespaces().iter().any(|namespace| {
+        let prefix = to_length_prefixed(namespace);
+        let upper_bound = namespace_upper_bound(&prefix);
+        match (start, end) {
+            (Some(start), Some(end)) => start >= prefix.as_slice() && end <= upper_bound.as_slice(),
+            _ => false,
+        }
+    })
+}
+
+fn concat(namespace: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut concatenated = namespace.to_vec();
+    concatenated.extend_from_slice(key);
+    concatenated
+}
+
+fn get_with_namespace(storage: &dyn Storage, namespace: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+    if is_global_key(key) {
+        storage.get(key)
+    } else {
+        storage.get(&concat(namespace, key))
+    }
+}
+
+fn range_with_namespace<'a>(
+    storage: &'a dyn Storage,
+    namespace: &[u8],
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Box<dyn Iterator<Item = Record> + 'a> {
+    if is_global_range(start, end) {
+        return storage.range(start, end, order);
+    }
+
+    let start = concat(namespace, start.unwrap_or_default());
+    let end = match end {
+        Some(end) => concat(namespace, end),
+        None => namespace_upper_bound(namespace),
+    };
+    let namespace_len = namespace.len();
+    Box::new(
+        storage
+            .range(Some(&start), Some(&end), order)
+            .map(move |(key, value)| (key[namespace_len..].to_vec(), value)),
+    )
+}
+
+fn namespace_upper_bound(namespace: &[u8]) -> Vec<u8> {
+    let mut upper_bound = namespace.to_vec();
+    // Increment the last byte below 255, the table namespace is never made of 255 bytes only
+    for i in (0..upper_bound.len()).rev() {
+        if upper_bound[i] == 255 {
+            upper_bound[i] = 0;
+        } else {
+            upper_bound[i] += 1;
+            break;
+        }
+    }
+    upper_bound
+}
+
+/// Helper to run a table-scoped execute message against the storage of its table.
+pub fn execute_on_table<T>(
+    deps: DepsMut,
+    table_id: Option<u64>,
+    execute: impl FnOnce(DepsMut) -> Result<T, ContractError>,
+) -> Result<T, ContractError> {
+    let table_id = table_id.unwrap_or(DEFAULT_TABLE_ID);
+    if table_id == DEFAULT_TABLE_ID {
+        return execute(deps);
+    }
+    if !TABLES.has(deps.storage, table_id) {
+        return Err(ContractError::TableNotFound { table_id });
+    }
+
+    let mut table_storage = TableStorage::new(deps.storage, table_id);
+    execute(DepsMut {
+        storage: &mut table_storage,
+        api: deps.api,
+        querier: deps.querier,
+    })
+}
+
+/// Helper to run a table-scoped query against the storage of its table.
+pub fn query_on_table<T>(
+    deps: Deps,
+    table_id: Option<u64>,
+    query: impl FnOnce(Deps) -> StdResult<T>,
+) -> StdResult<T> {
+    let table_id = table_id.unwrap_or(DEFAULT_TABLE_ID);
+    if table_id == DEFAULT_TABLE_ID {
+        return query(deps);
+    }
+    if !TABLES.has(deps.storage, table_id) {
+        return Err(StdError::generic_err(
+            ContractError::TableNotFound { table_id }.to_string(),
+        ));
+    }
+
+    query(Deps {
+        storage: &ReadonlyTableStorage {
+            storage: deps.storage,
+            namespace: table_namespace(table_id),
+        },
+        api: deps.api,
+        querier: deps.querier,
+    })
+}
+
+/// Helper to validate the denoms are not used by any other table, as the tables share the contract balances.
+pub fn validate_table_denoms(
+    storage: &dyn Storage,
+    denoms: &[String],
+) -> Result<(), ContractError> {
+    let table_id = TABLE_ID.may_load(storage)?.unwrap_or(DEFAULT_TABLE_ID);
+
+    for table in TABLES.range(storage, None, None, Order::Ascending) {
+        let (id, table) = table?;
+        if id == table_id {
+            continue;
+        }
+        if let Some(denom) = denoms.iter().find(|denom| table.denoms.contains(denom)) {
+            return Err(ContractError::DenomInUse {
+                denom: denom.clone(),
+                table_id: id,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Helper to reserve the denoms of the table config and its pending config in the tables registry.
+pub fn reserve_table_denoms(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let table_id = TABLE_ID.may_load(storage)?.unwrap_or(DEFAULT_TABLE_ID);
+    let (game_config, pending_config) = load_game_configs(storage)?;
+
+    let mut denoms = vec![game_config.game_denom];
+    if !denoms.contains(&pending_config.game_denom) {
+        denoms.push(pending_config.game_denom);
+    }
+    validate_table_denoms(storage, &denoms)?;
+
+    TABLES.save(
+        storage,
+        table_id,
+        &Table {
+            id: table_id,
+            denoms,
+        },
+    )?;
+    Ok(())
+}
+
+/// Helper to list the denoms reserved by all the tables, which can never be swept from the contract.
+pub fn get_reserved_denoms(storage: &dyn Storage) -> Result<Vec<String>, ContractError> {
+    let mut denoms = vec![];
+    for table in TABLES.range(storage, None, None, Order::Ascending) {
+        denoms.extend(table?.1.denoms);
+    }
+    Ok(denoms)
+}
+
+/// Helper to tell if a read holds for any table, i.e. to check the assets escrowed by all of them.
+/// It has to be called with the contract storage root, as a table storage cannot reach the others.
+pub fn any_table(
+    storage: &dyn Storage,
+    read: impl Fn(&dyn Storage) -> Result<bool, ContractError>,
+) -> Result<bool, ContractError> {
+    if read(storage)? {
+        return Ok(true);
+    }
+    for table_id in TABLES.keys(storage, None, None, Order::Ascending) {
+        let table_id = table_id?;
+        if table_id == DEFAULT_TABLE_ID {
+            continue;
+        }
+        let table_storage = ReadonlyTableStorage {
+            storage,
+            namespace: table_namespace(table_id),
+        };
+        if read(&table_storage)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -1,44 +1,49 @@
 use cosmwasm_std::{Addr, Coin, Env, QuerierWrapper, Storage, Uint128};
+use cw721::OwnerOfResponse;
 
 use crate::{
-    state::{GAME_CONFIG, GAME_STATE, PLAYER_ALLOCATIONS, PLAYER_REALLOCATIONS, POT_STATES},
+    state::{
+        GameConfig, GAME_CONFIG, GAME_STATE, OPERATORS, OWNERSHIP, PAUSED, PLAYER_ALLOCATIONS,
+        PLAYER_REALLOCATIONS, POT_STATES, ROUND_CANCELLED,
+    },
     ContractError,
 };
 
-// This is meant to give Unauthorized even after the Admin ownership over the contract has been thrown.
-pub fn validate_is_contract_admin(
-    querier: &QuerierWrapper,
-    env: &Env,
-    sus_admin: &Addr,
-) -> Result<(), ContractError> {
-    let contract_admin = querier
-        .query_wasm_contract_info(&env.contract.address)?
-        .admin;
+// This is meant to give Unauthorized even after the ownership over the contract has been renounced.
+pub fn validate_is_owner(storage: &dyn Storage, sus_owner: &Addr) -> Result<(), ContractError> {
+    let ownership = OWNERSHIP.load(storage)?;
 
-    if let Some(contract_admin) = contract_admin {
-        if contract_admin != *sus_admin {
-            return Err(ContractError::Unauthorized {});
-        }
-    } else {
+    if ownership.owner.as_ref() != Some(sus_owner) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+// Operators can run the game and manage raffles, the owner is always an operator as well.
+pub fn validate_is_operator(
+    storage: &dyn Storage,
+    sus_operator: &Addr,
+) -> Result<(), ContractError> {
+    if validate_is_owner(storage, sus_operator).is_ok() {
+        return Ok(());
+    }
+    if !OPERATORS.load(storage)?.contains(sus_operator) {
         return Err(ContractError::Unauthorized {});
     }
     Ok(())
 }
 
 // That function is meant to add a permissioned time-based layer over the game_end function,
-// leaving it permissionles after a threshold time if the admin doesn't.
-pub fn validate_is_contract_admin_game_end(
+// leaving it permissionles after a threshold time if the owner or operators don't.
+pub fn validate_is_operator_game_end(
     storage: &dyn Storage,
-    querier: &QuerierWrapper,
     env: &Env,
-    sus_admin: &Addr,
+    sus_operator: &Addr,
 ) -> Result<(), ContractError> {
     let game_config = GAME_CONFIG.load(storage)?;
     let game_state = GAME_STATE.load(storage)?;
 
-    let contract_admin = querier
-        .query_wasm_contract_info(&env.contract.address)?
-        .admin;
+    let contract_owner = OWNERSHIP.load(storage)?.owner;
 
     // Calculate if current time is within the threshold time after game end
     if env.block.time.seconds()
@@ -47,15 +52,36 @@ pub fn validate_is_contract_admin_game_end(
             .checked_add(game_config.game_end_threshold)
             .unwrap()
     {
-        if let Some(contract_admin) = contract_admin {
-            if contract_admin != *sus_admin {
-                return Err(ContractError::Unauthorized {});
-            }
+        if contract_owner.is_some() {
+            validate_is_operator(storage, sus_operator)?;
         } else {
-            // Within the threshold time, if there is no contract admin, any user can execute the function regardless of admin status
+            // Within the threshold time, if there is no contract owner, any user can execute the function regardless of operator status
         }
     } else {
-        // After the threshold time, any user can execute the function regardless of admin status
+        // After the threshold time, any user can execute the function regardless of operator status
+    }
+    Ok(())
+}
+
+// Helper to ensure a cw721 token notified through ReceiveNft has effectively been received by the contract.
+pub fn validate_nft_received(
+    querier: &QuerierWrapper,
+    env: &Env,
+    cw721_addr: &Addr,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let owner_resp: OwnerOfResponse = querier
+        .query_wasm_smart(
+            cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .map_err(|_| ContractError::Cw721TokenNotReceived {})?;
+
+    if owner_resp.owner != env.contract.address.as_str() {
+        return Err(ContractError::Cw721TokenNotReceived {});
     }
     Ok(())
 }
@@ -77,15 +103,15 @@ pub fn validate_pot_initial_amount(
 pub fn validate_increase_player_reallocations(
     storage: &mut dyn Storage,
     player: &Addr,
+    reallocations_limit: u64,
 ) -> Result<(), ContractError> {
-    // Load game configuration and the current number of reallocations for the player.
-    let game_config = GAME_CONFIG.load(storage)?;
+    // Load the current number of reallocations for the player, the limit includes any NFT holder extra reallocations.
     let current_reallocations = PLAYER_REALLOCATIONS
         .may_load(storage, player.to_string())?
         .unwrap_or_default();
 
     // Check if the player has reached the reallocation limit.
-    if current_reallocations >= game_config.reallocations_limit {
+    if current_reallocations >= reallocations_limit {
         return Err(ContractError::ReallocationsLimitReached {});
     }
 
@@ -122,6 +148,19 @@ pub fn validate_existing_allocation(
     Ok(())
 }
 
+pub fn validate_player_exposure(
+    game_config: &GameConfig,
+    exposure: Uint128,
+) -> Result<(), ContractError> {
+    if !game_config.max_player_exposure.is_zero() && exposure > game_config.max_player_exposure {
+        return Err(ContractError::ExposureCapExceeded {
+            max: game_config.max_player_exposure,
+        });
+    }
+
+    Ok(())
+}
+
 pub fn validate_pot_limit_not_exceeded(
     storage: &dyn Storage,
     pot_id: u8,
@@ -169,6 +208,17 @@ pub fn validate_game_time(storage: &mut dyn Storage, env: &Env) -> Result<(), Co
     Ok(())
 }
 
+// Helper to block the game actions while the game is paused, or the round has been cancelled.
+pub fn validate_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if ROUND_CANCELLED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::RoundCancelled {});
+    }
+    if PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::GamePaused {});
+    }
+    Ok(())
+}
+
 // Helper to validate the game's end time during game_end exeuction
 pub fn validate_game_end_time(storage: &dyn Storage, env: &Env) -> Result<(), ContractError> {
     let game_state = GAME_STATE.load(storage)?;
@@ -0,0 +1,85 @@
+use cosmwasm_std::{Api, Order, StdResult, Storage, Uint128};
+
+use super::config::validate_game_config;
+use crate::{
+    state::{GameConfig, VoteOption, VoteTally, PLAYER_ALLOCATIONS, PLAYER_VOTES, VOTE_OPTIONS},
+    ContractError,
+};
+
+const MAX_VOTE_OPTIONS: usize = 5;
+
+/// Helper to apply the settings of a vote option over a game config.
+pub fn apply_vote_option(mut config: GameConfig, option: &VoteOption) -> GameConfig {
+    if let Some(game_duration) = option.game_duration {
+        config.game_duration = game_duration;
+    }
+    if let Some(reallocations_limit) = option.reallocations_limit {
+        config.reallocations_limit = reallocations_limit;
+    }
+    config
+}
+
+/// Helper to validate the vote options menu, each option has to result in a valid config.
+pub fn validate_vote_options(
+    api: &dyn Api,
+    config: &GameConfig,
+    options: &[VoteOption],
+) -> Result<(), ContractError> {
+    if options.len() > MAX_VOTE_OPTIONS {
+        return Err(ContractError::InvalidInput {});
+    }
+    for option in options {
+        if option.label.is_empty()
+            || (option.game_duration.is_none() && option.reallocations_limit.is_none())
+        {
+            return Err(ContractError::InvalidInput {});
+        }
+        validate_game_config(api, &apply_vote_option(config.clone(), option))?;
+    }
+    Ok(())
+}
+
+/// Helper to tally the votes of the current round, each vote weighted by the player's total allocation.
+/// Ties are won by the first option, and no option wins without any weight.
+pub fn tally_votes(storage: &dyn Storage, round_count: u64) -> Result<VoteTally, ContractError> {
+    let options = VOTE_OPTIONS.may_load(storage)?.unwrap_or_default();
+    let mut weights = vec![Uint128::zero(); options.len()];
+
+    let votes = PLAYER_VOTES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (player, option_id) in votes {
+        let allocations = PLAYER_ALLOCATIONS
+            .may_load(storage, player)?
+            .unwrap_or_default();
+        let weight = allocations
+            .iter()
+            .try_fold(Uint128::zero(), |acc, allocation| {
+                acc.checked_add(allocation.amount)
+            })?;
+        if let Some(option_weight) = weights.get_mut(option_id as usize) {
+            *option_weight = option_weight.checked_add(weight)?;
+        }
+    }
+
+    let winning_option = weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| !weight.is_zero())
+        .fold(
+            None,
+            |winner: Option<(usize, &Uint128)>, (id, weight)| match winner {
+                Some((_, winner_weight)) if winner_weight >= weight => winner,
+                _ => Some((id, weight)),
+            },
+        )
+        .map(|(id, _)| id as u32);
+
+    Ok(VoteTally {
+        round_count,
+        options,
+        weights,
+        winning_option,
+        applied: false,
+    })
+}
@@ -0,0 +1,139 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, Env, QuerierWrapper, Uint128};
+use serde::Deserialize;
+
+use crate::{
+    error::invalid_config_field,
+    state::{VotingPowerSource, VotingPowerSourceKind},
+    ContractError,
+};
+
+/// Subset of the query messages exposed by the supported voting power sources.
+
+#[cw_serde]
+enum DaoVotingModuleQueryMsg {
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+enum Cw20StakeQueryMsg {
+    StakedBalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+enum Cw4GroupQueryMsg {
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+}
+
+#[derive(Deserialize)]
+struct VotingPowerAtHeightResponse {
+    power: Uint128,
+}
+
+#[derive(Deserialize)]
+struct StakedBalanceAtHeightResponse {
+    balance: Uint128,
+}
+
+#[derive(Deserialize)]
+struct MemberResponse {
+    weight: Option<u64>,
+}
+
+/// Helper to validate the voting power sources, each one needs at least one tier and ascending thresholds.
+pub fn validate_voting_power_sources(
+    api: &dyn Api,
+    sources: &[VotingPowerSource],
+) -> Result<(), ContractError> {
+    for source in sources {
+        api.addr_validate(source.addr.as_str())
+            .map_err(|err| invalid_config_field("voting_power_sources", &err.to_string()))?;
+        if source.tiers.is_empty()
+            || source.tiers[0].is_zero()
+            || source.tiers.windows(2).any(|tiers| tiers[0] >= tiers[1])
+        {
+            return Err(invalid_config_field(
+                "voting_power_sources",
+                "tiers must be non-zero and strictly ascending",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Helper to query the voting power of an address from a source, as of the previous block,
+/// so tokens staked or members added in the same block cannot be used for a discount.
+/// A source failing to answer, or not knowing the address, counts as no voting power.
+pub fn query_voting_power(
+    querier: &QuerierWrapper,
+    env: &Env,
+    source: &VotingPowerSource,
+    address: &Addr,
+) -> Uint128 {
+    let height = Some(env.block.height.saturating_sub(1));
+    match source.kind {
+        VotingPowerSourceKind::DaoVotingModule => querier
+            .query_wasm_smart::<VotingPowerAtHeightResponse>(
+                &source.addr,
+                &DaoVotingModuleQueryMsg::VotingPowerAtHeight {
+                    address: address.to_string(),
+                    height,
+                },
+            )
+            .map_or(Uint128::zero(), |res| res.power),
+        VotingPowerSourceKind::Cw20Stake => querier
+            .query_wasm_smart::<StakedBalanceAtHeightResponse>(
+                &source.addr,
+                &Cw20StakeQueryMsg::StakedBalanceAtHeight {
+                    address: address.to_string(),
+                    height,
+                },
+            )
+            .map_or(Uint128::zero(), |res| res.balance),
+        VotingPowerSourceKind::Cw4Group => querier
+            .query_wasm_smart::<MemberResponse>(
+                &source.addr,
+                &Cw4GroupQueryMsg::Member {
+                    addr: address.to_string(),
+                    at_height: height,
+                },
+            )
+            .map_or(Uint128::zero(), |res| {
+                Uint128::from(res.weight.unwrap_or_default())
+            }),
+    }
+}
+
+/// Helper to check an address is a current member of a cw4 group, a group failing to answer is an error.
+pub fn is_cw4_member(
+    querier: &QuerierWrapper,
+    group: &Addr,
+    address: &Addr,
+) -> Result<bool, ContractError> {
+    let res: MemberResponse = querier.query_wasm_smart(
+        group,
+        &Cw4GroupQueryMsg::Member {
+            addr: address.to_string(),
+            at_height: None,
+        },
+    )?;
+    Ok(res.weight.is_some())
+}
+
+/// Helper to get the amount of discount steps granted by a voting power, as the amount of tiers reached.
+pub fn get_voting_power_steps(source: &VotingPowerSource, voting_power: Uint128) -> u32 {
+    source
+        .tiers
+        .iter()
+        .take_while(|tier| voting_power >= **tier)
+        .count() as u32
+}
@@ -1,8 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw721::Cw721ReceiveMsg;
 use num_enum::{FromPrimitive, IntoPrimitive};
 
-use crate::state::{GameConfig, GameState, Raffle, TokenAllocation};
+use crate::state::{
+    AssetRecovery, AutoCompoundStrategy, BidCurve, ConfigProposal, Cw721Collection, Cw721Token,
+    GameConfig, GameState, Jackpot, JackpotConfig, JackpotTrigger, NftRegistration,
+    ParticipationGate, Raffle, RaffleSponsor, Season, SeasonConfig, SeasonStanding, Table,
+    TimelockedConfig, TokenAllocation, UnclaimedNft, VoteOption, VoteTally, VotingPowerSource,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -12,42 +18,169 @@ pub struct InstantiateMsg {
 
 #[cw_serde]
 pub struct UpdateGameConfig {
-    pub fee: Option<u64>,
     pub fee_reallocation: Option<u64>,
-    pub fee_address: Option<Addr>,
-    pub game_denom: Option<String>,
-    pub game_cw721_addrs: Vec<Addr>,
+    pub game_cw721_collections: Vec<Cw721Collection>,
     pub game_duration: Option<u64>,
     pub game_duration_epoch: Option<u64>,
     pub game_extend: Option<u64>,
     pub game_end_threshold: Option<u64>,
     pub min_pot_initial_allocation: Option<Uint128>,
     pub decay_factor: Option<Decimal>,
-    pub reallocations_limit: Option<u64>,
+    pub nft_claim_timeout: Option<u64>,
+    pub raffle_expiry_rounds: Option<u64>,
+    pub min_bid_floor: Option<Uint128>,
+    pub voting_power_sources: Option<Vec<VotingPowerSource>>,
+    pub bid_curve: Option<BidCurve>,
+    pub max_bid_multiplier: Option<Decimal>,
+    pub max_player_exposure: Option<Uint128>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     UpdateConfig {
+        table_id: Option<u64>,
         config: Box<UpdateGameConfig>,
     },
     AllocateTokens {
+        table_id: Option<u64>,
         pot_id: u8,
     },
+    AllocateFromBalance {
+        table_id: Option<u64>,
+        pot_id: u8,
+        amount: Uint128,
+    },
     ReallocateTokens {
+        table_id: Option<u64>,
         from_pot_id: u8,
         to_pot_id: u8,
     },
     GameEnd {
-        raffle_cw721_token_id: Option<String>,
-        raffle_cw721_token_addr: Option<String>,
+        table_id: Option<u64>,
         next_game_start: Option<u64>,
     },
     UpdateNextGame {
-        raffle_cw721_token_id: Option<String>,
-        raffle_cw721_token_addr: Option<String>,
+        table_id: Option<u64>,
+        next_game_start: Option<u64>,
+    },
+    SponsorRaffle {
+        table_id: Option<u64>,
+        message: Option<String>,
+    },
+    ReceiveNft(Cw721ReceiveMsg),
+    ClaimNft {
+        table_id: Option<u64>,
+        cw721_addr: String,
+        token_id: String,
+    },
+    RecoverAssets {
+        table_id: Option<u64>,
+        recover_raffle: bool,
+        cw721_tokens: Vec<Cw721Token>,
+    },
+    RegisterNfts {
+        table_id: Option<u64>,
+        collection: String,
+        token_ids: Vec<String>,
+    },
+    TransferOwnership {
+        new_owner: String,
+    },
+    AcceptOwnership {},
+    RenounceOwnership {},
+    UpdateOperators {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    Pause {
+        table_id: Option<u64>,
+    },
+    Unpause {
+        table_id: Option<u64>,
+    },
+    CancelRound {
+        table_id: Option<u64>,
+    },
+    Refund {
+        table_id: Option<u64>,
+    },
+    ProposeConfig {
+        table_id: Option<u64>,
+        changes: TimelockedConfig,
+    },
+    ExecuteProposal {
+        table_id: Option<u64>,
+        proposal_id: u64,
+    },
+    CancelProposal {
+        table_id: Option<u64>,
+        proposal_id: u64,
+    },
+    SetVoteOptions {
+        table_id: Option<u64>,
+        options: Vec<VoteOption>,
+    },
+    Vote {
+        table_id: Option<u64>,
+        option_id: u32,
+    },
+    CreateTable {
+        config: Box<GameConfig>,
         next_game_start: Option<u64>,
     },
+    StartSeason {
+        table_id: Option<u64>,
+        config: SeasonConfig,
+    },
+    SetJackpot {
+        table_id: Option<u64>,
+        config: JackpotConfig,
+    },
+    SetParticipationGate {
+        table_id: Option<u64>,
+        gate: ParticipationGate,
+    },
+    UpdateAllowlist {
+        table_id: Option<u64>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    UpdateBlocklist {
+        table_id: Option<u64>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    SetAutoCompound {
+        table_id: Option<u64>,
+        pot_strategy: Option<AutoCompoundStrategy>, // None to opt out, the winnings being sent again
+    },
+    ClaimBalance {
+        table_id: Option<u64>,
+    },
+    Deposit {
+        table_id: Option<u64>,
+    },
+    Withdraw {
+        table_id: Option<u64>,
+        amount: Uint128,
+    },
+    SetBalancePayouts {
+        table_id: Option<u64>,
+        enabled: bool, // credit the winnings to the balance instead of sending them
+    },
+}
+
+/// Hook messages accepted through cw721 SendNft
+
+#[cw_serde]
+pub enum ReceiveNftMsg {
+    SetRaffleNft {
+        table_id: Option<u64>,
+    },
+    SponsorRaffle {
+        table_id: Option<u64>,
+        message: Option<String>,
+    },
 }
 
 /// Reply
@@ -55,7 +188,7 @@ pub enum ExecuteMsg {
 #[derive(FromPrimitive, IntoPrimitive)]
 #[repr(u64)]
 pub enum ReplyMsg {
-    TransferNft = 1,
+    TransferNftToWinner = 2,
     #[default]
     Unknown,
 }
@@ -64,31 +197,140 @@ pub enum ReplyMsg {
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(GameConfigResponse)]
-    GameConfig {},
+    GameConfig { table_id: Option<u64> },
     #[returns(GameStateResponse)]
-    GameState {},
+    GameState { table_id: Option<u64> },
     #[returns(BidRangeResponse)]
-    BidRange { address: Option<String> },
+    BidRange {
+        table_id: Option<u64>,
+        address: Option<String>,
+    },
+    #[returns(BidCurveResponse)]
+    BidCurve {
+        table_id: Option<u64>,
+        epochs: Option<u32>,
+    },
     #[returns(PotStateResponse)]
-    PotState { pot_id: u8 },
+    PotState { table_id: Option<u64>, pot_id: u8 },
     #[returns(PotsStateResponse)]
-    PotsState {},
+    PotsState { table_id: Option<u64> },
     #[returns(WinningPotsResponse)]
-    WinningPots {},
+    WinningPots { table_id: Option<u64> },
     #[returns(PlayerAllocationsResponse)]
-    PlayerAllocations { address: String },
+    PlayerAllocations {
+        table_id: Option<u64>,
+        address: String,
+    },
     #[returns(PlayerReallocationsResponse)]
-    PlayerReallocations { address: String },
+    PlayerReallocations {
+        table_id: Option<u64>,
+        address: String,
+    },
     #[returns(AllPlayersAllocationsResponse)]
-    AllPlayersAllocations {},
+    AllPlayersAllocations { table_id: Option<u64> },
     #[returns(ReallocationFeePoolResponse)]
-    ReallocationFeePool {},
+    ReallocationFeePool { table_id: Option<u64> },
     #[returns(RaffleResponse)]
-    Raffle {},
+    Raffle { table_id: Option<u64> },
     #[returns(RaffleWinnerResponse)]
-    RaffleWinner {},
+    RaffleWinner { table_id: Option<u64> },
     #[returns(RaffleDenomSplitResponse)]
-    RaffleDenomSplit {},
+    RaffleDenomSplit { table_id: Option<u64> },
+    #[returns(RaffleSponsorsResponse)]
+    RaffleSponsors {
+        table_id: Option<u64>,
+        round: u64,
+        start_after: Option<u64>, // index of the last sponsor of the previous page, in sponsoring order
+        limit: Option<u32>,
+    },
+    #[returns(UnclaimedNftsResponse)]
+    UnclaimedNfts {
+        table_id: Option<u64>,
+        recipient: Option<String>,
+    },
+    #[returns(RecoveryLogResponse)]
+    RecoveryLog {
+        table_id: Option<u64>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(NftRegistrationsResponse)]
+    NftRegistrations {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(PlayerLimitsResponse)]
+    PlayerLimits {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    #[returns(EmergencyStateResponse)]
+    EmergencyState { table_id: Option<u64> },
+    #[returns(RefundClaimResponse)]
+    RefundClaim {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(PendingConfigResponse)]
+    PendingConfig { table_id: Option<u64> },
+    #[returns(PendingProposalsResponse)]
+    PendingProposals {
+        table_id: Option<u64>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(VotesResponse)]
+    Votes { table_id: Option<u64> },
+    #[returns(PlayerVoteResponse)]
+    PlayerVote {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(VoteTallyResponse)]
+    VoteTally { table_id: Option<u64>, round: u64 },
+    #[returns(TablesResponse)]
+    Tables {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(SeasonResponse)]
+    Season { table_id: Option<u64> },
+    #[returns(SeasonStandingsResponse)]
+    SeasonStandings {
+        table_id: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(JackpotResponse)]
+    Jackpot { table_id: Option<u64> },
+    #[returns(ParticipationResponse)]
+    Participation {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(AddressListResponse)]
+    Allowlist {
+        table_id: Option<u64>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AddressListResponse)]
+    Blocklist {
+        table_id: Option<u64>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AutoCompoundResponse)]
+    AutoCompound {
+        table_id: Option<u64>,
+        address: String,
+    },
+    #[returns(BalanceResponse)]
+    Balance {
+        table_id: Option<u64>,
+        address: String,
+    },
 }
 
 #[cw_serde]
@@ -105,6 +347,40 @@ pub struct GameStateResponse {
 pub struct BidRangeResponse {
     pub min_bid: Uint128,
     pub max_bid: Uint128,
+    pub max_bid_multiplier: Decimal,
+    pub max_player_exposure: Option<Uint128>, // the cap across all the pots, if any
+    pub remaining_exposure: Option<Uint128>,  // what the address can still allocate under the cap
+    pub discounts: Vec<CollectionDiscount>,
+    pub voting_power_discounts: Vec<VotingPowerDiscount>,
+}
+
+#[cw_serde]
+pub struct BidCurveResponse {
+    pub curve: BidCurve,
+    pub points: Vec<BidCurvePoint>, // from the current epoch on, not discounted and with the current pots
+}
+
+#[cw_serde]
+pub struct BidCurvePoint {
+    pub epoch: u64,
+    pub start_time: u64,
+    pub min_bid: Uint128,
+    pub max_bid: Uint128,
+}
+
+#[cw_serde]
+pub struct CollectionDiscount {
+    pub addr: Addr,
+    pub tokens_counted: u32,
+    pub discount: Uint128, // amount discounted from the min bid by this collection
+}
+
+#[cw_serde]
+pub struct VotingPowerDiscount {
+    pub addr: Addr,
+    pub voting_power: Uint128,
+    pub steps: u32,
+    pub discount: Uint128, // amount discounted from the min bid by this voting power source
 }
 
 #[cw_serde]
@@ -159,4 +435,133 @@ pub struct RaffleDenomSplitResponse {
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct RaffleSponsorsResponse {
+    pub sponsors: Vec<RaffleSponsor>,
+}
+
+#[cw_serde]
+pub struct UnclaimedNftsResponse {
+    pub nfts: Vec<UnclaimedNft>,
+}
+
+#[cw_serde]
+pub struct RecoveryLogResponse {
+    pub recoveries: Vec<AssetRecovery>,
+}
+
+#[cw_serde]
+pub struct NftRegistrationsResponse {
+    pub registrations: Vec<NftRegistration>,
+}
+
+#[cw_serde]
+pub struct PlayerLimitsResponse {
+    pub reallocations: u64,
+    pub reallocations_limit: u64, // including the extra reallocations granted to NFT holders
+    pub fee_reallocation: u64,
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    pub operators: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct EmergencyStateResponse {
+    pub paused: bool,
+    pub round_cancelled: bool, // players can be refunded while the round is cancelled
+}
+
+#[cw_serde]
+pub struct RefundClaimResponse {
+    pub amount: Uint128, // refund of a cancelled round left to claim after the game has been unpaused
+}
+
+#[cw_serde]
+pub struct PendingConfigResponse {
+    pub config: Option<GameConfig>,
+    pub changed_fields: Vec<String>, // fields differing from the current game config
+    pub activates_at_round: Option<u64>,
+    pub activates_after: Option<u64>, // the current round end time, the changes apply once the round is ended
+}
+
+#[cw_serde]
+pub struct PendingProposalsResponse {
+    pub proposals: Vec<ConfigProposal>,
+}
+
+#[cw_serde]
+pub struct VotesResponse {
+    pub round_count: u64,
+    pub tally: VoteTally, // live tally of the current round, weighted by the current allocations
+}
+
+#[cw_serde]
+pub struct PlayerVoteResponse {
+    pub option_id: Option<u32>,
+}
+
+#[cw_serde]
+pub struct VoteTallyResponse {
+    pub tally: Option<VoteTally>,
+}
+
+#[cw_serde]
+pub struct TablesResponse {
+    pub tables: Vec<Table>,
+}
+
+#[cw_serde]
+pub struct SeasonResponse {
+    pub season: Option<Season>, // the current season, or the last finished one
+    pub round_count: u64,
+}
+
+#[cw_serde]
+pub struct SeasonStandingsResponse {
+    pub season_id: Option<u64>,
+    pub standings: Vec<SeasonStanding>,
+}
+
+#[cw_serde]
+pub struct JackpotResponse {
+    pub jackpot: Option<Jackpot>,
+    pub trigger: Option<JackpotTrigger>, // the trigger met by the current board, paid out if it holds at game end
+}
+
+#[cw_serde]
+pub struct ParticipationResponse {
+    pub gate: ParticipationGate,
+    pub blocked: bool,
+    pub allowed: bool, // whether the address can currently allocate and win the raffle
+}
+
+#[cw_serde]
+pub struct AddressListResponse {
+    pub addresses: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct AutoCompoundResponse {
+    pub pot_strategy: Option<AutoCompoundStrategy>,
+    pub claimable: Uint128, // winnings that failed the bid checks when compounded, waiting to be claimed
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+    pub balance_payouts: bool, // whether the winnings are credited to the balance
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    pub game_duration_epoch: Option<u64>, // required migrating from v1, which had no epochs
+    pub max_tokens_counted: Option<u32>, // required migrating from v2.0.0, applied to each whitelisted collection
+    pub nft_claim_timeout: Option<u64>,  // required migrating from v2.0.0
+    pub raffle_expiry_rounds: Option<u64>, // required migrating from v2.0.0
+    pub min_bid_floor: Option<Uint128>,  // defaults to no floor migrating from v2.0.0
+    pub timelock_delay: Option<u64>,     // required migrating from v2.1.0 or older
+}
@@ -1,22 +1,44 @@
-use cosmwasm_std::{Deps, Env, StdResult};
+use cosmwasm_std::{Addr, Deps, Empty, Env, Order, StdError, StdResult};
+use cw_storage_plus::{Bound, Map};
 
 use crate::{
     helpers::{
+        config::changed_config_fields,
         game_end::{get_raffle_denom_prize_amounts, get_raffle_winner},
-        pot::{calculate_max_bid, calculate_min_bid, get_winning_pots},
+        gate::validate_participation,
+        jackpot::get_jackpot_trigger,
+        nft::calculate_player_limits,
+        pot::{
+            calculate_curve_min_bid, calculate_max_bid, calculate_min_bid,
+            calculate_min_bid_with_discounts, get_player_exposure, get_winning_pots,
+        },
+        vote::tally_votes,
     },
     msg::{
-        AllPlayersAllocationsResponse, BidRangeResponse, GameConfigResponse, GameStateResponse,
-        PlayerAllocationsResponse, PlayerReallocationsResponse, PotStateResponse,
-        PotsStateResponse, RaffleDenomSplitResponse, RaffleResponse, RaffleWinnerResponse,
-        ReallocationFeePoolResponse, WinningPotsResponse,
+        AddressListResponse, AllPlayersAllocationsResponse, AutoCompoundResponse, BalanceResponse,
+        BidCurvePoint, BidCurveResponse, BidRangeResponse, EmergencyStateResponse,
+        GameConfigResponse, GameStateResponse, JackpotResponse, NftRegistrationsResponse,
+        OwnershipResponse, ParticipationResponse, PendingConfigResponse, PendingProposalsResponse,
+        PlayerAllocationsResponse, PlayerLimitsResponse, PlayerReallocationsResponse,
+        PlayerVoteResponse, PotStateResponse, PotsStateResponse, RaffleDenomSplitResponse,
+        RaffleResponse, RaffleSponsorsResponse, RaffleWinnerResponse, ReallocationFeePoolResponse,
+        RecoveryLogResponse, RefundClaimResponse, SeasonResponse, SeasonStandingsResponse,
+        TablesResponse, UnclaimedNftsResponse, VoteTallyResponse, VotesResponse,
+        WinningPotsResponse,
     },
     state::{
-        GAME_CONFIG, GAME_STATE, PLAYER_ALLOCATIONS, PLAYER_REALLOCATIONS, POT_STATES, RAFFLE,
-        REALLOCATION_FEE_POOL,
+        ParticipationGate, AUTO_COMPOUND, BALANCES, BALANCE_PAYOUTS, BLOCKLIST, CLAIMABLE_BALANCES,
+        GAME_CONFIG, GAME_STATE, JACKPOT, NFT_REGISTRATIONS, OPERATORS, OWNERSHIP,
+        PARTICIPATION_GATE, PAUSED, PENDING_CONFIG, PLAYER_ALLOCATIONS, PLAYER_REALLOCATIONS,
+        PLAYER_VOTES, POT_STATES, PROPOSALS, RAFFLE, RAFFLE_SPONSORS, REALLOCATION_FEE_POOL,
+        RECOVERY_LOG, REFUND_CLAIMS, ROUND_CANCELLED, SEASON, TABLES, UNCLAIMED_NFTS, VOTE_TALLIES,
     },
+    ContractError,
 };
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 pub fn query_game_config(deps: Deps) -> StdResult<GameConfigResponse> {
     let config = GAME_CONFIG.load(deps.storage)?;
     Ok(GameConfigResponse { config })
@@ -32,12 +54,73 @@ pub fn query_bid_range(
     env: Env,
     address: Option<String>,
 ) -> StdResult<BidRangeResponse> {
-    let min_bid = calculate_min_bid(&deps, &env, address).unwrap();
+    // The registered NFTs are checked to still be owned, as the allocation re-verifies them before applying the discount
+    let (min_bid, discounts, voting_power_discounts) =
+        calculate_min_bid_with_discounts(&deps, &env, address.clone(), true)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
 
     // Calculate the max bid based on the original not discounted min bid
-    let original_min_bid = calculate_min_bid(&deps, &env, None).unwrap();
-    let max_bid = calculate_max_bid(&deps, original_min_bid).unwrap();
-    Ok(BidRangeResponse { min_bid, max_bid })
+    let original_min_bid =
+        calculate_min_bid(&deps, &env, None).map_err(|e| StdError::generic_err(e.to_string()))?;
+    let max_bid = calculate_max_bid(&deps, original_min_bid)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let max_player_exposure =
+        Some(game_config.max_player_exposure).filter(|exposure| !exposure.is_zero());
+    let remaining_exposure = match (&max_player_exposure, &address) {
+        (Some(max_player_exposure), Some(address)) => {
+            let exposure = get_player_exposure(deps.storage, &deps.api.addr_validate(address)?)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            Some(max_player_exposure.saturating_sub(exposure))
+        }
+        _ => None,
+    };
+    Ok(BidRangeResponse {
+        min_bid,
+        max_bid,
+        max_bid_multiplier: game_config.max_bid_multiplier,
+        max_player_exposure,
+        remaining_exposure,
+        discounts,
+        voting_power_discounts,
+    })
+}
+
+pub fn query_bid_curve(deps: Deps, env: Env, epochs: Option<u32>) -> StdResult<BidCurveResponse> {
+    let epochs = epochs.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    let elapsed_time = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(game_state.start_time);
+    let current_epoch_count = elapsed_time / game_config.game_duration_epoch;
+
+    let points = (current_epoch_count..current_epoch_count + epochs)
+        .map(|epoch| {
+            let min_bid = calculate_curve_min_bid(
+                deps.storage,
+                &game_config,
+                epoch,
+                game_state.extend_count,
+            )?;
+            Ok(BidCurvePoint {
+                epoch,
+                start_time: game_state.start_time + epoch * game_config.game_duration_epoch,
+                min_bid,
+                max_bid: calculate_max_bid(&deps, min_bid)?,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok(BidCurveResponse {
+        curve: game_config.bid_curve,
+        points,
+    })
 }
 
 pub fn query_pot_state(deps: Deps, pot_id: u8) -> StdResult<PotStateResponse> {
@@ -118,7 +201,7 @@ pub fn query_raffle(deps: Deps) -> StdResult<RaffleResponse> {
 
 pub fn query_raffle_winner(deps: Deps) -> StdResult<RaffleWinnerResponse> {
     let winning_pots = query_winning_pots(deps)?;
-    let raffle_winner = get_raffle_winner(deps.storage, &winning_pots.pots).unwrap();
+    let raffle_winner = get_raffle_winner(&deps, &winning_pots.pots).unwrap();
 
     Ok(RaffleWinnerResponse { raffle_winner })
 }
@@ -131,3 +214,288 @@ pub fn query_raffle_denom_split(deps: Deps) -> StdResult<RaffleDenomSplitRespons
         prize_to_treasury,
     })
 }
+
+pub fn query_raffle_sponsors(
+    deps: Deps,
+    round: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RaffleSponsorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let sponsors = RAFFLE_SPONSORS
+        .prefix(round)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, sponsor)| sponsor))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RaffleSponsorsResponse { sponsors })
+}
+
+pub fn query_unclaimed_nfts(
+    deps: Deps,
+    recipient: Option<String>,
+) -> StdResult<UnclaimedNftsResponse> {
+    let nfts = UNCLAIMED_NFTS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .filter_map(Result::ok)
+        .map(|(_, unclaimed_nft)| unclaimed_nft)
+        .filter(|unclaimed_nft| {
+            recipient
+                .as_ref()
+                .is_none_or(|recipient| unclaimed_nft.recipient == *recipient)
+        })
+        .collect();
+
+    Ok(UnclaimedNftsResponse { nfts })
+}
+
+pub fn query_recovery_log(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RecoveryLogResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let recoveries = RECOVERY_LOG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, recovery)| recovery))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RecoveryLogResponse { recoveries })
+}
+
+pub fn query_nft_registrations(deps: Deps, address: String) -> StdResult<NftRegistrationsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    let registrations = NFT_REGISTRATIONS
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, registration)| registration))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(NftRegistrationsResponse { registrations })
+}
+
+pub fn query_player_limits(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<PlayerLimitsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    let reallocations = PLAYER_REALLOCATIONS
+        .may_load(deps.storage, address.to_string())?
+        .unwrap_or_default();
+    let (reallocations_limit, fee_reallocation) =
+        calculate_player_limits(&deps, &env, &address, true)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok(PlayerLimitsResponse {
+        reallocations,
+        reallocations_limit,
+        fee_reallocation,
+    })
+}
+
+pub fn query_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+    let ownership = OWNERSHIP.load(deps.storage)?;
+    let operators = OPERATORS.load(deps.storage)?;
+
+    Ok(OwnershipResponse {
+        owner: ownership.owner,
+        pending_owner: ownership.pending_owner,
+        operators,
+    })
+}
+
+pub fn query_emergency_state(deps: Deps) -> StdResult<EmergencyStateResponse> {
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    let round_cancelled = ROUND_CANCELLED.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(EmergencyStateResponse {
+        paused,
+        round_cancelled,
+    })
+}
+
+pub fn query_refund_claim(deps: Deps, address: String) -> StdResult<RefundClaimResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let amount = REFUND_CLAIMS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+
+    Ok(RefundClaimResponse { amount })
+}
+
+pub fn query_pending_config(deps: Deps) -> StdResult<PendingConfigResponse> {
+    let game_config = GAME_CONFIG.load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    match PENDING_CONFIG.may_load(deps.storage)? {
+        Some(pending_config) => Ok(PendingConfigResponse {
+            changed_fields: changed_config_fields(&game_config, &pending_config),
+            config: Some(pending_config),
+            activates_at_round: Some(game_state.round_count + 1),
+            activates_after: Some(game_state.end_time),
+        }),
+        None => Ok(PendingConfigResponse {
+            config: None,
+            changed_fields: vec![],
+            activates_at_round: None,
+            activates_after: None,
+        }),
+    }
+}
+
+pub fn query_pending_proposals(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, proposal)| proposal))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PendingProposalsResponse { proposals })
+}
+
+pub fn query_votes(deps: Deps) -> StdResult<VotesResponse> {
+    let game_state = GAME_STATE.load(deps.storage)?;
+    let tally = tally_votes(deps.storage, game_state.round_count)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(VotesResponse {
+        round_count: game_state.round_count,
+        tally,
+    })
+}
+
+pub fn query_player_vote(deps: Deps, address: String) -> StdResult<PlayerVoteResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let option_id = PLAYER_VOTES.may_load(deps.storage, address.to_string())?;
+
+    Ok(PlayerVoteResponse { option_id })
+}
+
+pub fn query_vote_tally(deps: Deps, round: u64) -> StdResult<VoteTallyResponse> {
+    let tally = VOTE_TALLIES.may_load(deps.storage, round)?;
+
+    Ok(VoteTallyResponse { tally })
+}
+
+pub fn query_tables(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TablesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tables = TABLES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, table)| table))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TablesResponse { tables })
+}
+
+pub fn query_season(deps: Deps) -> StdResult<SeasonResponse> {
+    let season = SEASON.may_load(deps.storage)?;
+    let game_state = GAME_STATE.load(deps.storage)?;
+
+    Ok(SeasonResponse {
+        season,
+        round_count: game_state.round_count,
+    })
+}
+
+pub fn query_season_standings(
+    deps: Deps,
+    limit: Option<u32>,
+) -> StdResult<SeasonStandingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let season = SEASON.may_load(deps.storage)?;
+
+    Ok(SeasonStandingsResponse {
+        season_id: season.as_ref().map(|season| season.id),
+        standings: season
+            .map(|season| season.leaders.into_iter().take(limit).collect())
+            .unwrap_or_default(),
+    })
+}
+
+pub fn query_jackpot(deps: Deps) -> StdResult<JackpotResponse> {
+    let jackpot = JACKPOT.may_load(deps.storage)?;
+    let trigger = get_winning_pots(deps.storage)
+        .and_then(|winning_pots| get_jackpot_trigger(deps.storage, &winning_pots))
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok(JackpotResponse { jackpot, trigger })
+}
+
+pub fn query_participation(deps: Deps, address: String) -> StdResult<ParticipationResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let gate = PARTICIPATION_GATE
+        .may_load(deps.storage)?
+        .unwrap_or(ParticipationGate::Open {});
+
+    Ok(ParticipationResponse {
+        gate,
+        blocked: BLOCKLIST.has(deps.storage, &address),
+        allowed: validate_participation(&deps, &address).is_ok(),
+    })
+}
+
+pub fn query_address_list(
+    deps: Deps,
+    list: &Map<&Addr, Empty>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AddressListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = list
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AddressListResponse { addresses })
+}
+
+pub fn query_auto_compound(deps: Deps, address: String) -> StdResult<AutoCompoundResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    Ok(AutoCompoundResponse {
+        pot_strategy: AUTO_COMPOUND.may_load(deps.storage, &address)?,
+        claimable: CLAIMABLE_BALANCES
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default(),
+    })
+}
+
+pub fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    Ok(BalanceResponse {
+        balance: BALANCES
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default(),
+        balance_payouts: BALANCE_PAYOUTS.has(deps.storage, &address),
+    })
+}
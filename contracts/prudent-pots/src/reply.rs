@@ -1,14 +1,42 @@
-use cosmwasm_std::{attr, Response, SubMsgResult};
+use cosmwasm_std::{attr, DepsMut, Env, Response, SubMsgResult};
 
-use crate::ContractError;
+use crate::{
+    state::{PENDING_NFT_TRANSFERS, UNCLAIMED_NFTS},
+    ContractError,
+};
 
-// This reply function is called by reply_always. This should only do nothing, or throw an error, foreach NFT send during game_end workflow to the raflle winner.
-pub fn transfer_nft_reply(msg: SubMsgResult) -> Result<Response, ContractError> {
-    match msg.into() {
+// This reply function is called by reply_always for the NFT sent to the raffle winner during game_end.
+// On failure the NFT is escrowed instead of reverting the whole game_end, so the winner can claim it later.
+pub fn transfer_nft_to_winner_reply(
+    deps: DepsMut,
+    env: Env,
+    round_count: u64,
+    msg: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let mut unclaimed_nft = PENDING_NFT_TRANSFERS.load(deps.storage, round_count)?;
+    PENDING_NFT_TRANSFERS.remove(deps.storage, round_count);
+
+    match msg.into_result() {
         Ok(_data) => Ok(Response::new().add_attributes(vec![
             attr("method", "reply"),
-            attr("action", "transfer_nft_reply"),
+            attr("action", "transfer_nft_to_winner_reply"),
         ])),
-        _ => Err(ContractError::Cw721TokenNotReceived {}),
+        Err(err) => {
+            unclaimed_nft.escrow_time = env.block.time.seconds();
+            UNCLAIMED_NFTS.save(
+                deps.storage,
+                (&unclaimed_nft.cw721_addr, &unclaimed_nft.token_id),
+                &unclaimed_nft,
+            )?;
+
+            Ok(Response::new().add_attributes(vec![
+                attr("method", "reply"),
+                attr("action", "transfer_nft_to_winner_reply"),
+                attr("escrowed_nft_addr", unclaimed_nft.cw721_addr),
+                attr("escrowed_nft_id", unclaimed_nft.token_id),
+                attr("escrowed_nft_recipient", unclaimed_nft.recipient),
+                attr("error", err),
+            ]))
+        }
     }
 }
@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
@@ -17,8 +17,9 @@ pub struct OldGameConfig {
     pub reallocations_limit: u64,
 }
 
+// Game config layout up to v2.0.0, before the per-collection discounts and the later config fields
 #[cw_serde]
-pub struct GameConfig {
+pub struct GameConfigV2 {
     pub fee: u64,
     pub fee_reallocation: u64,
     pub fee_address: Addr,
@@ -33,6 +34,242 @@ pub struct GameConfig {
     pub reallocations_limit: u64,
 }
 
+// Game config layout of v2.1.0, before the timelock, the bid curves and the bid caps
+#[cw_serde]
+pub struct GameConfigV2_1 {
+    pub fee: u64,
+    pub fee_reallocation: u64,
+    pub fee_address: Addr,
+    pub game_denom: String,
+    pub game_cw721_collections: Vec<Cw721Collection>,
+    pub game_duration: u64,
+    pub game_duration_epoch: u64,
+    pub game_extend: u64,
+    pub game_end_threshold: u64,
+    pub min_pot_initial_allocation: Uint128,
+    pub decay_factor: Decimal,
+    pub reallocations_limit: u64,
+    pub nft_claim_timeout: u64,
+    pub raffle_expiry_rounds: u64,
+    pub min_bid_floor: Uint128,
+    pub voting_power_sources: Vec<VotingPowerSource>,
+}
+
+#[cw_serde]
+pub struct Cw721Collection {
+    pub addr: Addr,
+    pub discount_per_token: Decimal, // i.e. 0.05 as 5% off the min bid for each token held
+    pub max_tokens_counted: u32, // tokens held above this amount do not grant any further discount
+    pub trait_multipliers: Vec<TraitMultiplier>, // tokens matching a trait count as weight tokens, i.e. Rarity=Legendary as 3
+    pub extra_reallocations_per_token: u64, // reallocations granted above reallocations_limit for each token held
+    pub max_extra_reallocations: u64, // cap of the extra reallocations granted by this collection
+    pub holder_fee_reallocation: Option<u64>, // lower fee_reallocation for holders of at least one token
+}
+
+#[cw_serde]
+pub struct TraitMultiplier {
+    pub trait_type: String,
+    pub value: String,
+    pub weight: u32,
+}
+
+#[cw_serde]
+pub enum VotingPowerSourceKind {
+    DaoVotingModule, // queried via VotingPowerAtHeight
+    Cw20Stake,       // queried via StakedBalanceAtHeight
+    Cw4Group,        // queried via Member
+}
+
+#[cw_serde]
+pub struct VotingPowerSource {
+    pub addr: Addr,
+    pub kind: VotingPowerSourceKind,
+    pub tiers: Vec<Uint128>, // ascending thresholds, i.e. [1000, 5000] grants one step from 1000 and two steps from 5000
+}
+
+/// Curve of the min bid over the round, its position being the epochs elapsed times the extensions plus one.
+#[cw_serde]
+pub enum BidCurve {
+    Linear {}, // grows by decay_factor at each position
+    Exponential {
+        growth: Decimal, // i.e. 0.1 compounds 10% at each position
+    },
+    Stepwise {
+        multipliers: Vec<Decimal>, // applied by position, the last one holds for the rest of the round
+    },
+    PotValue {
+        ratio: Decimal, // i.e. 0.05 as 5% of the total pots value, never below min_pot_initial_allocation
+    },
+}
+
+impl Default for BidCurve {
+    fn default() -> Self {
+        BidCurve::Linear {}
+    }
+}
+
+#[cw_serde]
+pub struct GameConfig {
+    pub fee: u64,
+    pub fee_reallocation: u64,
+    pub fee_address: Addr,
+    pub game_denom: String,
+    pub game_cw721_collections: Vec<Cw721Collection>, // these are the cw721 collections that grant minBid discount eligibility
+    pub game_duration: u64,
+    pub game_duration_epoch: u64, // i.e., 3600 for 1 hour intervals
+    pub game_extend: u64,
+    pub game_end_threshold: u64,
+    pub min_pot_initial_allocation: Uint128, // i.e. 1000000 for 1 $OSMO, which is also used as starting bet amount.
+    pub decay_factor: Decimal,               // i.e. 0.05 as 5%
+    pub reallocations_limit: u64,
+    pub nft_claim_timeout: u64, // i.e. 86400 seconds before the admin can reclaim an unclaimed raffle NFT
+    pub raffle_expiry_rounds: u64, // i.e. 10 rounds an unwon raffle prize rolls over before the admin can recover it
+    pub min_bid_floor: Uint128, // the min bid is never discounted below this amount by NFT holdings or voting power
+    pub voting_power_sources: Vec<VotingPowerSource>, // stakers or members reaching each tier get one decay_factor discount step
+    pub timelock_delay: u64, // i.e. 86400 seconds a config proposal has to wait before it can be executed
+    #[serde(default)] // configs stored before the curves were introduced keep the linear one
+    pub bid_curve: BidCurve,
+    #[serde(default = "default_max_bid_multiplier")]
+    pub max_bid_multiplier: Decimal, // i.e. 2 to cap bids at twice the average pot, or twice the min bid when higher
+    #[serde(default)]
+    pub max_player_exposure: Uint128, // total a player can allocate across the pots in a round, zero for no cap
+}
+
+pub fn default_max_bid_multiplier() -> Decimal {
+    Decimal::from_ratio(2u128, 1u128)
+}
+
+// Config fields that can only be changed through a timelocked proposal, for the players trust
+#[cw_serde]
+pub struct TimelockedConfig {
+    pub fee: Option<u64>,
+    pub fee_address: Option<Addr>,
+    pub game_denom: Option<String>,
+    pub reallocations_limit: Option<u64>,
+    pub timelock_delay: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ConfigProposal {
+    pub id: u64,
+    pub changes: TimelockedConfig,
+    pub proposed_at: u64,
+    pub eta: u64, // the proposal can be executed from this timestamp on
+}
+
+// Next round settings the players of the current round can vote on, defined by the owner
+#[cw_serde]
+pub struct VoteOption {
+    pub label: String,
+    pub game_duration: Option<u64>,
+    pub reallocations_limit: Option<u64>,
+}
+
+#[cw_serde]
+pub struct VoteTally {
+    pub round_count: u64,
+    pub options: Vec<VoteOption>,
+    pub weights: Vec<Uint128>, // total allocation of the players voting for each option, by option index
+    pub winning_option: Option<u32>,
+    pub applied: bool, // false if the winning option would have resulted in an invalid config for the next round
+}
+
+#[cw_serde]
+pub enum SeasonPointsMode {
+    Winnings {},                     // one point per token won in the round
+    Placements { points: Vec<u64> }, // fixed points for the round top winners, i.e. [10, 5, 3]
+}
+
+#[cw_serde]
+pub enum SeasonPoolSource {
+    Fee {},      // a slice of the total fee taken at each game end
+    Rollover {}, // a slice of the tokens rolling over to the next round pots
+}
+
+#[cw_serde]
+pub struct SeasonConfig {
+    pub rounds: u64,
+    pub points_mode: SeasonPointsMode,
+    pub pool_source: SeasonPoolSource,
+    pub pool_share: u64,         // i.e. 20 as 20% of the pool source
+    pub payout_shares: Vec<u64>, // pool percentage paid to each leaderboard position, i.e. [50, 30, 20]
+}
+
+#[cw_serde]
+pub struct SeasonStanding {
+    pub player: String,
+    pub points: Uint128,
+}
+
+#[cw_serde]
+pub struct SeasonPayout {
+    pub player: String,
+    pub points: Uint128,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct Season {
+    pub id: u64,
+    pub config: SeasonConfig,
+    pub start_round: u64,
+    pub end_round: u64, // the last round counted, the pool is paid out at its game end
+    pub denom: String,  // the game denom at the season start, the pool is paid out in it
+    pub pool: Uint128,  // reserved out of the pots until paid out
+    pub leaders: Vec<SeasonStanding>, // top of the leaderboard, kept up to date as points are awarded
+    pub payouts: Vec<SeasonPayout>,
+    pub finished: bool,
+}
+
+#[cw_serde]
+pub enum JackpotSource {
+    ReallocationFee {}, // a slice of the reallocation fee pool sent to the treasury at each game end
+    WinningFee {},      // a slice of the total fee taken on the winning pots at each game end
+}
+
+#[cw_serde]
+pub enum JackpotTrigger {
+    AllPotsWinning {},
+    MedianIsAverage {}, // the median pot holds exactly the average of the five pots
+}
+
+#[cw_serde]
+pub struct JackpotConfig {
+    pub source: JackpotSource,
+    pub share: u64, // i.e. 20 as 20% of the jackpot source
+    pub triggers: Vec<JackpotTrigger>,
+}
+
+#[cw_serde]
+pub struct JackpotPayout {
+    pub round_count: u64,
+    pub trigger: JackpotTrigger,
+    pub amount: Uint128,
+    pub winners: u32,
+}
+
+#[cw_serde]
+pub struct Jackpot {
+    pub config: JackpotConfig,
+    pub denom: String, // the game denom when the jackpot was set, the pool is paid out in it
+    pub pool: Uint128, // reserved out of the pots until paid out
+    pub last_payout: Option<JackpotPayout>,
+}
+
+#[cw_serde]
+pub enum ParticipationGate {
+    Open {},
+    Allowlist {}, // only the allowlisted addresses can play, i.e. for private tournaments
+    Cw4Group { addr: Addr }, // only the current members of the cw4 group can play, i.e. for partner tournaments
+}
+
+#[cw_serde]
+pub enum AutoCompoundStrategy {
+    FixedPot { pot_id: u8 },
+    LastPots {}, // the pots the player allocated to in the ended round, the winnings split evenly
+    LowestPot {}, // the pot with the lowest amount once the next round pots are funded
+}
+
 #[cw_serde]
 pub struct Raffle {
     pub cw721_token_id: Option<String>, // the tokenId of the raffle nft to be won
@@ -40,6 +277,75 @@ pub struct Raffle {
     pub denom_amount: Uint128, // this is limited to the same game_config.game_denom for now
 }
 
+#[cw_serde]
+pub struct RaffleSponsor {
+    pub sponsor: Addr,
+    pub denom_amount: Uint128, // game_config.game_denom amount attached to the raffle, zero for NFT sponsorships
+    pub cw721_addr: Option<String>,
+    pub cw721_token_id: Option<String>,
+    pub message: Option<String>, // optional attribution text shown alongside the sponsorship
+}
+
+/// Prizes attached to the raffle by the sponsors of a round, so an expired raffle recovery leaves them in place.
+#[cw_serde]
+#[derive(Default)]
+pub struct RaffleRoundSponsorship {
+    pub denom_amount: Uint128,
+    pub cw721_token: Option<Cw721Token>,
+}
+
+#[cw_serde]
+pub struct UnclaimedNft {
+    pub cw721_addr: String,
+    pub token_id: String,
+    pub recipient: Addr, // the raffle winner the transfer failed for
+    pub round_count: u64,
+    pub escrow_time: u64, // used to compute when the admin can reclaim it
+}
+
+#[cw_serde]
+pub struct Cw721Token {
+    pub cw721_addr: String,
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct AssetRecovery {
+    pub id: u64,
+    pub recipient: Addr,
+    pub round_count: u64,
+    pub time: u64,
+    pub coins: Vec<Coin>,
+    pub cw721_tokens: Vec<Cw721Token>,
+    pub expired_raffle: bool, // true if the coins and tokens include an expired raffle prize
+}
+
+#[cw_serde]
+pub struct NftRegistration {
+    pub collection: Addr,
+    pub tokens: Vec<RegisteredNft>,
+    pub round_count: u64, // registrations only count for the round they have been made in
+    pub block_height: u64, // registrations only count from the next block, so NFTs cannot be borrowed for a single tx
+}
+
+#[cw_serde]
+pub struct RegisteredNft {
+    pub token_id: String,
+    pub weight: u32, // cached from the token metadata traits at registration, so min bid calculation does not query it
+}
+
+#[cw_serde]
+pub struct NftWeight {
+    pub weight: u32,
+    pub trait_multipliers: Vec<TraitMultiplier>, // the collection multipliers the weight has been computed with
+}
+
+#[cw_serde]
+pub struct Ownership {
+    pub owner: Option<Addr>,         // None once the ownership has been renounced
+    pub pending_owner: Option<Addr>, // set by the owner, until the new owner accepts the transfer
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct GameState {
@@ -60,17 +366,67 @@ pub struct PlayerAllocations {
     pub allocations: Vec<TokenAllocation>,
 }
 
+#[cw_serde]
+pub struct Table {
+    pub id: u64,
+    pub denoms: Vec<String>, // game denoms reserved by the table config and its pending config
+}
+
 #[cw_serde]
 pub struct FirstBidder {
     pub bidder: String,
     pub time: u64,
 }
 
-pub const GAME_CONFIG: Item<GameConfig> = Item::new("game_config_v2");
+// Global items, shared by all the tables
+pub const TABLES: Map<u64, Table> = Map::new("tables");
+pub const TABLE_COUNT: Item<u64> = Item::new("table_count");
+pub const OWNERSHIP: Item<Ownership> = Item::new("ownership");
+pub const OPERATORS: Item<Vec<Addr>> = Item::new("operators");
+
+// Table items, stored under the namespace of each table
+pub const TABLE_ID: Item<u64> = Item::new("table_id"); // unset for the default table
+pub const OLD_GAME_CONFIG: Item<OldGameConfig> = Item::new("game_config");
+pub const GAME_CONFIG_V2: Item<GameConfigV2> = Item::new("game_config_v2");
+pub const GAME_CONFIG_V2_1: Item<GameConfigV2_1> = Item::new("game_config_v3"); // same key, read before the layout upgrade
+pub const GAME_CONFIG: Item<GameConfig> = Item::new("game_config_v3");
+pub const PENDING_CONFIG: Item<GameConfig> = Item::new("pending_config");
+pub const PROPOSALS: Map<u64, ConfigProposal> = Map::new("proposals");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const VOTE_OPTIONS: Item<Vec<VoteOption>> = Item::new("vote_options");
+pub const PLAYER_VOTES: Map<String, u32> = Map::new("player_votes");
+pub const VOTE_TALLIES: Map<u64, VoteTally> = Map::new("vote_tallies");
+pub const JACKPOT: Item<Jackpot> = Item::new("jackpot");
+pub const SEASON: Item<Season> = Item::new("season"); // the current season, or the last finished one
+pub const SEASON_POINTS: Map<(u64, &str), Uint128> = Map::new("season_points");
+pub const PARTICIPATION_GATE: Item<ParticipationGate> = Item::new("participation_gate"); // open when unset
+pub const ALLOWLIST: Map<&Addr, Empty> = Map::new("allowlist");
+pub const BLOCKLIST: Map<&Addr, Empty> = Map::new("blocklist"); // checked whatever the gate is
+pub const PARTICIPATION_VERIFIED: Map<&Addr, Empty> = Map::new("participation_verified"); // players who passed the gate this round
+pub const AUTO_COMPOUND: Map<&Addr, AutoCompoundStrategy> = Map::new("auto_compound");
+pub const CLAIMABLE_BALANCES: Map<&Addr, Uint128> = Map::new("claimable_balances"); // winnings failing the auto-compound bid checks
+pub const CLAIMABLE_TOTAL: Item<Uint128> = Item::new("claimable_total");
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances"); // deposits and credited winnings, reserved out of the pots
+pub const BALANCES_TOTAL: Item<Uint128> = Item::new("balances_total");
+pub const BALANCE_PAYOUTS: Map<&Addr, Empty> = Map::new("balance_payouts"); // players whose winnings are credited to their balance
 pub const GAME_STATE: Item<GameState> = Item::new("game_state");
 pub const POT_STATES: Map<u8, TokenAllocation> = Map::new("pot_states");
 pub const PLAYER_ALLOCATIONS: Map<String, Vec<TokenAllocation>> = Map::new("player_allocations");
 pub const PLAYER_REALLOCATIONS: Map<String, u64> = Map::new("player_reallocations");
+pub const PLAYER_REALLOCATION_FEES: Map<String, Uint128> = Map::new("player_reallocation_fees");
+pub const PAUSED: Item<bool> = Item::new("paused");
+pub const ROUND_CANCELLED: Item<bool> = Item::new("round_cancelled");
+pub const REFUND_CLAIMS: Map<&Addr, Uint128> = Map::new("refund_claims"); // refunds of a cancelled round still to be claimed once unpaused
+pub const REFUND_CLAIMS_TOTAL: Item<Uint128> = Item::new("refund_claims_total");
 pub const REALLOCATION_FEE_POOL: Item<Uint128> = Item::new("reallocation_fee_pool");
 pub const RAFFLE: Item<Raffle> = Item::new("raffle");
 pub const FIRST_BIDDER: Map<u8, FirstBidder> = Map::new("first_bidder");
+pub const RAFFLE_SPONSORS: Map<(u64, u64), RaffleSponsor> = Map::new("raffle_sponsors"); // (round_count, index)
+pub const RAFFLE_ROUND_SPONSORSHIPS: Map<u64, RaffleRoundSponsorship> =
+    Map::new("raffle_round_sponsorships");
+pub const PENDING_NFT_TRANSFERS: Map<u64, UnclaimedNft> = Map::new("pending_nft_transfers"); // round_count of the raffle
+pub const UNCLAIMED_NFTS: Map<(&str, &str), UnclaimedNft> = Map::new("unclaimed_nfts");
+pub const RAFFLE_UNWON_ROUNDS: Item<u64> = Item::new("raffle_unwon_rounds");
+pub const RECOVERY_LOG: Map<u64, AssetRecovery> = Map::new("recovery_log");
+pub const NFT_REGISTRATIONS: Map<(&Addr, &Addr), NftRegistration> = Map::new("nft_registrations");
+pub const NFT_WEIGHTS: Map<(&Addr, &str), NftWeight> = Map::new("nft_weights"); // (collection, token_id)
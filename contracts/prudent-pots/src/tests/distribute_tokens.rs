@@ -67,7 +67,7 @@ mod tests {
         let messages =
             get_distribution_send_msgs(&deps.as_ref(), &winning_pots, total_losing_tokens)
                 .unwrap()
-                .0;
+                .msgs;
 
         // Assertions
         let config = GAME_CONFIG.load(deps.as_ref().storage).unwrap();
@@ -5,7 +5,9 @@ pub mod tests {
     use crate::{
         contract::instantiate,
         msg::InstantiateMsg,
-        state::{GameConfig, TokenAllocation, PLAYER_ALLOCATIONS, POT_STATES},
+        state::{
+            BidCurve, Cw721Collection, GameConfig, TokenAllocation, PLAYER_ALLOCATIONS, POT_STATES,
+        },
     };
     use cosmwasm_std::{Addr, Decimal, DepsMut, Env, MessageInfo, StdError, Storage, Uint128};
 
@@ -27,10 +29,26 @@ pub mod tests {
             fee_reallocation: 5,
             fee_address: Addr::unchecked("fee_address"),
             game_denom: "token".to_string(),
-            game_cw721_addrs: vec![Addr::unchecked("nft")],
+            game_cw721_collections: vec![Cw721Collection {
+                addr: Addr::unchecked("nft"),
+                discount_per_token: Decimal::from_str("0.05").unwrap(),
+                max_tokens_counted: 3,
+                trait_multipliers: vec![],
+                extra_reallocations_per_token: 0,
+                max_extra_reallocations: 0,
+                holder_fee_reallocation: None,
+            }],
             min_pot_initial_allocation: Uint128::new(200u128),
             decay_factor: Decimal::from_str("0.05").unwrap(),
             reallocations_limit: 10,
+            nft_claim_timeout: 86400,
+            raffle_expiry_rounds: 10,
+            min_bid_floor: Uint128::new(100u128),
+            voting_power_sources: vec![],
+            timelock_delay: 86400,
+            bid_curve: BidCurve::Linear {},
+            max_bid_multiplier: Decimal::from_ratio(2u128, 1u128),
+            max_player_exposure: Uint128::zero(),
         };
 
         // Perform instantiation first
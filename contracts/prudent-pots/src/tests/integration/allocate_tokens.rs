@@ -1,11 +1,19 @@
-use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Decimal, Uint128};
+use cw_multi_test::{next_block, App};
 
-use crate::msg::{PotStateResponse, QueryMsg};
-use crate::state::TokenAllocation;
+use crate::msg::{
+    BidRangeResponse, CollectionDiscount, ExecuteMsg, PotStateResponse, QueryMsg, UpdateGameConfig,
+    VotingPowerDiscount,
+};
+use crate::state::{TokenAllocation, VotingPowerSource, VotingPowerSourceKind};
 use crate::tests::integration::fixtures::{
-    default_with_balances, increase_app_time, DENOM_GAME, GAME_EXTEND,
+    default_cw721_collection, default_with_balances, increase_app_time,
+    instantiate_voting_power_mock, mock_cw20_stake_contract, mock_cw4_group_contract,
+    mock_dao_voting_module_contract, DENOM_GAME, GAME_EXTEND, MIN_BID_FLOOR,
+};
+use crate::tests::integration::helpers::{
+    allocate_tokens, mint_nfts, register_nfts, update_config,
 };
-use crate::tests::integration::helpers::allocate_tokens;
 use crate::ContractError;
 
 #[test]
@@ -23,7 +31,13 @@ fn test_allocate_tokens_works() {
     // Query states
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 1 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 1,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -58,7 +72,13 @@ fn test_allocate_tokens_fails() {
     // Query states to ensure no changes in the pot state after the failed allocation
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 1 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 1,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -95,7 +115,13 @@ fn test_allocate_tokens_min_bet_works() {
     // Query states
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 1 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 1,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -112,7 +138,13 @@ fn test_allocate_tokens_min_bet_works() {
     // Query states
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 2 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 2,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -141,7 +173,13 @@ fn test_allocate_tokens_min_bet_works() {
     // Query states
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 3 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 3,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -170,7 +208,13 @@ fn test_allocate_tokens_min_bet_works() {
     allocate_tokens(&mut app, &pp_addr, &info, 3).unwrap();
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 3 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 3,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -200,7 +244,13 @@ fn test_allocate_tokens_min_bet_works() {
     allocate_tokens(&mut app, &pp_addr, &info, 4).unwrap();
     let pots_state: PotStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotState { pot_id: 4 })
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 4,
+            },
+        )
         .unwrap();
     let expected_pots_state = PotStateResponse {
         pot: TokenAllocation {
@@ -211,4 +261,299 @@ fn test_allocate_tokens_min_bet_works() {
     assert_eq!(pots_state, expected_pots_state);
 }
 
-// TODO_FUTURE: Test NFT allocation bid discount for Raffle NFT contract hodlers
+#[test]
+fn test_allocate_tokens_nft_discount_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Register up to the collection max_tokens_counted, the discount applies from the next block
+    mint_nfts(&mut app, &cw721_addr, 1, 5, Addr::unchecked("user1"));
+    let info = mock_info("user1", &[]);
+    register_nfts(
+        &mut app,
+        &pp_addr,
+        &info,
+        &cw721_addr,
+        vec!["1".to_string(), "2".to_string(), "3".to_string()],
+    )
+    .unwrap();
+    app.update_block(next_block);
+
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user1".to_string()),
+            },
+        )
+        .unwrap();
+    // 1_000_000 discounted by 5% three times is 857_375, which is raised to the min bid floor
+    assert_eq!(bid_range.min_bid, Uint128::new(MIN_BID_FLOOR));
+    assert_eq!(
+        bid_range.discounts,
+        vec![CollectionDiscount {
+            addr: cw721_addr.clone(),
+            tokens_counted: 3,
+            discount: Uint128::new(142_625u128),
+        }]
+    );
+
+    // Players without NFTs get no discount
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user2".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.min_bid, Uint128::new(1_000_000u128));
+    assert!(bid_range.discounts.is_empty());
+
+    // Bidding below the floor fails
+    let info = mock_info("user1", &coins(MIN_BID_FLOOR - 1, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap_err();
+    if let Some(ContractError::BidOutOfRange { min, max }) = res.downcast_ref::<ContractError>() {
+        assert_eq!(*min, Uint128::new(MIN_BID_FLOOR));
+        assert_eq!(*max, Uint128::new(2000000u128));
+    } else {
+        panic!("Expected ContractError::BidOutOfRange");
+    }
+
+    // Bidding the discounted min bid works
+    let info = mock_info("user1", &coins(MIN_BID_FLOOR, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+}
+
+#[test]
+fn test_allocate_tokens_voting_power_discount_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Configure a source of each kind, reaching each tier grants one decay_factor discount step
+    let dao_voting_addr = instantiate_voting_power_mock(
+        &mut app,
+        mock_dao_voting_module_contract(),
+        vec![("user1", 1_500)],
+    );
+    let cw20_stake_addr =
+        instantiate_voting_power_mock(&mut app, mock_cw20_stake_contract(), vec![("user1", 999)]);
+    let cw4_group_addr =
+        instantiate_voting_power_mock(&mut app, mock_cw4_group_contract(), vec![("user2", 10)]);
+    let tiers = vec![Uint128::new(1_000u128), Uint128::new(5_000u128)];
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: Some(vec![
+                    VotingPowerSource {
+                        addr: dao_voting_addr.clone(),
+                        kind: VotingPowerSourceKind::DaoVotingModule,
+                        tiers: tiers.clone(),
+                    },
+                    VotingPowerSource {
+                        addr: cw20_stake_addr,
+                        kind: VotingPowerSourceKind::Cw20Stake,
+                        tiers,
+                    },
+                    VotingPowerSource {
+                        addr: cw4_group_addr.clone(),
+                        kind: VotingPowerSourceKind::Cw4Group,
+                        tiers: vec![Uint128::new(1u128), Uint128::new(10u128)],
+                    },
+                ]),
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    // user1 reaches the first dao voting tier only, 1_000_000 is discounted by 5% once
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user1".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.min_bid, Uint128::new(950_000u128));
+    assert_eq!(
+        bid_range.voting_power_discounts,
+        vec![VotingPowerDiscount {
+            addr: dao_voting_addr,
+            voting_power: Uint128::new(1_500u128),
+            steps: 1,
+            discount: Uint128::new(50_000u128),
+        }]
+    );
+
+    // user2 reaches both cw4 group tiers, 1_000_000 is discounted by 5% twice
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            pp_addr.clone(),
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user2".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.min_bid, Uint128::new(902_500u128));
+    assert_eq!(bid_range.voting_power_discounts[0].addr, cw4_group_addr);
+    assert_eq!(bid_range.voting_power_discounts[0].steps, 2);
+
+    let info = mock_info("user2", &coins(902_499, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap_err();
+    if let Some(ContractError::BidOutOfRange { min, max }) = res.downcast_ref::<ContractError>() {
+        assert_eq!(*min, Uint128::new(902_500u128));
+        assert_eq!(*max, Uint128::new(2000000u128));
+    } else {
+        panic!("Expected ContractError::BidOutOfRange");
+    }
+
+    let info = mock_info("user2", &coins(902_500, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+
+    // Sources with tiers not in ascending order are rejected
+    let res = update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: Some(vec![VotingPowerSource {
+                    addr: cw4_group_addr,
+                    kind: VotingPowerSourceKind::Cw4Group,
+                    tiers: vec![Uint128::new(10u128), Uint128::new(1u128)],
+                }]),
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "voting_power_sources"
+    ));
+}
+
+#[test]
+fn test_allocate_tokens_exposure_cap_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Nobody allocated yet, so the limits apply to the current round right away
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![default_cw721_collection(&cw721_addr)],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: None,
+                bid_curve: None,
+                max_bid_multiplier: Some(Decimal::from_ratio(3u128, 1u128)),
+                max_player_exposure: Some(Uint128::new(3_000_000u128)),
+            }),
+        },
+    )
+    .unwrap();
+
+    // The max bid is three times the average pot, and the whole cap is left to the player
+    let query_bid_range = |app: &App| -> BidRangeResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &pp_addr,
+                &QueryMsg::BidRange {
+                    table_id: None,
+                    address: Some("user1".to_string()),
+                },
+            )
+            .unwrap()
+    };
+    let bid_range = query_bid_range(&app);
+    assert_eq!(bid_range.max_bid, Uint128::new(3_000_000u128));
+    assert_eq!(
+        bid_range.max_player_exposure,
+        Some(Uint128::new(3_000_000u128))
+    );
+    assert_eq!(
+        bid_range.remaining_exposure,
+        Some(Uint128::new(3_000_000u128))
+    );
+
+    let info = mock_info("user1", &coins(2_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+    assert_eq!(
+        query_bid_range(&app).remaining_exposure,
+        Some(Uint128::new(1_000_000u128))
+    );
+
+    // A bid within the max bid is still rejected above the cap across the pots
+    let info = mock_info("user1", &coins(1_500_000, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::ExposureCapExceeded { max }) if *max == Uint128::new(3_000_000u128)
+    ));
+
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 2).unwrap();
+}
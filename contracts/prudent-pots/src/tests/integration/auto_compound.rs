@@ -0,0 +1,169 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Uint128};
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{PlayerAllocationsResponse, PotStateResponse, QueryMsg},
+    state::{AutoCompoundStrategy, TokenAllocation},
+    tests::integration::{
+        fixtures::{default_with_balances, DENOM_GAME},
+        helpers::{
+            claim_balance, play_round, query_auto_compound, query_balance, set_auto_compound,
+        },
+    },
+    ContractError,
+};
+
+#[test]
+fn test_auto_compound_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    // Fund the next round pots enough for the winnings to be within the bid range
+    app.send_tokens(
+        Addr::unchecked("user1"),
+        pp_addr.clone(),
+        &coins(40_000_000, DENOM_GAME),
+    )
+    .unwrap();
+
+    let user5_info = mock_info("user5", &[]);
+    let res = set_auto_compound(
+        &mut app,
+        &pp_addr,
+        &user5_info,
+        Some(AutoCompoundStrategy::FixedPot { pot_id: 6 }),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidPot {})
+    ));
+    set_auto_compound(
+        &mut app,
+        &pp_addr,
+        &user5_info,
+        Some(AutoCompoundStrategy::LastPots {}),
+    )
+    .unwrap();
+    assert_eq!(
+        query_auto_compound(&app, &pp_addr, "user5").pot_strategy,
+        Some(AutoCompoundStrategy::LastPots {})
+    );
+
+    play_round(&mut app, &pp_addr);
+
+    // The winnings are not sent, but allocated to the same pot in the next round
+    let balance = app.wrap().query_balance("user5", DENOM_GAME).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100_000_000 - 2_419_200));
+    let player_allocations: PlayerAllocationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerAllocations {
+                table_id: None,
+                address: "user5".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        player_allocations.allocations,
+        vec![TokenAllocation {
+            pot_id: 5,
+            amount: Uint128::new(8_518_840),
+        }]
+    );
+    let pot_state: PotStateResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 1,
+            },
+        )
+        .unwrap();
+    let initial_pot_amount = pot_state.pot.amount;
+    let pot_state: PotStateResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 5,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pot_state.pot.amount,
+        initial_pot_amount + Uint128::new(8_518_840)
+    );
+    assert!(query_balance(&app, &pp_addr, "user5").balance.is_zero());
+
+    // Opting out sends the winnings again from the next game end
+    set_auto_compound(&mut app, &pp_addr, &user5_info, None).unwrap();
+    assert_eq!(
+        query_auto_compound(&app, &pp_addr, "user5").pot_strategy,
+        None
+    );
+}
+
+#[test]
+fn test_auto_compound_fallback_to_claimable_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let user5_info = mock_info("user5", &[]);
+    set_auto_compound(
+        &mut app,
+        &pp_addr,
+        &user5_info,
+        Some(AutoCompoundStrategy::LowestPot {}),
+    )
+    .unwrap();
+
+    // The winnings are above the next round max bid, so they are credited to the claimable balance
+    play_round(&mut app, &pp_addr);
+
+    let player_allocations: PlayerAllocationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerAllocations {
+                table_id: None,
+                address: "user5".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(player_allocations.allocations.is_empty());
+    assert_eq!(
+        query_auto_compound(&app, &pp_addr, "user5").claimable,
+        Uint128::new(8_518_840)
+    );
+
+    // Only the claimable balance owner can claim it, once
+    let res = claim_balance(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NoClaimableBalance {})
+    ));
+    claim_balance(&mut app, &pp_addr, &user5_info).unwrap();
+    let balance = app.wrap().query_balance("user5", DENOM_GAME).unwrap();
+    assert_eq!(
+        balance.amount,
+        Uint128::new(100_000_000 - 2_419_200 + 8_518_840)
+    );
+    assert!(query_auto_compound(&app, &pp_addr, "user5")
+        .claimable
+        .is_zero());
+    let res = claim_balance(&mut app, &pp_addr, &user5_info).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NoClaimableBalance {})
+    ));
+}
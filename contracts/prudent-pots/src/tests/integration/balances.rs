@@ -0,0 +1,120 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+
+use crate::{
+    msg::{PlayerAllocationsResponse, PotStateResponse, QueryMsg},
+    state::TokenAllocation,
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+        },
+        helpers::{
+            allocate_from_balance, allocate_tokens, deposit, game_end, query_balance,
+            set_balance_payouts, withdraw,
+        },
+    },
+    ContractError,
+};
+
+#[test]
+fn test_balances_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Deposit once and allocate from the balance, without sending funds
+    let user1_info = mock_info("user1", &[]);
+    deposit(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(5_000_000, DENOM_GAME)),
+    )
+    .unwrap();
+    assert_eq!(
+        query_balance(&app, &pp_addr, "user1").balance,
+        Uint128::new(5_000_000)
+    );
+
+    let res = allocate_from_balance(&mut app, &pp_addr, &user1_info, 1, 5_000_001).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientFunds {})
+    ));
+    allocate_from_balance(&mut app, &pp_addr, &user1_info, 1, 2_000_000).unwrap();
+    assert_eq!(
+        query_balance(&app, &pp_addr, "user1").balance,
+        Uint128::new(3_000_000)
+    );
+    let player_allocations: PlayerAllocationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerAllocations {
+                table_id: None,
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        player_allocations.allocations,
+        vec![TokenAllocation {
+            pot_id: 1,
+            amount: Uint128::new(2_000_000),
+        }]
+    );
+
+    // The winner opted in to balance payouts gets the winnings credited instead of sent
+    let user5_info = mock_info("user5", &[]);
+    set_balance_payouts(&mut app, &pp_addr, &user5_info, true).unwrap();
+    assert!(query_balance(&app, &pp_addr, "user5").balance_payouts);
+
+    let info_2 = mock_info("user2", &coins(1_400_001, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap();
+    let info_3 = mock_info("user3", &coins(1_680_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_3, 3).unwrap();
+    let info_4 = mock_info("user4", &coins(2_016_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_4, 4).unwrap();
+    let info_5 = mock_info("user5", &coins(2_419_200, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_5, 5).unwrap();
+
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+
+    assert_eq!(
+        query_balance(&app, &pp_addr, "user5").balance,
+        Uint128::new(8_518_840)
+    );
+    let balance = app.wrap().query_balance("user5", DENOM_GAME).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100_000_000 - 2_419_200));
+
+    // The balances are not part of the next round pots
+    let pot_state: PotStateResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 1,
+            },
+        )
+        .unwrap();
+    assert_eq!(pot_state.pot.amount, Uint128::new(1_109_600));
+
+    // Withdrawals are limited to the balance
+    let res = withdraw(&mut app, &pp_addr, &user1_info, 0).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidFunds {})
+    ));
+    let res = withdraw(&mut app, &pp_addr, &user1_info, 3_000_001).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InsufficientFunds {})
+    ));
+    withdraw(&mut app, &pp_addr, &user1_info, 3_000_000).unwrap();
+    assert!(query_balance(&app, &pp_addr, "user1").balance.is_zero());
+    let balance = app.wrap().query_balance("user1", DENOM_GAME).unwrap();
+    assert_eq!(balance.amount, Uint128::new(100_000_000 - 2_000_000));
+}
@@ -0,0 +1,205 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr};
+use cw721::OwnerOfResponse;
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{ExecuteMsg, QueryMsg, ReceiveNftMsg, UnclaimedNftsResponse, UpdateGameConfig},
+    tests::integration::{
+        fixtures::{
+            blocked_cw721_contract, default_cw721_collection, default_cw721_msg,
+            default_with_balances, increase_app_time, instantiate_cw721, ADMIN_ADDRESS,
+            BLOCKED_NFT_RECIPIENT, DENOM_GAME, GAME_DURATION, NFT_CLAIM_TIMEOUT,
+        },
+        helpers::{allocate_tokens, claim_nft, game_end, mint_nfts, send_nft, update_config},
+    },
+    ContractError,
+};
+
+#[test]
+fn test_claim_nft_escrowed_on_failed_transfer_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Instantiate a collection rejecting transfers to the raffle winner, and whitelist it
+    let blocked_cw721_id = app.store_code(blocked_cw721_contract());
+    let blocked_cw721_addr = instantiate_cw721(&mut app, blocked_cw721_id, default_cw721_msg());
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![
+                    default_cw721_collection(&cw721_addr),
+                    default_cw721_collection(&blocked_cw721_addr),
+                ],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: None,
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    // Set the raffle NFT from the blocked collection
+    mint_nfts(
+        &mut app,
+        &blocked_cw721_addr,
+        1,
+        1,
+        Addr::unchecked(ADMIN_ADDRESS),
+    );
+    send_nft(
+        &mut app,
+        &blocked_cw721_addr,
+        &Addr::unchecked(ADMIN_ADDRESS),
+        &pp_addr,
+        "1",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
+    )
+    .unwrap();
+
+    // The blocked recipient makes pot 2 (Even) the only winning pot, so it also wins the raffle
+    let info_1 = mock_info(BLOCKED_NFT_RECIPIENT, &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_1, 2).unwrap();
+
+    // Fund the contract so the next round can start with the minimum pot allocation
+    app.send_tokens(
+        Addr::unchecked(ADMIN_ADDRESS),
+        pp_addr.clone(),
+        &coins(5_000_000, DENOM_GAME),
+    )
+    .unwrap();
+
+    // Game end does not revert even if the NFT transfer to the winner fails
+    increase_app_time(&mut app, GAME_DURATION);
+    let user1_balance_before = app
+        .wrap()
+        .query_balance(BLOCKED_NFT_RECIPIENT, DENOM_GAME)
+        .unwrap();
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+    let user1_balance_after = app
+        .wrap()
+        .query_balance(BLOCKED_NFT_RECIPIENT, DENOM_GAME)
+        .unwrap();
+    assert!(user1_balance_after.amount > user1_balance_before.amount);
+
+    // The NFT is escrowed in the contract for the raffle winner
+    let unclaimed_nfts: UnclaimedNftsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::UnclaimedNfts {
+                table_id: None,
+                recipient: Some(BLOCKED_NFT_RECIPIENT.to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(unclaimed_nfts.nfts.len(), 1);
+    assert_eq!(
+        unclaimed_nfts.nfts[0].cw721_addr,
+        blocked_cw721_addr.to_string()
+    );
+    assert_eq!(unclaimed_nfts.nfts[0].token_id, "1");
+    assert_eq!(unclaimed_nfts.nfts[0].round_count, 1);
+
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &blocked_cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, pp_addr.to_string());
+
+    // Another player cannot claim it
+    let res = claim_nft(
+        &mut app,
+        &pp_addr,
+        &mock_info("user2", &[]),
+        &blocked_cw721_addr,
+        "1",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // The winner retrying still fails while the collection rejects the transfer, keeping the escrow
+    claim_nft(
+        &mut app,
+        &pp_addr,
+        &mock_info(BLOCKED_NFT_RECIPIENT, &[]),
+        &blocked_cw721_addr,
+        "1",
+    )
+    .unwrap_err();
+
+    // The admin cannot reclaim it before the timeout
+    let res = claim_nft(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        &blocked_cw721_addr,
+        "1",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NftClaimTimeoutNotReached { .. })
+    ));
+
+    // After the timeout the admin reclaims it
+    increase_app_time(&mut app, NFT_CLAIM_TIMEOUT);
+    claim_nft(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        &blocked_cw721_addr,
+        "1",
+    )
+    .unwrap();
+
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &blocked_cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, ADMIN_ADDRESS);
+
+    let unclaimed_nfts: UnclaimedNftsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::UnclaimedNfts {
+                table_id: None,
+                recipient: None,
+            },
+        )
+        .unwrap();
+    assert!(unclaimed_nfts.nfts.is_empty());
+}
@@ -1,31 +1,46 @@
 use std::str::FromStr;
 
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::mock_info;
-use cosmwasm_std::{coin, coins, Addr, BlockInfo, Coin, Decimal, Empty, Uint128};
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, Binary, BlockInfo, Coin, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128,
+};
 use cw_multi_test::{App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Map;
 
-use crate::msg::{ExecuteMsg, GameConfigResponse, InstantiateMsg, QueryMsg, UpdateGameConfig};
-use crate::state::{GameConfig, Raffle};
-use crate::tests::integration::helpers::{game_end, mint_nfts, update_config};
+use crate::helpers::nft::Extension;
+use crate::msg::{
+    ExecuteMsg, GameConfigResponse, InstantiateMsg, QueryMsg, ReceiveNftMsg, UpdateGameConfig,
+};
+use crate::state::{BidCurve, Cw721Collection, GameConfig, Raffle};
+use crate::tests::integration::helpers::{game_end, mint_nfts, send_nft, update_config};
 
 pub const DENOM_GAME: &str = "udenom";
 pub const GAME_DURATION: u64 = 3600;
 pub const GAME_EXTEND: u64 = 600;
+pub const NFT_CLAIM_TIMEOUT: u64 = 86400;
+pub const RAFFLE_EXPIRY_ROUNDS: u64 = 1;
+pub const TIMELOCK_DELAY: u64 = 86400;
+pub const MIN_BID_FLOOR: u128 = 900_000u128;
 
 pub const ADMIN_ADDRESS: &str = "admin_address";
 pub const ADMIN_BALANCE: u128 = 1_000_000_000_000u128;
 pub const MINTER_ADDRESS: &str = "merlin";
 
+pub const BLOCKED_NFT_RECIPIENT: &str = "user1";
+
 const CONTRACT_NAME: &str = "Magic Power";
 const SYMBOL: &str = "MGK";
 
-fn pp_contract() -> Box<dyn Contract<Empty>> {
+pub fn pp_contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         crate::contract::execute,
         crate::contract::instantiate,
         crate::contract::query,
     )
-    .with_reply(crate::contract::reply);
+    .with_reply(crate::contract::reply)
+    .with_migrate(crate::contract::migrate);
     Box::new(contract)
 }
 
@@ -38,7 +53,222 @@ fn cw721_contract() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
-fn instantiate_cw721(app: &mut App, code_id: u64, msg: cw721_base::InstantiateMsg) -> Addr {
+// cw721 contract rejecting any transfer to BLOCKED_NFT_RECIPIENT, used to simulate failing prize transfers
+fn blocked_cw721_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw721_base::ExecuteMsg<Option<Empty>, Empty>,
+) -> Result<Response, cw721_base::ContractError> {
+    if let cw721_base::ExecuteMsg::TransferNft { recipient, .. } = &msg {
+        if recipient == BLOCKED_NFT_RECIPIENT {
+            return Err(StdError::generic_err("recipient blocked").into());
+        }
+    }
+    cw721_base::entry::execute(deps, env, info, msg)
+}
+
+pub fn blocked_cw721_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        blocked_cw721_execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    Box::new(contract)
+}
+
+// cw721 contract using the metadata extension, used to test trait based discounts
+pub type MetadataCw721Contract<'a> = cw721_base::Cw721Contract<'a, Extension, Empty, Empty, Empty>;
+
+fn metadata_cw721_instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw721_base::InstantiateMsg,
+) -> StdResult<Response> {
+    MetadataCw721Contract::default().instantiate(deps, env, info, msg)
+}
+
+fn metadata_cw721_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw721_base::ExecuteMsg<Extension, Empty>,
+) -> Result<Response, cw721_base::ContractError> {
+    MetadataCw721Contract::default().execute(deps, env, info, msg)
+}
+
+fn metadata_cw721_query(
+    deps: Deps,
+    env: Env,
+    msg: cw721_base::QueryMsg<Empty>,
+) -> StdResult<Binary> {
+    MetadataCw721Contract::default().query(deps, env, msg)
+}
+
+pub fn metadata_cw721_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        metadata_cw721_execute,
+        metadata_cw721_instantiate,
+        metadata_cw721_query,
+    );
+    Box::new(contract)
+}
+
+// Voting power source mocks, each one answering the query of the contract it mocks from the instantiated powers
+const MOCK_POWERS: Map<&str, Uint128> = Map::new("powers");
+
+#[cw_serde]
+pub struct MockVotingPowerInstantiateMsg {
+    pub powers: Vec<(String, Uint128)>,
+}
+
+#[cw_serde]
+enum MockDaoVotingModuleQueryMsg {
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+enum MockCw20StakeQueryMsg {
+    StakedBalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+enum MockCw4GroupQueryMsg {
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+struct MockVotingPowerAtHeightResponse {
+    power: Uint128,
+    height: u64,
+}
+
+#[cw_serde]
+struct MockStakedBalanceAtHeightResponse {
+    balance: Uint128,
+    height: u64,
+}
+
+#[cw_serde]
+struct MockMemberResponse {
+    weight: Option<u64>,
+}
+
+fn mock_voting_power_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockVotingPowerInstantiateMsg,
+) -> StdResult<Response> {
+    for (address, power) in msg.powers {
+        MOCK_POWERS.save(deps.storage, &address, &power)?;
+    }
+    Ok(Response::new())
+}
+
+fn mock_voting_power_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn mock_load_power(deps: Deps, address: &str) -> StdResult<Uint128> {
+    Ok(MOCK_POWERS
+        .may_load(deps.storage, address)?
+        .unwrap_or_default())
+}
+
+fn mock_dao_voting_module_query(
+    deps: Deps,
+    env: Env,
+    msg: MockDaoVotingModuleQueryMsg,
+) -> StdResult<Binary> {
+    let MockDaoVotingModuleQueryMsg::VotingPowerAtHeight { address, height } = msg;
+    to_json_binary(&MockVotingPowerAtHeightResponse {
+        power: mock_load_power(deps, &address)?,
+        height: height.unwrap_or(env.block.height),
+    })
+}
+
+fn mock_cw20_stake_query(deps: Deps, env: Env, msg: MockCw20StakeQueryMsg) -> StdResult<Binary> {
+    let MockCw20StakeQueryMsg::StakedBalanceAtHeight { address, height } = msg;
+    to_json_binary(&MockStakedBalanceAtHeightResponse {
+        balance: mock_load_power(deps, &address)?,
+        height: height.unwrap_or(env.block.height),
+    })
+}
+
+fn mock_cw4_group_query(deps: Deps, _env: Env, msg: MockCw4GroupQueryMsg) -> StdResult<Binary> {
+    let MockCw4GroupQueryMsg::Member { addr, .. } = msg;
+    let weight = MOCK_POWERS.may_load(deps.storage, &addr)?;
+    to_json_binary(&MockMemberResponse {
+        weight: weight.map(|w| w.u128() as u64),
+    })
+}
+
+pub fn mock_dao_voting_module_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mock_voting_power_execute,
+        mock_voting_power_instantiate,
+        mock_dao_voting_module_query,
+    );
+    Box::new(contract)
+}
+
+pub fn mock_cw20_stake_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mock_voting_power_execute,
+        mock_voting_power_instantiate,
+        mock_cw20_stake_query,
+    );
+    Box::new(contract)
+}
+
+pub fn mock_cw4_group_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        mock_voting_power_execute,
+        mock_voting_power_instantiate,
+        mock_cw4_group_query,
+    );
+    Box::new(contract)
+}
+
+pub fn instantiate_voting_power_mock(
+    app: &mut App,
+    contract: Box<dyn Contract<Empty>>,
+    powers: Vec<(&str, u128)>,
+) -> Addr {
+    let code_id = app.store_code(contract);
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(ADMIN_ADDRESS),
+        &MockVotingPowerInstantiateMsg {
+            powers: powers
+                .into_iter()
+                .map(|(address, power)| (address.to_string(), Uint128::new(power)))
+                .collect(),
+        },
+        &[],
+        "voting-power-mock",
+        None,
+    )
+    .unwrap()
+}
+
+pub fn instantiate_cw721(app: &mut App, code_id: u64, msg: cw721_base::InstantiateMsg) -> Addr {
     app.instantiate_contract(
         code_id,
         Addr::unchecked(ADMIN_ADDRESS),
@@ -100,12 +330,7 @@ pub fn default_with_balances(
 
     // instantiate cw721 contract
     let cw721_id = app.store_code(cw721_contract());
-    let cw721_msg = cw721_base::InstantiateMsg {
-        name: CONTRACT_NAME.to_string(),
-        symbol: SYMBOL.to_string(),
-        minter: String::from(MINTER_ADDRESS),
-    };
-    let cw721_addr = instantiate_cw721(&mut app, cw721_id, cw721_msg);
+    let cw721_addr = instantiate_cw721(&mut app, cw721_id, default_cw721_msg());
 
     // Instantiate Prudent Pots contract with 5 tokens (1 per pot). Conditionally based on Raffle option field.
     let pp_id = app.store_code(pp_contract());
@@ -120,14 +345,22 @@ pub fn default_with_balances(
                     fee_reallocation: 5,
                     fee_address: Addr::unchecked("treasury_addr"),
                     game_denom: DENOM_GAME.to_string(),
-                    game_cw721_addrs: vec![Addr::unchecked(&cw721_addr)],
+                    game_cw721_collections: vec![default_cw721_collection(&cw721_addr)],
                     game_duration: 1, // we hardcode 1 here in order to let the game expire inmediately, so we execute the raffle init wflow (this could be avoided by instantiating a predicatable contract)
                     game_duration_epoch: GAME_EXTEND, // we hardcode 1 here in order to let the game expire inmediately, so we execute the raffle init wflow (this could be avoided by instantiating a predicatable contract)
-                    game_extend: GAME_EXTEND,
+                    game_extend: 1, // cannot be greater than the game_duration, it is updated along with it below
                     game_end_threshold: GAME_EXTEND,
                     min_pot_initial_allocation: Uint128::new(1_000_000u128),
                     decay_factor: Decimal::from_str("0.05").unwrap(),
                     reallocations_limit: 10,
+                    nft_claim_timeout: NFT_CLAIM_TIMEOUT,
+                    raffle_expiry_rounds: RAFFLE_EXPIRY_ROUNDS,
+                    min_bid_floor: Uint128::new(MIN_BID_FLOOR),
+                    voting_power_sources: vec![],
+                    timelock_delay: TIMELOCK_DELAY,
+                    bid_curve: BidCurve::Linear {},
+                    max_bid_multiplier: Decimal::from_ratio(2u128, 1u128),
+                    max_player_exposure: Uint128::zero(),
                 },
                 next_game_start: None,
             };
@@ -158,24 +391,29 @@ pub fn default_with_balances(
                 &mut app,
                 &pp_addr,
                 &ExecuteMsg::UpdateConfig {
+                    table_id: None,
                     config: Box::new(UpdateGameConfig {
-                        fee: None,
                         fee_reallocation: None,
-                        fee_address: None,
-                        game_denom: None,
-                        game_cw721_addrs: vec![Addr::unchecked(&cw721_addr)], // set the same to avoid updating
+                        game_cw721_collections: vec![default_cw721_collection(&cw721_addr)], // set the same to avoid updating
                         game_duration: Some(GAME_DURATION),
                         game_duration_epoch: None,
-                        game_extend: None,
+                        game_extend: Some(GAME_EXTEND),
                         game_end_threshold: None,
                         min_pot_initial_allocation: None,
                         decay_factor: None,
-                        reallocations_limit: None,
+                        nft_claim_timeout: None,
+                        raffle_expiry_rounds: None,
+                        min_bid_floor: None,
+                        voting_power_sources: None,
+                        bid_curve: None,
+                        max_bid_multiplier: None,
+                        max_player_exposure: None,
                     }),
                 },
             )
             .unwrap();
             pp_msg.config.game_duration = GAME_DURATION; // this is to make the below assert pass
+            pp_msg.config.game_extend = GAME_EXTEND;
 
             // Increase time to expire game
             increase_app_time(&mut app, 2);
@@ -186,7 +424,7 @@ pub fn default_with_balances(
             };
 
             // Game end to start the first real round (this would break the counter and so start from 1)
-            // sending 100 extra $DENOM as raffle prize + 1 NFT
+            // sending 100 extra $DENOM as raffle prize
             game_end(
                 &mut app,
                 &pp_addr,
@@ -194,11 +432,22 @@ pub fn default_with_balances(
                     ADMIN_ADDRESS,
                     &coins(raffle.denom_amount.into(), DENOM_GAME), // raffle denom
                 ),
-                raffle.cw721_token_id,        // raffle nft prize
-                Some(cw721_addr.to_string()), // overriding the None passed from outside as contract wasnt instantiated yet
                 next_game_start_time,
             )
             .unwrap();
+
+            // Send 1 NFT as raffle prize, the collection is the one instantiated above
+            if let Some(token_id) = raffle.cw721_token_id {
+                send_nft(
+                    &mut app,
+                    &cw721_addr,
+                    &Addr::unchecked(ADMIN_ADDRESS),
+                    &pp_addr,
+                    &token_id,
+                    &ReceiveNftMsg::SetRaffleNft { table_id: None },
+                )
+                .unwrap();
+            }
         }
         None => {
             pp_msg = InstantiateMsg {
@@ -207,7 +456,7 @@ pub fn default_with_balances(
                     fee_reallocation: 5,
                     fee_address: Addr::unchecked("treasury_addr"),
                     game_denom: DENOM_GAME.to_string(),
-                    game_cw721_addrs: vec![Addr::unchecked(&cw721_addr)],
+                    game_cw721_collections: vec![default_cw721_collection(&cw721_addr)],
                     game_duration: GAME_DURATION,
                     game_duration_epoch: GAME_EXTEND,
                     game_extend: GAME_EXTEND,
@@ -215,6 +464,14 @@ pub fn default_with_balances(
                     min_pot_initial_allocation: Uint128::new(1_000_000u128),
                     decay_factor: Decimal::from_str("0.05").unwrap(),
                     reallocations_limit: 10,
+                    nft_claim_timeout: NFT_CLAIM_TIMEOUT,
+                    raffle_expiry_rounds: RAFFLE_EXPIRY_ROUNDS,
+                    min_bid_floor: Uint128::new(MIN_BID_FLOOR),
+                    voting_power_sources: vec![],
+                    timelock_delay: TIMELOCK_DELAY,
+                    bid_curve: BidCurve::Linear {},
+                    max_bid_multiplier: Decimal::from_ratio(2u128, 1u128),
+                    max_player_exposure: Uint128::zero(),
                 },
                 next_game_start: match next_game_start_offset {
                     Some(offset) => Some(app.block_info().time.plus_seconds(offset).seconds()),
@@ -231,7 +488,7 @@ pub fn default_with_balances(
     };
 
     // If your CustomApp has a different interface for queries, adjust this section accordingly
-    let query_msg = QueryMsg::GameConfig {};
+    let query_msg = QueryMsg::GameConfig { table_id: None };
     let config: GameConfigResponse = app
         .wrap()
         .query_wasm_smart(pp_addr.clone(), &query_msg)
@@ -248,6 +505,26 @@ pub fn default_with_balances(
 
 // UTILITIES
 
+pub fn default_cw721_collection(cw721_addr: &Addr) -> Cw721Collection {
+    Cw721Collection {
+        addr: cw721_addr.clone(),
+        discount_per_token: Decimal::from_str("0.05").unwrap(),
+        max_tokens_counted: 3,
+        trait_multipliers: vec![],
+        extra_reallocations_per_token: 0,
+        max_extra_reallocations: 0,
+        holder_fee_reallocation: None,
+    }
+}
+
+pub fn default_cw721_msg() -> cw721_base::InstantiateMsg {
+    cw721_base::InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER_ADDRESS),
+    }
+}
+
 pub fn increase_app_time(app: &mut App, seconds: u64) {
     let block_info = app.block_info();
     app.set_block(BlockInfo {
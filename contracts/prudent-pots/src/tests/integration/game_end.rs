@@ -1,16 +1,16 @@
-use cosmwasm_std::{assert_approx_eq, coin, coins, testing::mock_info, Uint128};
+use cosmwasm_std::{assert_approx_eq, coin, coins, testing::mock_info, Addr, Uint128};
 use cw721::{Cw721QueryMsg, TokensResponse};
 
 use crate::{
     msg::{
         GameStateResponse, PlayerAllocationsResponse, PotsStateResponse, QueryMsg,
         RaffleDenomSplitResponse, RaffleResponse, RaffleWinnerResponse,
-        ReallocationFeePoolResponse, WinningPotsResponse,
+        ReallocationFeePoolResponse, ReceiveNftMsg, WinningPotsResponse,
     },
     state::{Raffle, TokenAllocation},
     tests::integration::{
         fixtures::{default_with_balances, ADMIN_ADDRESS, DENOM_GAME, GAME_EXTEND},
-        helpers::{game_end, reallocate_tokens},
+        helpers::{game_end, reallocate_tokens, send_nft},
     },
     ContractError,
 };
@@ -32,7 +32,7 @@ fn test_game_end_one_winner_simple_works() {
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 0);
     assert_eq!(game_state.state.round_count, 1);
@@ -80,7 +80,7 @@ fn test_game_end_one_winner_simple_works() {
     // Assert get_winning_pots only returns pot 5
     let winning_pots: WinningPotsResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::WinningPots {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::WinningPots { table_id: None })
         .unwrap();
     assert_eq!(winning_pots.pots.len(), 1); // only one winner
     assert_eq!(winning_pots.pots[0], 5); // pot id 5
@@ -90,7 +90,7 @@ fn test_game_end_one_winner_simple_works() {
 
     // Game end and new raffles
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    game_end(&mut app, &pp_addr, &info, None, None, None).unwrap();
+    game_end(&mut app, &pp_addr, &info, None).unwrap();
 
     // Get user balance after game_end
     let user5_balance_after = app.wrap().query_balance("user5", DENOM_GAME).unwrap();
@@ -102,7 +102,7 @@ fn test_game_end_one_winner_simple_works() {
     // Query pots_state and sum all of them
     let pots_state: PotsStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
         .unwrap();
     let pots_state_total_amount: Uint128 = pots_state.pots.iter().map(|pot| pot.amount).sum();
     // Query contract balance and compare with summed pots_state after new round is started
@@ -172,7 +172,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 0);
     assert_eq!(game_state.state.round_count, 2); // this is a side effect of raffle which can be set only on 2nd and next rounds
@@ -180,7 +180,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Query raffle
     let raffle: RaffleResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
         .unwrap();
     assert_eq!(
         raffle.raffle,
@@ -220,6 +220,7 @@ fn test_game_end_one_winner_raffle_both_works() {
         .query_wasm_smart(
             &pp_addr,
             &QueryMsg::PlayerAllocations {
+                table_id: None,
                 address: "user5".to_string(),
             },
         )
@@ -241,7 +242,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Assert reallocation fee pool is 5% of previous move
     let reallocation_fee_pool: ReallocationFeePoolResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::ReallocationFeePool {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::ReallocationFeePool { table_id: None })
         .unwrap();
     assert_eq!(
         reallocation_fee_pool.reallocation_fee_pool,
@@ -251,14 +252,14 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 1);
 
     // Check split has been effective with a late-game action as reallocate when 1 second is left
     let raffle_denom_split: RaffleDenomSplitResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::RaffleDenomSplit {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::RaffleDenomSplit { table_id: None })
         .unwrap();
     assert_eq!(
         raffle_denom_split.prize_to_distribute,
@@ -282,7 +283,7 @@ fn test_game_end_one_winner_raffle_both_works() {
 
     let pots_state: PotsStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
         .unwrap();
     let total_sum: u128 = pots_state
         .pots
@@ -293,7 +294,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Assert get_winning_pots only returns pot 2
     let winning_pots: WinningPotsResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::WinningPots {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::WinningPots { table_id: None })
         .unwrap();
     assert_eq!(winning_pots.pots.len(), 1); // only one winner
     assert_eq!(winning_pots.pots[0], 2);
@@ -319,26 +320,29 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Assert winner user of raffle
     let raffle_winner: RaffleWinnerResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::RaffleWinner {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::RaffleWinner { table_id: None })
         .unwrap();
     assert_eq!(raffle_winner.raffle_winner, Some("user5".to_string()));
 
     // Game end and new raffles
     let info = mock_info(ADMIN_ADDRESS, &coins(200_000_000u128, DENOM_GAME));
-    let _res = game_end(
+    let _res = game_end(&mut app, &pp_addr, &info, None).unwrap();
+
+    // Send the new raffle NFT for the next round
+    send_nft(
         &mut app,
+        &cw721_addr,
+        &Addr::unchecked(ADMIN_ADDRESS),
         &pp_addr,
-        &info,
-        Some("2".to_string()),
-        Some(cw721_addr.to_string()),
-        None,
+        "2",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
     )
     .unwrap();
 
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 0);
     assert_eq!(game_state.state.round_count, 3);
@@ -388,7 +392,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Assert raffle state
     let raffle: RaffleResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
         .unwrap();
     assert_eq!(
         raffle.raffle,
@@ -402,7 +406,7 @@ fn test_game_end_one_winner_raffle_both_works() {
     // Query pots_state and sum all of them
     let pots_state: PotsStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
         .unwrap();
     let pots_state_total_amount: Uint128 = pots_state.pots.iter().map(|pot| pot.amount).sum();
 
@@ -450,7 +454,7 @@ fn test_game_end_future_works() {
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 0);
     assert_eq!(game_state.state.round_count, 1);
@@ -465,7 +469,7 @@ fn test_game_end_future_works() {
 
     // Game end, we expect it to fail as the game didnt start yet
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    let res = game_end(&mut app, &pp_addr, &info, None, None, None).unwrap_err();
+    let res = game_end(&mut app, &pp_addr, &info, None).unwrap_err();
     assert!(matches!(
         res.downcast_ref::<ContractError>(),
         Some(ContractError::GameNotStarted {})
@@ -480,7 +484,7 @@ fn test_game_end_future_works() {
 
     // Game end, we expect it to fail as the game didnt finish yet
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    let res = game_end(&mut app, &pp_addr, &info, None, None, None).unwrap_err();
+    let res = game_end(&mut app, &pp_addr, &info, None).unwrap_err();
     assert!(matches!(
         res.downcast_ref::<ContractError>(),
         Some(ContractError::GameStillActive {})
@@ -499,7 +503,7 @@ fn test_game_end_future_works() {
 
     // Game end, expect it to succeed, restart it inmediately
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    game_end(&mut app, &pp_addr, &info, None, None, None).unwrap();
+    game_end(&mut app, &pp_addr, &info, None).unwrap();
 
     // Allocate tokens, we expect it to succeed as the game just started
     let info_1 = mock_info("user1", &coins(1_000_000, DENOM_GAME));
@@ -516,8 +520,6 @@ fn test_game_end_future_works() {
         &mut app,
         &pp_addr,
         &info,
-        None,
-        None,
         Some(next_game_start - (GAME_EXTEND + 1)),
     )
     .unwrap_err();
@@ -526,17 +528,15 @@ fn test_game_end_future_works() {
         &mut app,
         &pp_addr,
         &info,
-        None,
-        None,
         Some(next_game_start - GAME_EXTEND),
     )
     .unwrap_err();
     // make it succeed
-    game_end(&mut app, &pp_addr, &info, None, None, Some(next_game_start)).unwrap();
+    game_end(&mut app, &pp_addr, &info, Some(next_game_start)).unwrap();
 
     // Game end, we expect it to fail as the game didnt start yet
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    let res = game_end(&mut app, &pp_addr, &info, None, None, None).unwrap_err();
+    let res = game_end(&mut app, &pp_addr, &info, None).unwrap_err();
     assert!(matches!(
         res.downcast_ref::<ContractError>(),
         Some(ContractError::GameNotStarted {})
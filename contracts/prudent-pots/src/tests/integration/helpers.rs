@@ -1,7 +1,19 @@
-use cosmwasm_std::{Addr, Empty, MessageInfo};
+use cosmwasm_std::{coins, testing::mock_info, to_json_binary, Addr, Empty, MessageInfo, Uint128};
 use cw_multi_test::{error::AnyError, App, AppResponse, Executor};
 
-use crate::{msg::ExecuteMsg, tests::integration::fixtures::MINTER_ADDRESS};
+use crate::{
+    helpers::nft::{Extension, Metadata, Trait},
+    msg::{
+        AutoCompoundResponse, BalanceResponse, ExecuteMsg, JackpotResponse, ParticipationResponse,
+        PendingProposalsResponse, QueryMsg, RaffleWinnerResponse, ReceiveNftMsg, SeasonResponse,
+        VotesResponse,
+    },
+    state::{
+        AutoCompoundStrategy, Cw721Token, GameConfig, JackpotConfig, ParticipationGate,
+        SeasonConfig, TimelockedConfig, VoteOption,
+    },
+    tests::integration::fixtures::{increase_app_time, DENOM_GAME, GAME_DURATION, MINTER_ADDRESS},
+};
 
 use super::fixtures::ADMIN_ADDRESS;
 
@@ -29,7 +41,10 @@ pub fn allocate_tokens(
     app.execute_contract(
         info.sender.clone(),
         pp_addr.clone(),
-        &ExecuteMsg::AllocateTokens { pot_id },
+        &ExecuteMsg::AllocateTokens {
+            table_id: None,
+            pot_id,
+        },
         &info.funds,
     )
 }
@@ -45,6 +60,7 @@ pub fn reallocate_tokens(
         info.sender.clone(),
         pp_addr.clone(),
         &ExecuteMsg::ReallocateTokens {
+            table_id: None,
             from_pot_id,
             to_pot_id,
         },
@@ -56,16 +72,13 @@ pub fn game_end(
     app: &mut App,
     pp_addr: &Addr,
     info: &MessageInfo,
-    raffle_cw721_token_id: Option<String>,
-    raffle_cw721_token_addr: Option<String>,
     next_game_start: Option<u64>,
 ) -> Result<AppResponse, AnyError> {
     app.execute_contract(
         info.sender.clone(),
         pp_addr.clone(),
         &ExecuteMsg::GameEnd {
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
+            table_id: None,
             next_game_start,
         },
         &info.funds,
@@ -76,24 +89,592 @@ pub fn update_next_game(
     app: &mut App,
     pp_addr: &Addr,
     info: &MessageInfo,
-    raffle_cw721_token_id: Option<String>,
-    raffle_cw721_token_addr: Option<String>,
     next_game_start: Option<u64>,
 ) -> Result<AppResponse, AnyError> {
     app.execute_contract(
         info.sender.clone(),
         pp_addr.clone(),
         &ExecuteMsg::UpdateNextGame {
-            raffle_cw721_token_id,
-            raffle_cw721_token_addr,
+            table_id: None,
             next_game_start,
         },
         &info.funds,
     )
 }
 
+pub fn sponsor_raffle(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    message: Option<String>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SponsorRaffle {
+            table_id: None,
+            message,
+        },
+        &info.funds,
+    )
+}
+
+pub fn claim_nft(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    cw721_addr: &Addr,
+    token_id: &str,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::ClaimNft {
+            table_id: None,
+            cw721_addr: cw721_addr.to_string(),
+            token_id: token_id.to_string(),
+        },
+        &info.funds,
+    )
+}
+
+pub fn recover_assets(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    recover_raffle: bool,
+    cw721_tokens: Vec<Cw721Token>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::RecoverAssets {
+            table_id: None,
+            recover_raffle,
+            cw721_tokens,
+        },
+        &info.funds,
+    )
+}
+
+pub fn register_nfts(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    collection: &Addr,
+    token_ids: Vec<String>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::RegisterNfts {
+            table_id: None,
+            collection: collection.to_string(),
+            token_ids,
+        },
+        &info.funds,
+    )
+}
+
+pub fn transfer_ownership(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    new_owner: &str,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::TransferOwnership {
+            new_owner: new_owner.to_string(),
+        },
+        &info.funds,
+    )
+}
+
+pub fn accept_ownership(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::AcceptOwnership {},
+        &info.funds,
+    )
+}
+
+pub fn renounce_ownership(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::RenounceOwnership {},
+        &info.funds,
+    )
+}
+
+pub fn update_operators(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    add: Vec<&str>,
+    remove: Vec<&str>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::UpdateOperators {
+            add: add.into_iter().map(String::from).collect(),
+            remove: remove.into_iter().map(String::from).collect(),
+        },
+        &info.funds,
+    )
+}
+
+pub fn pause(app: &mut App, pp_addr: &Addr, info: &MessageInfo) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Pause { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn unpause(app: &mut App, pp_addr: &Addr, info: &MessageInfo) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Unpause { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn cancel_round(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::CancelRound { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn refund(app: &mut App, pp_addr: &Addr, info: &MessageInfo) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Refund { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn propose_config(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    changes: TimelockedConfig,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::ProposeConfig {
+            table_id: None,
+            changes,
+        },
+        &info.funds,
+    )
+}
+
+pub fn execute_proposal(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    proposal_id: u64,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::ExecuteProposal {
+            table_id: None,
+            proposal_id,
+        },
+        &info.funds,
+    )
+}
+
+pub fn cancel_proposal(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    proposal_id: u64,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::CancelProposal {
+            table_id: None,
+            proposal_id,
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_vote_options(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    options: Vec<VoteOption>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SetVoteOptions {
+            table_id: None,
+            options,
+        },
+        &info.funds,
+    )
+}
+
+pub fn vote(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    option_id: u32,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Vote {
+            table_id: None,
+            option_id,
+        },
+        &info.funds,
+    )
+}
+
+pub fn start_season(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    config: SeasonConfig,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::StartSeason {
+            table_id: None,
+            config,
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_jackpot(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    config: JackpotConfig,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SetJackpot {
+            table_id: None,
+            config,
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_participation_gate(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    gate: ParticipationGate,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SetParticipationGate {
+            table_id: None,
+            gate,
+        },
+        &info.funds,
+    )
+}
+
+pub fn update_allowlist(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    add: Vec<&str>,
+    remove: Vec<&str>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::UpdateAllowlist {
+            table_id: None,
+            add: add.into_iter().map(String::from).collect(),
+            remove: remove.into_iter().map(String::from).collect(),
+        },
+        &info.funds,
+    )
+}
+
+pub fn update_blocklist(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    add: Vec<&str>,
+    remove: Vec<&str>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::UpdateBlocklist {
+            table_id: None,
+            add: add.into_iter().map(String::from).collect(),
+            remove: remove.into_iter().map(String::from).collect(),
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_auto_compound(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    pot_strategy: Option<AutoCompoundStrategy>,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SetAutoCompound {
+            table_id: None,
+            pot_strategy,
+        },
+        &info.funds,
+    )
+}
+
+pub fn claim_balance(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::ClaimBalance { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn deposit(app: &mut App, pp_addr: &Addr, info: &MessageInfo) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Deposit { table_id: None },
+        &info.funds,
+    )
+}
+
+pub fn withdraw(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    amount: u128,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::Withdraw {
+            table_id: None,
+            amount: Uint128::new(amount),
+        },
+        &info.funds,
+    )
+}
+
+pub fn allocate_from_balance(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    pot_id: u8,
+    amount: u128,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::AllocateFromBalance {
+            table_id: None,
+            pot_id,
+            amount: Uint128::new(amount),
+        },
+        &info.funds,
+    )
+}
+
+pub fn set_balance_payouts(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    enabled: bool,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::SetBalancePayouts {
+            table_id: None,
+            enabled,
+        },
+        &info.funds,
+    )
+}
+
+pub fn create_table(
+    app: &mut App,
+    pp_addr: &Addr,
+    info: &MessageInfo,
+    config: GameConfig,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        info.sender.clone(),
+        pp_addr.clone(),
+        &ExecuteMsg::CreateTable {
+            config: Box::new(config),
+            next_game_start: None,
+        },
+        &info.funds,
+    )
+}
+
+// Plays a round won by user5 alone on the highest pot, for 8_518_840 tokens
+pub fn play_round(app: &mut App, pp_addr: &Addr) {
+    let info_1 = mock_info("user1", &coins(2_000_000, DENOM_GAME));
+    allocate_tokens(app, pp_addr, &info_1, 1).unwrap();
+    let info_2 = mock_info("user2", &coins(1_400_001, DENOM_GAME));
+    allocate_tokens(app, pp_addr, &info_2, 2).unwrap();
+    let info_3 = mock_info("user3", &coins(1_680_000, DENOM_GAME));
+    allocate_tokens(app, pp_addr, &info_3, 3).unwrap();
+    let info_4 = mock_info("user4", &coins(2_016_000, DENOM_GAME));
+    allocate_tokens(app, pp_addr, &info_4, 4).unwrap();
+    let info_5 = mock_info("user5", &coins(2_419_200, DENOM_GAME));
+    allocate_tokens(app, pp_addr, &info_5, 5).unwrap();
+
+    increase_app_time(app, GAME_DURATION);
+    game_end(app, pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+}
+
+// PP queries
+
+pub fn query_balance(app: &App, pp_addr: &Addr, address: &str) -> BalanceResponse {
+    app.wrap()
+        .query_wasm_smart(
+            pp_addr,
+            &QueryMsg::Balance {
+                table_id: None,
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+pub fn query_auto_compound(app: &App, pp_addr: &Addr, address: &str) -> AutoCompoundResponse {
+    app.wrap()
+        .query_wasm_smart(
+            pp_addr,
+            &QueryMsg::AutoCompound {
+                table_id: None,
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+pub fn query_season(app: &App, pp_addr: &Addr) -> SeasonResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::Season { table_id: None })
+        .unwrap()
+}
+
+pub fn query_jackpot(app: &App, pp_addr: &Addr) -> JackpotResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::Jackpot { table_id: None })
+        .unwrap()
+}
+
+pub fn query_votes(app: &App, pp_addr: &Addr) -> VotesResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::Votes { table_id: None })
+        .unwrap()
+}
+
+pub fn query_pending_proposals(app: &App, pp_addr: &Addr) -> PendingProposalsResponse {
+    app.wrap()
+        .query_wasm_smart(
+            pp_addr,
+            &QueryMsg::PendingProposals {
+                table_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+pub fn query_participation(app: &App, pp_addr: &Addr, address: &str) -> ParticipationResponse {
+    app.wrap()
+        .query_wasm_smart(
+            pp_addr,
+            &QueryMsg::Participation {
+                table_id: None,
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+pub fn query_raffle_winner(app: &App, pp_addr: &Addr) -> Option<String> {
+    let res: RaffleWinnerResponse = app
+        .wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::RaffleWinner { table_id: None })
+        .unwrap();
+    res.raffle_winner
+}
+
 // CW721
 
+pub fn send_nft(
+    app: &mut App,
+    cw721_addr: &Addr,
+    sender: &Addr,
+    pp_addr: &Addr,
+    token_id: &str,
+    hook_msg: &ReceiveNftMsg,
+) -> Result<AppResponse, AnyError> {
+    app.execute_contract(
+        sender.clone(),
+        cw721_addr.clone(),
+        &cw721::Cw721ExecuteMsg::SendNft {
+            contract: pp_addr.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_json_binary(hook_msg).unwrap(),
+        },
+        &[],
+    )
+}
+
 pub fn mint_nfts(app: &mut App, cw721_addr: &Addr, start_id: u64, count: u64, to_addr: Addr) {
     for i in start_id..count + 1 {
         let _res = app
@@ -106,8 +687,41 @@ pub fn mint_nfts(app: &mut App, cw721_addr: &Addr, start_id: u64, count: u64, to
                     token_uri: None,
                     extension: None,
                 },
-                &vec![],
+                &[],
             )
             .unwrap();
     }
 }
+
+pub fn mint_nft_with_traits(
+    app: &mut App,
+    cw721_addr: &Addr,
+    token_id: &str,
+    to_addr: &Addr,
+    traits: Vec<(&str, &str)>,
+) {
+    app.execute_contract(
+        Addr::unchecked(MINTER_ADDRESS),
+        cw721_addr.clone(),
+        &cw721_base::msg::ExecuteMsg::<Extension, Empty>::Mint {
+            token_id: token_id.to_string(),
+            owner: to_addr.to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                attributes: Some(
+                    traits
+                        .into_iter()
+                        .map(|(trait_type, value)| Trait {
+                            display_type: None,
+                            trait_type: trait_type.to_string(),
+                            value: value.to_string(),
+                        })
+                        .collect(),
+                ),
+                ..Metadata::default()
+            }),
+        },
+        &[],
+    )
+    .unwrap();
+}
@@ -0,0 +1,116 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+
+use crate::{
+    state::{JackpotConfig, JackpotPayout, JackpotSource, JackpotTrigger},
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+        },
+        helpers::{allocate_tokens, game_end, query_jackpot, set_jackpot},
+    },
+    ContractError,
+};
+
+#[test]
+fn test_jackpot_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+    let jackpot_config = JackpotConfig {
+        source: JackpotSource::WinningFee {},
+        share: 50,
+        triggers: vec![JackpotTrigger::MedianIsAverage {}],
+    };
+
+    // Only the owner can set the jackpot
+    let res = set_jackpot(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        jackpot_config.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // At least one trigger is required
+    let res = set_jackpot(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        JackpotConfig {
+            triggers: vec![],
+            ..jackpot_config.clone()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "triggers"
+    ));
+
+    set_jackpot(&mut app, &pp_addr, &admin_info, jackpot_config).unwrap();
+
+    let info_1 = mock_info("user1", &coins(2_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_1, 1).unwrap();
+    let info_2 = mock_info("user2", &coins(1_400_001, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap();
+    let info_3 = mock_info("user3", &coins(1_680_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_3, 3).unwrap();
+    let info_4 = mock_info("user4", &coins(2_016_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_4, 4).unwrap();
+    let info_5 = mock_info("user5", &coins(2_419_200, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_5, 5).unwrap();
+
+    // The board is uneven, so the jackpot only grows by the fee slice at game end
+    let jackpot = query_jackpot(&app, &pp_addr);
+    assert_eq!(jackpot.trigger, None);
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+
+    // The pool is paid out in the game denom the jackpot was set with
+    let jackpot = query_jackpot(&app, &pp_addr).jackpot.unwrap();
+    assert_eq!(jackpot.pool, Uint128::new(224_180));
+    assert_eq!(jackpot.denom, DENOM_GAME);
+
+    // Even bids keep the median pot on the average, the only winning pot players share the whole jackpot
+    for (i, user) in ["user1", "user2", "user3", "user4", "user5"]
+        .iter()
+        .enumerate()
+    {
+        let info = mock_info(user, &coins(2_000_000, DENOM_GAME));
+        allocate_tokens(&mut app, &pp_addr, &info, i as u8 + 1).unwrap();
+    }
+    let jackpot = query_jackpot(&app, &pp_addr);
+    assert_eq!(jackpot.trigger, Some(JackpotTrigger::MedianIsAverage {}));
+
+    increase_app_time(&mut app, GAME_DURATION);
+    let res = game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+    let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert_eq!(
+        wasm.attributes
+            .iter()
+            .find(|a| a.key == "jackpot_outgoing_tokens")
+            .unwrap()
+            .value,
+        "457400"
+    );
+
+    let jackpot = query_jackpot(&app, &pp_addr).jackpot.unwrap();
+    assert_eq!(jackpot.pool, Uint128::zero());
+    assert_eq!(
+        jackpot.last_payout,
+        Some(JackpotPayout {
+            round_count: 2,
+            trigger: JackpotTrigger::MedianIsAverage {},
+            amount: Uint128::new(457_400), // the previous pool plus this round fee slice
+            winners: 1,
+        })
+    );
+}
@@ -0,0 +1,362 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Decimal, Storage, Uint128};
+use cw_multi_test::{error::AnyError, App, AppResponse, Executor};
+
+use crate::{
+    msg::{GameConfigResponse, MigrateMsg, OwnershipResponse, QueryMsg, TablesResponse},
+    state::{
+        BidCurve, GameConfigV2, GameConfigV2_1, OldGameConfig, Ownership, Table, GAME_CONFIG,
+        GAME_CONFIG_V2, GAME_CONFIG_V2_1, OLD_GAME_CONFIG, OPERATORS, OWNERSHIP, TABLES,
+    },
+    tests::integration::{
+        fixtures::{
+            default_with_balances, pp_contract, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+            GAME_EXTEND, NFT_CLAIM_TIMEOUT, RAFFLE_EXPIRY_ROUNDS, TIMELOCK_DELAY,
+        },
+        helpers::allocate_tokens,
+    },
+    ContractError,
+};
+
+const CONTRACT_NAME: &str = "crates.io:prudent-pots";
+
+// Rewrites the contract state as it was stored by a previous version
+fn set_legacy_state(
+    app: &mut App,
+    pp_addr: &Addr,
+    contract: &str,
+    version: &str,
+    legacy_config: impl FnOnce(&mut dyn Storage),
+) {
+    let mut storage = app.contract_storage_mut(pp_addr);
+    GAME_CONFIG.remove(storage.as_mut());
+    OWNERSHIP.remove(storage.as_mut());
+    OPERATORS.remove(storage.as_mut());
+    cw2::set_contract_version(storage.as_mut(), contract, version).unwrap();
+    legacy_config(storage.as_mut());
+}
+
+fn migrate(app: &mut App, pp_addr: &Addr, msg: &MigrateMsg) -> Result<AppResponse, AnyError> {
+    let pp_id = app.store_code(pp_contract());
+    app.migrate_contract(Addr::unchecked(ADMIN_ADDRESS), pp_addr.clone(), msg, pp_id)
+}
+
+fn full_migrate_msg() -> MigrateMsg {
+    MigrateMsg {
+        game_duration_epoch: Some(GAME_EXTEND),
+        max_tokens_counted: Some(3),
+        nft_claim_timeout: Some(NFT_CLAIM_TIMEOUT),
+        raffle_expiry_rounds: Some(RAFFLE_EXPIRY_ROUNDS),
+        min_bid_floor: None,
+        timelock_delay: Some(TIMELOCK_DELAY),
+    }
+}
+
+#[test]
+fn test_migrate_from_v1_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    set_legacy_state(&mut app, &pp_addr, CONTRACT_NAME, "1.0.0", |storage| {
+        OLD_GAME_CONFIG
+            .save(
+                storage,
+                &OldGameConfig {
+                    fee: 3,
+                    fee_reallocation: 5,
+                    fee_address: Addr::unchecked("fee_address"),
+                    game_denom: DENOM_GAME.to_string(),
+                    game_cw721_addrs: vec![cw721_addr.clone()],
+                    game_duration: GAME_DURATION,
+                    game_extend: GAME_EXTEND,
+                    game_end_threshold: 600,
+                    min_pot_initial_allocation: Uint128::new(1_000_000u128),
+                    decay_factor: Uint128::new(95u128),
+                    reallocations_limit: 10,
+                },
+            )
+            .unwrap();
+    });
+
+    // The v1 config has no epochs, so they have to be provided
+    let res = migrate(
+        &mut app,
+        &pp_addr,
+        &MigrateMsg {
+            game_duration_epoch: None,
+            ..full_migrate_msg()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::MissingMigrateField { field }) if field == "game_duration_epoch"
+    ));
+
+    migrate(&mut app, &pp_addr, &full_migrate_msg()).unwrap();
+
+    // The decay factor is converted from the 95% kept to the 5% decayed, all steps being applied
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.game_duration_epoch, GAME_EXTEND);
+    assert_eq!(
+        game_config.config.decay_factor,
+        Decimal::from_str("0.05").unwrap()
+    );
+    assert_eq!(game_config.config.game_cw721_collections.len(), 1);
+    assert_eq!(
+        game_config.config.game_cw721_collections[0].discount_per_token,
+        Decimal::from_str("0.05").unwrap()
+    );
+    assert_eq!(game_config.config.nft_claim_timeout, NFT_CLAIM_TIMEOUT);
+
+    let storage = app.contract_storage(&pp_addr);
+    assert!(OLD_GAME_CONFIG
+        .may_load(storage.as_ref())
+        .unwrap()
+        .is_none());
+    assert!(GAME_CONFIG_V2.may_load(storage.as_ref()).unwrap().is_none());
+    drop(storage);
+
+    let contract_version = cw2::query_contract_info(&app.wrap(), &pp_addr).unwrap();
+    assert_eq!(contract_version.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_migrate_from_v2_0_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    set_legacy_state(&mut app, &pp_addr, CONTRACT_NAME, "2.0.0", |storage| {
+        GAME_CONFIG_V2
+            .save(
+                storage,
+                &GameConfigV2 {
+                    fee: 3,
+                    fee_reallocation: 5,
+                    fee_address: Addr::unchecked("fee_address"),
+                    game_denom: DENOM_GAME.to_string(),
+                    game_cw721_addrs: vec![cw721_addr.clone()],
+                    game_duration: GAME_DURATION,
+                    game_duration_epoch: GAME_EXTEND,
+                    game_extend: GAME_EXTEND,
+                    game_end_threshold: 600,
+                    min_pot_initial_allocation: Uint128::new(1_000_000u128),
+                    decay_factor: Decimal::from_str("0.05").unwrap(),
+                    reallocations_limit: 10,
+                },
+            )
+            .unwrap();
+    });
+
+    // The config fields introduced since v2.0.0 have to be provided
+    let res = migrate(
+        &mut app,
+        &pp_addr,
+        &MigrateMsg {
+            nft_claim_timeout: None,
+            ..full_migrate_msg()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::MissingMigrateField { field }) if field == "nft_claim_timeout"
+    ));
+
+    migrate(
+        &mut app,
+        &pp_addr,
+        &MigrateMsg {
+            game_duration_epoch: None,
+            ..full_migrate_msg()
+        },
+    )
+    .unwrap();
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(
+        game_config.config.game_cw721_collections[0].addr,
+        cw721_addr
+    );
+    assert_eq!(
+        game_config.config.game_cw721_collections[0].max_tokens_counted,
+        3
+    );
+    assert_eq!(
+        game_config.config.raffle_expiry_rounds,
+        RAFFLE_EXPIRY_ROUNDS
+    );
+    assert!(game_config.config.min_bid_floor.is_zero());
+    assert!(game_config.config.voting_power_sources.is_empty());
+
+    // The wasm admin becomes the contract owner
+    let ownership: OwnershipResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Ownership {})
+        .unwrap();
+    assert_eq!(ownership.owner, Some(Addr::unchecked(ADMIN_ADDRESS)));
+    assert!(ownership.operators.is_empty());
+
+    // The game goes on with the migrated config
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+}
+
+#[test]
+fn test_migrate_from_v2_1_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    set_legacy_state(&mut app, &pp_addr, CONTRACT_NAME, "2.1.0", |storage| {
+        TABLES.remove(storage, 0);
+        GAME_CONFIG_V2_1
+            .save(
+                storage,
+                &GameConfigV2_1 {
+                    fee: 3,
+                    fee_reallocation: 5,
+                    fee_address: Addr::unchecked("fee_address"),
+                    game_denom: DENOM_GAME.to_string(),
+                    game_cw721_collections: vec![],
+                    game_duration: GAME_DURATION,
+                    game_duration_epoch: GAME_EXTEND,
+                    game_extend: GAME_EXTEND,
+                    game_end_threshold: 600,
+                    min_pot_initial_allocation: Uint128::new(1_000_000u128),
+                    decay_factor: Decimal::from_str("0.05").unwrap(),
+                    reallocations_limit: 10,
+                    nft_claim_timeout: NFT_CLAIM_TIMEOUT,
+                    raffle_expiry_rounds: RAFFLE_EXPIRY_ROUNDS,
+                    min_bid_floor: Uint128::zero(),
+                    voting_power_sources: vec![],
+                },
+            )
+            .unwrap();
+        OWNERSHIP
+            .save(
+                storage,
+                &Ownership {
+                    owner: Some(Addr::unchecked(ADMIN_ADDRESS)),
+                    pending_owner: None,
+                },
+            )
+            .unwrap();
+        OPERATORS.save(storage, &vec![]).unwrap();
+    });
+
+    // The timelock introduced since v2.1.0 has to be provided
+    let res = migrate(
+        &mut app,
+        &pp_addr,
+        &MigrateMsg {
+            timelock_delay: None,
+            ..MigrateMsg::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::MissingMigrateField { field }) if field == "timelock_delay"
+    ));
+
+    migrate(
+        &mut app,
+        &pp_addr,
+        &MigrateMsg {
+            timelock_delay: Some(TIMELOCK_DELAY),
+            ..MigrateMsg::default()
+        },
+    )
+    .unwrap();
+
+    // The later config fields get their defaults
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.timelock_delay, TIMELOCK_DELAY);
+    assert_eq!(game_config.config.bid_curve, BidCurve::Linear {});
+    assert_eq!(
+        game_config.config.max_bid_multiplier,
+        Decimal::from_ratio(2u128, 1u128)
+    );
+    assert!(game_config.config.max_player_exposure.is_zero());
+
+    // The existing game becomes the default table, reserving its denom
+    let tables: TablesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::Tables {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        tables.tables,
+        vec![Table {
+            id: 0,
+            denoms: vec![DENOM_GAME.to_string()],
+        }]
+    );
+
+    // The game goes on with the migrated config
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+}
+
+#[test]
+fn test_migrate_version_checks_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Migrating from the current version runs no step
+    migrate(&mut app, &pp_addr, &MigrateMsg::default()).unwrap();
+
+    // Another contract cannot be migrated
+    cw2::set_contract_version(
+        app.contract_storage_mut(&pp_addr).as_mut(),
+        "crates.io:another-contract",
+        "1.0.0",
+    )
+    .unwrap();
+    let res = migrate(&mut app, &pp_addr, &full_migrate_msg()).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidContractName { .. })
+    ));
+
+    // Downgrades are refused
+    cw2::set_contract_version(
+        app.contract_storage_mut(&pp_addr).as_mut(),
+        CONTRACT_NAME,
+        "99.0.0",
+    )
+    .unwrap();
+    let res = migrate(&mut app, &pp_addr, &full_migrate_msg()).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::CannotMigrateToOlderVersion { .. })
+    ));
+}
@@ -1,8 +1,27 @@
+#[allow(clippy::assertions_on_constants, clippy::useless_vec)]
 mod allocate_tokens;
+mod auto_compound;
+mod balances;
+mod claim_nft;
+#[allow(clippy::let_and_return, clippy::manual_map, clippy::useless_vec)]
 mod fixtures;
+#[allow(clippy::useless_vec)]
 mod game_end;
 mod helpers;
 mod instantiate;
+mod jackpot;
+mod migrate;
+mod ownership;
+mod participation;
+mod pause;
+mod proposals;
 mod reallocate_tokens;
+mod recover_assets;
+mod register_nfts;
+mod season;
+mod sponsor_raffle;
+mod tables;
 mod update_config;
+#[allow(clippy::useless_vec)]
 mod update_next_game;
+mod vote;
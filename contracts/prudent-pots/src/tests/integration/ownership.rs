@@ -0,0 +1,164 @@
+use cosmwasm_std::{coin, testing::mock_info, Addr};
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{ExecuteMsg, OwnershipResponse, QueryMsg, UpdateGameConfig},
+    tests::integration::{
+        fixtures::{default_with_balances, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION},
+        helpers::{
+            accept_ownership, renounce_ownership, transfer_ownership, update_next_game,
+            update_operators,
+        },
+    },
+    ContractError,
+};
+
+fn query_ownership(app: &cw_multi_test::App, pp_addr: &Addr) -> OwnershipResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::Ownership {})
+        .unwrap()
+}
+
+fn execute_update_config(
+    app: &mut cw_multi_test::App,
+    pp_addr: &Addr,
+    sender: &str,
+) -> Result<cw_multi_test::AppResponse, cw_multi_test::error::AnyError> {
+    app.execute_contract(
+        Addr::unchecked(sender),
+        pp_addr.clone(),
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: Some(1),
+                game_cw721_collections: vec![],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: None,
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+        &[],
+    )
+}
+
+#[test]
+fn test_ownership_operators_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+    let operator_info = mock_info("operator", &[]);
+
+    // The instantiator is the owner
+    assert_eq!(
+        query_ownership(&app, &pp_addr),
+        OwnershipResponse {
+            owner: Some(Addr::unchecked(ADMIN_ADDRESS)),
+            pending_owner: None,
+            operators: vec![],
+        }
+    );
+
+    // Only the owner can add operators
+    let res =
+        update_operators(&mut app, &pp_addr, &operator_info, vec!["operator"], vec![]).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    update_operators(&mut app, &pp_addr, &admin_info, vec!["operator"], vec![]).unwrap();
+    assert_eq!(
+        query_ownership(&app, &pp_addr).operators,
+        vec![Addr::unchecked("operator")]
+    );
+
+    // Operators manage the next game, but cannot change the config
+    let next_game_start = Some(app.block_info().time.seconds() + GAME_DURATION);
+    update_next_game(&mut app, &pp_addr, &operator_info, next_game_start).unwrap();
+    let res = update_next_game(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        next_game_start,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    let res = execute_update_config(&mut app, &pp_addr, "operator").unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+}
+
+#[test]
+fn test_ownership_transfer_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+    let new_owner_info = mock_info("new_owner", &[]);
+
+    // Only the owner can start a transfer
+    let res = transfer_ownership(&mut app, &pp_addr, &new_owner_info, "new_owner").unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    transfer_ownership(&mut app, &pp_addr, &admin_info, "new_owner").unwrap();
+
+    // The current owner keeps the ownership until the pending owner accepts it
+    let ownership = query_ownership(&app, &pp_addr);
+    assert_eq!(ownership.owner, Some(Addr::unchecked(ADMIN_ADDRESS)));
+    assert_eq!(ownership.pending_owner, Some(Addr::unchecked("new_owner")));
+    let res = execute_update_config(&mut app, &pp_addr, "new_owner").unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Only the pending owner can accept it
+    let res = accept_ownership(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    accept_ownership(&mut app, &pp_addr, &new_owner_info).unwrap();
+
+    let ownership = query_ownership(&app, &pp_addr);
+    assert_eq!(ownership.owner, Some(Addr::unchecked("new_owner")));
+    assert_eq!(ownership.pending_owner, None);
+    execute_update_config(&mut app, &pp_addr, "new_owner").unwrap();
+    let res = execute_update_config(&mut app, &pp_addr, ADMIN_ADDRESS).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Once renounced, nobody can change the config anymore
+    renounce_ownership(&mut app, &pp_addr, &new_owner_info).unwrap();
+    assert_eq!(query_ownership(&app, &pp_addr).owner, None);
+    let res = execute_update_config(&mut app, &pp_addr, "new_owner").unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+}
@@ -0,0 +1,141 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr};
+
+use crate::{
+    msg::{AddressListResponse, QueryMsg},
+    state::ParticipationGate,
+    tests::integration::{
+        fixtures::{
+            default_with_balances, instantiate_voting_power_mock, mock_cw4_group_contract,
+            ADMIN_ADDRESS, DENOM_GAME,
+        },
+        helpers::{
+            allocate_tokens, cancel_round, query_participation, query_raffle_winner,
+            reallocate_tokens, refund, set_participation_gate, update_allowlist, update_blocklist,
+        },
+    },
+    ContractError,
+};
+
+#[test]
+fn test_participation_gating_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        3,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+
+    // Only the owner can gate the table
+    let res = set_participation_gate(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        ParticipationGate::Allowlist {},
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // With the allowlist gate only the allowlisted addresses can play
+    set_participation_gate(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        ParticipationGate::Allowlist {},
+    )
+    .unwrap();
+    update_allowlist(&mut app, &pp_addr, &admin_info, vec!["user1"], vec![]).unwrap();
+
+    let allowlist: AddressListResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::Allowlist {
+                table_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(allowlist.addresses, vec![Addr::unchecked("user1")]);
+
+    let info_2 = mock_info("user2", &coins(1_000_000, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NotAllowed {})
+    ));
+    let info_1 = mock_info("user1", &coins(1_000_001, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_1, 4).unwrap();
+
+    // A cw4 group failing to answer is an error, rather than a silent rejection
+    set_participation_gate(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        ParticipationGate::Cw4Group {
+            addr: pp_addr.clone(),
+        },
+    )
+    .unwrap();
+    let info_3 = mock_info("user3", &coins(1_000_000, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info_3, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Std(_))
+    ));
+
+    // With the cw4 gate only the current group members can play
+    let cw4_group_addr = instantiate_voting_power_mock(
+        &mut app,
+        mock_cw4_group_contract(),
+        vec![("user1", 1), ("user2", 1)],
+    );
+    set_participation_gate(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        ParticipationGate::Cw4Group {
+            addr: cw4_group_addr,
+        },
+    )
+    .unwrap();
+    assert!(!query_participation(&app, &pp_addr, "user3").allowed);
+
+    let info_3 = mock_info("user3", &coins(1_000_000, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info_3, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NotAllowed {})
+    ));
+    allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap();
+    assert_eq!(
+        query_raffle_winner(&app, &pp_addr),
+        Some("user1".to_string())
+    );
+
+    // Blocked players cannot play nor win the raffle, whatever the gate
+    update_blocklist(&mut app, &pp_addr, &admin_info, vec!["user1"], vec![]).unwrap();
+    let participation = query_participation(&app, &pp_addr, "user1");
+    assert!(participation.blocked);
+    assert!(!participation.allowed);
+
+    let res = reallocate_tokens(&mut app, &pp_addr, &mock_info("user1", &[]), 4, 3).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Blocked {})
+    ));
+    assert_eq!(
+        query_raffle_winner(&app, &pp_addr),
+        Some("user2".to_string())
+    );
+
+    // Refunds are never gated, so blocked players get their funds back from a cancelled round
+    cancel_round(&mut app, &pp_addr, &admin_info).unwrap();
+    refund(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap();
+    let balance = app.wrap().query_balance("user1", DENOM_GAME).unwrap();
+    assert_eq!(balance.amount.u128(), 100_000_000);
+}
@@ -0,0 +1,170 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+
+use crate::{
+    msg::{
+        EmergencyStateResponse, GameStateResponse, PotsStateResponse, QueryMsg,
+        ReallocationFeePoolResponse, RefundClaimResponse,
+    },
+    tests::integration::{
+        fixtures::{default_with_balances, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION},
+        helpers::{
+            allocate_tokens, cancel_round, game_end, pause, reallocate_tokens, refund, unpause,
+        },
+    },
+    ContractError,
+};
+
+#[test]
+fn test_pause_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+
+    // Only the owner can pause the game
+    let res = pause(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+    pause(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[])).unwrap();
+
+    // Allocations, reallocations and game end are blocked while paused
+    let res = allocate_tokens(&mut app, &pp_addr, &info, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::GamePaused {})
+    ));
+    let res = reallocate_tokens(&mut app, &pp_addr, &info, 1, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::GamePaused {})
+    ));
+    app.update_block(|block| block.time = block.time.plus_seconds(GAME_DURATION));
+    let res = game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::GamePaused {})
+    ));
+
+    // Refunds are only available for cancelled rounds
+    let res = refund(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::RoundNotCancelled {})
+    ));
+
+    // Once unpaused the round can end as usual
+    unpause(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[])).unwrap();
+    let emergency_state: EmergencyStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::EmergencyState { table_id: None })
+        .unwrap();
+    assert!(!emergency_state.paused);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+}
+
+#[test]
+fn test_cancel_round_refund_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let info_1 = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    let info_2 = mock_info("user2", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_1, 1).unwrap();
+    allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap();
+    // The reallocation fee is kept by the contract and refunded as well
+    reallocate_tokens(&mut app, &pp_addr, &info_1, 1, 3).unwrap();
+
+    cancel_round(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[])).unwrap();
+    let res = cancel_round(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::RoundCancelled {})
+    ));
+    let res = allocate_tokens(&mut app, &pp_addr, &info_1, 4).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::RoundCancelled {})
+    ));
+
+    // User1 gets back the whole allocation, including the reallocation fee
+    let balance_before = app.wrap().query_balance("user1", DENOM_GAME).unwrap();
+    refund(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap();
+    let balance_after = app.wrap().query_balance("user1", DENOM_GAME).unwrap();
+    assert_eq!(
+        balance_after.amount - balance_before.amount,
+        Uint128::new(1_000_000u128)
+    );
+    let fee_pool: ReallocationFeePoolResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::ReallocationFeePool { table_id: None })
+        .unwrap();
+    assert!(fee_pool.reallocation_fee_pool.is_zero());
+
+    // Nothing left to refund for user1
+    let res = refund(&mut app, &pp_addr, &mock_info("user1", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NoRefundAvailable {})
+    ));
+
+    // Unpausing does not wait for user2, whose refund is kept as a claim
+    unpause(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[])).unwrap();
+    let refund_claim: RefundClaimResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RefundClaim {
+                table_id: None,
+                address: "user2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(refund_claim.amount, Uint128::new(1_000_000u128));
+
+    // The next round is seeded with the remaining balance, leaving the refund claim out
+    let pots_state: PotsStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
+        .unwrap();
+    let pots_total: Uint128 = pots_state.pots.iter().map(|pot| pot.amount).sum();
+    let contract_balance = app.wrap().query_balance(&pp_addr, DENOM_GAME).unwrap();
+    assert_eq!(
+        contract_balance.amount,
+        pots_total + Uint128::new(1_000_000u128)
+    );
+
+    let balance_before = app.wrap().query_balance("user2", DENOM_GAME).unwrap();
+    refund(&mut app, &pp_addr, &mock_info("user2", &[])).unwrap();
+    let balance_after = app.wrap().query_balance("user2", DENOM_GAME).unwrap();
+    assert_eq!(
+        balance_after.amount - balance_before.amount,
+        Uint128::new(1_000_000u128)
+    );
+    let res = refund(&mut app, &pp_addr, &mock_info("user2", &[])).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::RoundNotCancelled {})
+    ));
+
+    let emergency_state: EmergencyStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::EmergencyState { table_id: None })
+        .unwrap();
+    assert!(!emergency_state.paused);
+    assert!(!emergency_state.round_cancelled);
+    let game_state: GameStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
+        .unwrap();
+    assert_eq!(game_state.state.round_count, 2);
+    allocate_tokens(&mut app, &pp_addr, &info_1, 1).unwrap();
+}
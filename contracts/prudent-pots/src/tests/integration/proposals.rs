@@ -0,0 +1,294 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Uint128};
+use cw_multi_test::{BankSudo, SudoMsg};
+
+use crate::{
+    msg::{GameConfigResponse, PendingConfigResponse, PotsStateResponse, QueryMsg, RaffleResponse},
+    state::TimelockedConfig,
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+            TIMELOCK_DELAY,
+        },
+        helpers::{
+            allocate_tokens, cancel_proposal, deposit, execute_proposal, game_end, propose_config,
+            query_pending_proposals, withdraw,
+        },
+    },
+    ContractError,
+};
+
+const DENOM_NEW: &str = "unew";
+
+fn empty_timelocked_config() -> TimelockedConfig {
+    TimelockedConfig {
+        fee: None,
+        fee_address: None,
+        game_denom: None,
+        reallocations_limit: None,
+        timelock_delay: None,
+    }
+}
+
+#[test]
+fn test_proposals_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+
+    // Only the owner can propose config changes
+    let res = propose_config(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        TimelockedConfig {
+            fee: Some(3),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Proposals are validated upfront
+    let res = propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            fee: Some(11),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "fee"
+    ));
+
+    propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            fee: Some(3),
+            fee_address: Some(Addr::unchecked("new_treasury")),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap();
+    propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            reallocations_limit: Some(20),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap();
+
+    // Proposals are public along with their eta
+    let pending_proposals = query_pending_proposals(&app, &pp_addr);
+    assert_eq!(pending_proposals.proposals.len(), 2);
+    let eta = app.block_info().time.seconds() + TIMELOCK_DELAY;
+    assert_eq!(pending_proposals.proposals[0].eta, eta);
+
+    // Proposals cannot be executed before their eta
+    let res = execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::ProposalNotReady { eta: not_before }) if *not_before == eta
+    ));
+
+    // The owner can withdraw a proposal
+    cancel_proposal(&mut app, &pp_addr, &admin_info, 2).unwrap();
+    let res = cancel_proposal(&mut app, &pp_addr, &admin_info, 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::ProposalNotFound {})
+    ));
+    assert_eq!(query_pending_proposals(&app, &pp_addr).proposals.len(), 1);
+
+    // Without any player committed to the round the proposal applies immediately once executed
+    increase_app_time(&mut app, TIMELOCK_DELAY);
+    execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap();
+    assert!(query_pending_proposals(&app, &pp_addr).proposals.is_empty());
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.fee, 3);
+    assert_eq!(
+        game_config.config.fee_address,
+        Addr::unchecked("new_treasury")
+    );
+    assert_eq!(game_config.config.reallocations_limit, 10);
+}
+
+#[test]
+fn test_execute_proposal_staged_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+
+    // Shorten the timelock delay, so the next proposal can be executed during a round
+    propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            timelock_delay: Some(60),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap();
+    increase_app_time(&mut app, TIMELOCK_DELAY);
+    execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap();
+    game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+    propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            fee: Some(3),
+            fee_address: Some(Addr::unchecked("new_treasury")),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap();
+    increase_app_time(&mut app, 60);
+    execute_proposal(&mut app, &pp_addr, &admin_info, 2).unwrap();
+
+    // The fee address applies immediately, while the fee waits for the next round
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.fee, 5);
+    assert_eq!(
+        game_config.config.fee_address,
+        Addr::unchecked("new_treasury")
+    );
+    let pending_config: PendingConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PendingConfig { table_id: None })
+        .unwrap();
+    assert_eq!(pending_config.changed_fields, vec!["fee".to_string()]);
+}
+
+#[test]
+fn test_execute_proposal_game_denom_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+
+    propose_config(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        TimelockedConfig {
+            game_denom: Some(DENOM_NEW.to_string()),
+            ..empty_timelocked_config()
+        },
+    )
+    .unwrap();
+    increase_app_time(&mut app, TIMELOCK_DELAY);
+
+    // The contract has to hold funds in the new denom to seed the next round pots
+    let res = execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::GameDenomNotFunded { denom }) if denom == DENOM_NEW
+    ));
+
+    app.sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: pp_addr.to_string(),
+        amount: coins(4_000_000, DENOM_NEW),
+    }))
+    .unwrap();
+
+    // The player balances are not stored with their denom, so the denom cannot change while they are held
+    let user1_info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    deposit(&mut app, &pp_addr, &user1_info).unwrap();
+    let res = execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::GameDenomInUse { denom }) if denom == DENOM_GAME
+    ));
+
+    withdraw(&mut app, &pp_addr, &mock_info("user1", &[]), 1_000_000).unwrap();
+    execute_proposal(&mut app, &pp_addr, &admin_info, 1).unwrap();
+
+    // The current pots were seeded in the old denom, so the switch waits for the next round even without bids
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.game_denom, DENOM_GAME);
+    let pending_config: PendingConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PendingConfig { table_id: None })
+        .unwrap();
+    assert_eq!(
+        pending_config.changed_fields,
+        vec!["game_denom".to_string()]
+    );
+
+    // The new denom balance cannot seed the pots yet, so the switch stays pending
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.game_denom, DENOM_GAME);
+
+    // Once funded, the next round pots are reseeded from the new denom balance alone
+    app.sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: pp_addr.to_string(),
+        amount: coins(1_000_000, DENOM_NEW),
+    }))
+    .unwrap();
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.game_denom, DENOM_NEW);
+
+    let pots_state: PotsStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
+        .unwrap();
+    let pots_total: Uint128 = pots_state.pots.iter().map(|pot| pot.amount).sum();
+    let raffle: RaffleResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
+        .unwrap();
+    let new_denom_balance = app.wrap().query_balance(&pp_addr, DENOM_NEW).unwrap();
+    assert_eq!(pots_total, Uint128::new(5_000_000));
+    assert!(raffle.raffle.denom_amount.is_zero());
+    assert_eq!(
+        pots_total + raffle.raffle.denom_amount,
+        new_denom_balance.amount
+    );
+}
@@ -1,9 +1,17 @@
-use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+use std::str::FromStr;
 
-use crate::msg::{PotsStateResponse, QueryMsg};
-use crate::state::TokenAllocation;
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Decimal, Uint128};
+use cw_multi_test::next_block;
+
+use crate::msg::{
+    ExecuteMsg, PlayerLimitsResponse, PotStateResponse, PotsStateResponse, QueryMsg,
+    UpdateGameConfig,
+};
+use crate::state::{Cw721Collection, TokenAllocation};
 use crate::tests::integration::fixtures::{default_with_balances, DENOM_GAME};
-use crate::tests::integration::helpers::{allocate_tokens, reallocate_tokens};
+use crate::tests::integration::helpers::{
+    allocate_tokens, mint_nfts, reallocate_tokens, register_nfts, update_config,
+};
 
 #[test]
 fn test_reallocate_tokens_works() {
@@ -27,7 +35,7 @@ fn test_reallocate_tokens_works() {
 
     let pots_state: PotsStateResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotsState {})
+        .query_wasm_smart(pp_addr.clone(), &QueryMsg::PotsState { table_id: None })
         .unwrap();
 
     let expected_pots_state = PotsStateResponse {
@@ -56,3 +64,112 @@ fn test_reallocate_tokens_works() {
     };
     assert_eq!(pots_state, expected_pots_state);
 }
+
+#[test]
+fn test_reallocate_tokens_nft_holder_perks_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Holders get 2 extra reallocations per token up to 5, and a 1% reallocation fee
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![Cw721Collection {
+                    addr: cw721_addr.clone(),
+                    discount_per_token: Decimal::from_str("0.05").unwrap(),
+                    max_tokens_counted: 3,
+                    trait_multipliers: vec![],
+                    extra_reallocations_per_token: 2,
+                    max_extra_reallocations: 5,
+                    holder_fee_reallocation: Some(1),
+                }],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: None,
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    mint_nfts(&mut app, &cw721_addr, 1, 3, Addr::unchecked("user1"));
+    register_nfts(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        &cw721_addr,
+        vec!["1".to_string(), "2".to_string(), "3".to_string()],
+    )
+    .unwrap();
+    app.update_block(next_block);
+
+    let player_limits: PlayerLimitsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerLimits {
+                table_id: None,
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        player_limits,
+        PlayerLimitsResponse {
+            reallocations: 0,
+            reallocations_limit: 15,
+            fee_reallocation: 1,
+        }
+    );
+
+    // Players without registered NFTs keep the global limits
+    let player_limits: PlayerLimitsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerLimits {
+                table_id: None,
+                address: "user2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(player_limits.reallocations_limit, 10);
+    assert_eq!(player_limits.fee_reallocation, 5);
+
+    // The holder reallocation is charged the lower fee
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+    reallocate_tokens(&mut app, &pp_addr, &info, 1, 2).unwrap();
+
+    let pot_state: PotStateResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PotState {
+                table_id: None,
+                pot_id: 2,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pot_state.pot.amount,
+        Uint128::new((1_000_000 * 99 / 100) + 1_000_000)
+    );
+}
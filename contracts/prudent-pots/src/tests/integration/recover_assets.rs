@@ -0,0 +1,304 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Uint128};
+use cw721::OwnerOfResponse;
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{PotsStateResponse, QueryMsg, RaffleResponse, ReceiveNftMsg, RecoveryLogResponse},
+    state::{Cw721Token, Raffle},
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+        },
+        helpers::{game_end, mint_nfts, recover_assets, send_nft, sponsor_raffle},
+    },
+    ContractError,
+};
+
+const DENOM_STRAY: &str = "ustray";
+
+#[test]
+fn test_recover_assets_stray_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![
+            coin(100_000_000u128, DENOM_GAME.to_string()),
+            coin(100_000_000u128, DENOM_STRAY.to_string()),
+        ],
+        None,
+        None,
+    );
+
+    // Send by mistake a non game_denom coin and a cw721 token to the contract
+    app.send_tokens(
+        Addr::unchecked("user1"),
+        pp_addr.clone(),
+        &coins(10_000_000, DENOM_STRAY),
+    )
+    .unwrap();
+    mint_nfts(&mut app, &cw721_addr, 1, 2, Addr::unchecked("user1"));
+    app.execute_contract(
+        Addr::unchecked("user1"),
+        cw721_addr.clone(),
+        &cw721::Cw721ExecuteMsg::TransferNft {
+            recipient: pp_addr.to_string(),
+            token_id: "1".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // Register the other NFT as sponsored raffle prize
+    send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked("user1"),
+        &pp_addr,
+        "2",
+        &ReceiveNftMsg::SponsorRaffle {
+            table_id: None,
+            message: None,
+        },
+    )
+    .unwrap();
+
+    // Only the admin can recover assets
+    let res =
+        recover_assets(&mut app, &pp_addr, &mock_info("user1", &[]), false, vec![]).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // The raffle prize cannot be swept as a stray token
+    let res = recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        false,
+        vec![Cw721Token {
+            cw721_addr: cw721_addr.to_string(),
+            token_id: "2".to_string(),
+        }],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Cw721TokenNotRecoverable {})
+    ));
+
+    // The unwon raffle prize cannot be recovered before it expires
+    let res = recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        true,
+        vec![],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::RaffleNotExpired { .. })
+    ));
+
+    let game_denom_balance_before = app.wrap().query_balance(&pp_addr, DENOM_GAME).unwrap();
+
+    // Sweep the stray coin and token
+    recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        false,
+        vec![Cw721Token {
+            cw721_addr: cw721_addr.to_string(),
+            token_id: "1".to_string(),
+        }],
+    )
+    .unwrap();
+
+    let admin_stray_balance = app
+        .wrap()
+        .query_balance(ADMIN_ADDRESS, DENOM_STRAY)
+        .unwrap();
+    assert_eq!(admin_stray_balance.amount, Uint128::new(10_000_000u128));
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, ADMIN_ADDRESS);
+
+    // The game_denom balance is untouched
+    let game_denom_balance_after = app.wrap().query_balance(&pp_addr, DENOM_GAME).unwrap();
+    assert_eq!(game_denom_balance_before, game_denom_balance_after);
+
+    // The recovery is recorded in the audit log
+    let recovery_log: RecoveryLogResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RecoveryLog {
+                table_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(recovery_log.recoveries.len(), 1);
+    assert_eq!(recovery_log.recoveries[0].id, 1);
+    assert_eq!(
+        recovery_log.recoveries[0].recipient,
+        Addr::unchecked(ADMIN_ADDRESS)
+    );
+    assert_eq!(
+        recovery_log.recoveries[0].coins,
+        coins(10_000_000, DENOM_STRAY)
+    );
+    assert!(!recovery_log.recoveries[0].expired_raffle);
+
+    // Nothing left to recover
+    let res = recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        false,
+        vec![],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidInput {})
+    ));
+}
+
+#[test]
+fn test_recover_assets_expired_raffle_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Sponsor the raffle with both an NFT and game_denom funds
+    mint_nfts(&mut app, &cw721_addr, 1, 1, Addr::unchecked("user1"));
+    send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked("user1"),
+        &pp_addr,
+        "1",
+        &ReceiveNftMsg::SponsorRaffle {
+            table_id: None,
+            message: None,
+        },
+    )
+    .unwrap();
+    sponsor_raffle(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(10_000_000, DENOM_GAME)),
+        None,
+    )
+    .unwrap();
+
+    // Nobody plays for two rounds, so the raffle prize is unwon for more than RAFFLE_EXPIRY_ROUNDS
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+    // A sponsorship of the previous round has only been unwon for one round, so it is not expired either
+    sponsor_raffle(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(2_000_000, DENOM_GAME)),
+        None,
+    )
+    .unwrap();
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+
+    // A sponsorship of the current round is not part of the expired prize
+    sponsor_raffle(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(3_000_000, DENOM_GAME)),
+        None,
+    )
+    .unwrap();
+
+    let admin_balance_before = app.wrap().query_balance(ADMIN_ADDRESS, DENOM_GAME).unwrap();
+    let pots_state_before: PotsStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
+        .unwrap();
+
+    recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        true,
+        vec![],
+    )
+    .unwrap();
+
+    // The expired raffle prizes are sent to the admin
+    let admin_balance_after = app.wrap().query_balance(ADMIN_ADDRESS, DENOM_GAME).unwrap();
+    assert_eq!(
+        admin_balance_after.amount - admin_balance_before.amount,
+        Uint128::new(10_000_000u128)
+    );
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, ADMIN_ADDRESS);
+
+    // The raffle is reset to the previous and current round sponsorships and the pots are untouched
+    let raffle: RaffleResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
+        .unwrap();
+    assert_eq!(
+        raffle.raffle,
+        Raffle {
+            cw721_token_id: None,
+            cw721_addr: None,
+            denom_amount: Uint128::new(5_000_000u128),
+        }
+    );
+    let pots_state_after: PotsStateResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PotsState { table_id: None })
+        .unwrap();
+    assert_eq!(pots_state_before, pots_state_after);
+
+    let recovery_log: RecoveryLogResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RecoveryLog {
+                table_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(recovery_log.recoveries.len(), 1);
+    assert!(recovery_log.recoveries[0].expired_raffle);
+    assert_eq!(
+        recovery_log.recoveries[0].cw721_tokens,
+        vec![Cw721Token {
+            cw721_addr: cw721_addr.to_string(),
+            token_id: "1".to_string(),
+        }]
+    );
+}
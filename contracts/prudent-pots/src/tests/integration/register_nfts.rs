@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Decimal, Empty, Uint128};
+use cw_multi_test::{next_block, Executor};
+
+use crate::{
+    msg::{BidRangeResponse, ExecuteMsg, NftRegistrationsResponse, QueryMsg, UpdateGameConfig},
+    state::{Cw721Collection, RegisteredNft, TraitMultiplier},
+    tests::integration::{
+        fixtures::{
+            default_cw721_collection, default_cw721_msg, default_with_balances, instantiate_cw721,
+            metadata_cw721_contract, DENOM_GAME, MIN_BID_FLOOR,
+        },
+        helpers::{allocate_tokens, mint_nft_with_traits, mint_nfts, register_nfts, update_config},
+    },
+    ContractError,
+};
+
+fn query_min_bid(app: &cw_multi_test::App, pp_addr: &Addr, address: &str) -> Uint128 {
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            pp_addr,
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some(address.to_string()),
+            },
+        )
+        .unwrap();
+    bid_range.min_bid
+}
+
+#[test]
+fn test_register_nfts_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    mint_nfts(&mut app, &cw721_addr, 1, 4, Addr::unchecked("user1"));
+    mint_nfts(&mut app, &cw721_addr, 5, 5, Addr::unchecked("user2"));
+    let info = mock_info("user1", &[]);
+
+    // Tokens owned by someone else cannot be registered
+    let res = register_nfts(
+        &mut app,
+        &pp_addr,
+        &info,
+        &cw721_addr,
+        vec!["1".to_string(), "5".to_string()],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Cw721TokenNotOwned { token_id }) if token_id == "5"
+    ));
+
+    // Registering more tokens than the collection max_tokens_counted fails
+    let res = register_nfts(
+        &mut app,
+        &pp_addr,
+        &info,
+        &cw721_addr,
+        vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+        ],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidInput {})
+    ));
+
+    // Collections not whitelisted cannot be registered
+    let res = register_nfts(
+        &mut app,
+        &pp_addr,
+        &info,
+        &Addr::unchecked("not_whitelisted"),
+        vec!["1".to_string()],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidCw721Collection {})
+    ));
+
+    // Holding NFTs without registering them grants no discount
+    assert_eq!(
+        query_min_bid(&app, &pp_addr, "user1"),
+        Uint128::new(1_000_000u128)
+    );
+
+    // The registration does not count in the same block it has been made
+    register_nfts(
+        &mut app,
+        &pp_addr,
+        &info,
+        &cw721_addr,
+        vec!["1".to_string(), "2".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+        query_min_bid(&app, &pp_addr, "user1"),
+        Uint128::new(1_000_000u128)
+    );
+
+    // From the next block 1_000_000 is discounted by 5% twice
+    app.update_block(next_block);
+    assert_eq!(
+        query_min_bid(&app, &pp_addr, "user1"),
+        Uint128::new(902_500u128)
+    );
+
+    // Transferring a registered token away invalidates it when the snapshot is re-verified on bid
+    app.execute_contract(
+        Addr::unchecked("user1"),
+        cw721_addr.clone(),
+        &cw721_base::msg::ExecuteMsg::<Option<Empty>, Empty>::TransferNft {
+            recipient: "user2".to_string(),
+            token_id: "2".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    // The bid range already leaves the transferred token out, matching what the bid will accept
+    assert_eq!(
+        query_min_bid(&app, &pp_addr, "user1"),
+        Uint128::new(950_000u128)
+    );
+    let info = mock_info("user1", &coins(902_500, DENOM_GAME));
+    let res = allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap_err();
+    if let Some(ContractError::BidOutOfRange { min, max: _ }) = res.downcast_ref::<ContractError>()
+    {
+        assert_eq!(*min, Uint128::new(950_000u128));
+    } else {
+        panic!("Expected ContractError::BidOutOfRange");
+    }
+
+    let info = mock_info("user1", &coins(950_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+
+    let registrations: NftRegistrationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::NftRegistrations {
+                table_id: None,
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(registrations.registrations.len(), 1);
+    assert_eq!(
+        registrations.registrations[0].tokens,
+        vec![RegisteredNft {
+            token_id: "1".to_string(),
+            weight: 1
+        }]
+    );
+}
+
+#[test]
+fn test_register_nfts_trait_weights_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Whitelist a collection with metadata, where rarer tokens count as more tokens
+    let metadata_cw721_id = app.store_code(metadata_cw721_contract());
+    let metadata_cw721_addr = instantiate_cw721(&mut app, metadata_cw721_id, default_cw721_msg());
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: None,
+                game_cw721_collections: vec![
+                    default_cw721_collection(&cw721_addr),
+                    Cw721Collection {
+                        addr: metadata_cw721_addr.clone(),
+                        discount_per_token: Decimal::from_str("0.05").unwrap(),
+                        max_tokens_counted: 3,
+                        trait_multipliers: vec![
+                            TraitMultiplier {
+                                trait_type: "Rarity".to_string(),
+                                value: "Legendary".to_string(),
+                                weight: 3,
+                            },
+                            TraitMultiplier {
+                                trait_type: "Rarity".to_string(),
+                                value: "Rare".to_string(),
+                                weight: 2,
+                            },
+                        ],
+                        extra_reallocations_per_token: 0,
+                        max_extra_reallocations: 0,
+                        holder_fee_reallocation: None,
+                    },
+                ],
+                game_duration: None,
+                game_duration_epoch: None,
+                game_extend: None,
+                game_end_threshold: None,
+                min_pot_initial_allocation: None,
+                decay_factor: None,
+                nft_claim_timeout: None,
+                raffle_expiry_rounds: None,
+                min_bid_floor: None,
+                voting_power_sources: None,
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
+            }),
+        },
+    )
+    .unwrap();
+
+    let user1 = Addr::unchecked("user1");
+    let user2 = Addr::unchecked("user2");
+    mint_nft_with_traits(
+        &mut app,
+        &metadata_cw721_addr,
+        "1",
+        &user1,
+        vec![("Rarity", "Legendary"), ("Background", "Gold")],
+    );
+    mint_nft_with_traits(
+        &mut app,
+        &metadata_cw721_addr,
+        "2",
+        &user2,
+        vec![("Rarity", "Rare")],
+    );
+    mint_nft_with_traits(
+        &mut app,
+        &metadata_cw721_addr,
+        "3",
+        &user2,
+        vec![("Rarity", "Rare")],
+    );
+
+    register_nfts(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        &metadata_cw721_addr,
+        vec!["1".to_string()],
+    )
+    .unwrap();
+    register_nfts(
+        &mut app,
+        &pp_addr,
+        &mock_info("user2", &[]),
+        &metadata_cw721_addr,
+        vec!["2".to_string(), "3".to_string()],
+    )
+    .unwrap();
+    app.update_block(next_block);
+
+    // The token weight is cached at registration
+    let registrations: NftRegistrationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::NftRegistrations {
+                table_id: None,
+                address: "user2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        registrations.registrations[0].tokens,
+        vec![
+            RegisteredNft {
+                token_id: "2".to_string(),
+                weight: 2
+            },
+            RegisteredNft {
+                token_id: "3".to_string(),
+                weight: 2
+            }
+        ]
+    );
+
+    // A single legendary token counts as 3 tokens, 1_000_000 discounted by 5% three times is raised to the floor
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user1".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.min_bid, Uint128::new(MIN_BID_FLOOR));
+    assert_eq!(bid_range.discounts[0].tokens_counted, 3);
+
+    // Two rare tokens count as 4 tokens, capped to the collection max_tokens_counted
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: Some("user2".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.discounts[0].tokens_counted, 3);
+    assert_eq!(bid_range.discounts[0].discount, Uint128::new(142_625u128));
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::{coin, testing::mock_info, Uint128};
+
+use crate::{
+    msg::{QueryMsg, SeasonStandingsResponse},
+    state::{SeasonConfig, SeasonPayout, SeasonPointsMode, SeasonPoolSource, SeasonStanding},
+    tests::integration::{
+        fixtures::{default_with_balances, ADMIN_ADDRESS, DENOM_GAME},
+        helpers::{play_round, query_season, start_season},
+    },
+    ContractError,
+};
+
+#[test]
+fn test_season_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+    let season_config = SeasonConfig {
+        rounds: 2,
+        points_mode: SeasonPointsMode::Placements { points: vec![3, 1] },
+        pool_source: SeasonPoolSource::Fee {},
+        pool_share: 50,
+        payout_shares: vec![70, 30],
+    };
+
+    // Only the owner can start a season
+    let res = start_season(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        season_config.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // The payout shares have to sum to 100
+    let res = start_season(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        SeasonConfig {
+            payout_shares: vec![70, 20],
+            ..season_config.clone()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "payout_shares"
+    ));
+
+    start_season(&mut app, &pp_addr, &admin_info, season_config.clone()).unwrap();
+
+    // A season cannot be started while another one is in progress
+    let res = start_season(&mut app, &pp_addr, &admin_info, season_config.clone()).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::SeasonInProgress {})
+    ));
+
+    // Nobody allocated yet, so the current round is counted
+    let season = query_season(&app, &pp_addr);
+    let season = season.season.unwrap();
+    assert_eq!(season.id, 1);
+    assert_eq!((season.start_round, season.end_round), (1, 2));
+    // The pool is paid out in the game denom the season started with
+    assert_eq!(season.denom, DENOM_GAME);
+
+    play_round(&mut app, &pp_addr);
+
+    // The winners of the round are awarded points by placement and the fee slice feeds the season pool
+    let standings: SeasonStandingsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::SeasonStandings {
+                table_id: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(standings.season_id, Some(1));
+    assert_eq!(
+        standings.standings,
+        vec![SeasonStanding {
+            player: "user5".to_string(),
+            points: Uint128::new(3),
+        }]
+    );
+    let season = query_season(&app, &pp_addr);
+    assert_eq!(season.season.unwrap().pool, Uint128::new(224_180));
+
+    // Once the last round is ended the pool is paid out, the share of the empty positions rolls over
+    play_round(&mut app, &pp_addr);
+
+    let season = query_season(&app, &pp_addr);
+    assert_eq!(season.round_count, 3);
+    let season = season.season.unwrap();
+    assert!(season.finished);
+    assert_eq!(season.pool, Uint128::zero());
+    assert_eq!(
+        season.payouts,
+        vec![SeasonPayout {
+            player: "user5".to_string(),
+            points: Uint128::new(6),
+            amount: Uint128::new(319_606),
+        }]
+    );
+
+    // A new season can be started once the previous one is finished
+    start_season(&mut app, &pp_addr, &admin_info, season_config).unwrap();
+    let season = query_season(&app, &pp_addr);
+    let season = season.season.unwrap();
+    assert_eq!(season.id, 2);
+    assert_eq!((season.start_round, season.end_round), (3, 4));
+}
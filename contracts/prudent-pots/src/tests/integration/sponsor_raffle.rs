@@ -0,0 +1,203 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Uint128};
+use cw721::OwnerOfResponse;
+
+use crate::{
+    msg::{QueryMsg, RaffleResponse, RaffleSponsorsResponse, ReceiveNftMsg},
+    state::{Raffle, RaffleSponsor},
+    tests::integration::{
+        fixtures::{default_with_balances, DENOM_GAME},
+        helpers::{mint_nfts, send_nft, sponsor_raffle},
+    },
+    ContractError,
+};
+
+#[test]
+fn test_sponsor_raffle_denom_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        2,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // Any address can sponsor the raffle by attaching game_denom funds
+    let info_1 = mock_info("user1", &coins(10_000_000, DENOM_GAME));
+    sponsor_raffle(
+        &mut app,
+        &pp_addr,
+        &info_1,
+        Some("Brought to you by user1".to_string()),
+    )
+    .unwrap();
+    let info_2 = mock_info("user2", &coins(5_000_000, DENOM_GAME));
+    sponsor_raffle(&mut app, &pp_addr, &info_2, None).unwrap();
+
+    // Raffle denom amount is incremented by both sponsorships
+    let raffle: RaffleResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
+        .unwrap();
+    assert_eq!(raffle.raffle.denom_amount, Uint128::new(15_000_000u128));
+
+    // Sponsors are recorded for the current round
+    let sponsors: RaffleSponsorsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RaffleSponsors {
+                table_id: None,
+                round: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        sponsors.sponsors,
+        vec![
+            RaffleSponsor {
+                sponsor: Addr::unchecked("user1"),
+                denom_amount: Uint128::new(10_000_000u128),
+                cw721_addr: None,
+                cw721_token_id: None,
+                message: Some("Brought to you by user1".to_string()),
+            },
+            RaffleSponsor {
+                sponsor: Addr::unchecked("user2"),
+                denom_amount: Uint128::new(5_000_000u128),
+                cw721_addr: None,
+                cw721_token_id: None,
+                message: None,
+            }
+        ]
+    );
+
+    // Sponsors are paginated by their index in the round
+    let sponsors: RaffleSponsorsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RaffleSponsors {
+                table_id: None,
+                round: 1,
+                start_after: Some(0),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(sponsors.sponsors.len(), 1);
+    assert_eq!(sponsors.sponsors[0].sponsor, Addr::unchecked("user2"));
+
+    // Sponsoring without funds fails
+    let res = sponsor_raffle(&mut app, &pp_addr, &mock_info("user1", &[]), None).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidFunds {})
+    ));
+
+    // Sponsoring with a too long message fails
+    let res = sponsor_raffle(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(1_000_000, DENOM_GAME)),
+        Some("x".repeat(257)),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::SponsorMessageTooLong { .. })
+    ));
+}
+
+#[test]
+fn test_sponsor_raffle_nft_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // mint 2x nfts as minter to user1
+    mint_nfts(&mut app, &cw721_addr, 1, 2, Addr::unchecked("user1"));
+
+    // Send the NFT to the contract as sponsored raffle prize, no approval required
+    send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked("user1"),
+        &pp_addr,
+        "1",
+        &ReceiveNftMsg::SponsorRaffle {
+            table_id: None,
+            message: Some("gm".to_string()),
+        },
+    )
+    .unwrap();
+
+    let raffle: RaffleResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
+        .unwrap();
+    assert_eq!(
+        raffle.raffle,
+        Raffle {
+            cw721_token_id: Some("1".to_string()),
+            cw721_addr: Some(cw721_addr.to_string()),
+            denom_amount: Uint128::zero(),
+        }
+    );
+
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, pp_addr.to_string());
+
+    let sponsors: RaffleSponsorsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::RaffleSponsors {
+                table_id: None,
+                round: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        sponsors.sponsors,
+        vec![RaffleSponsor {
+            sponsor: Addr::unchecked("user1"),
+            denom_amount: Uint128::zero(),
+            cw721_addr: Some(cw721_addr.to_string()),
+            cw721_token_id: Some("1".to_string()),
+            message: Some("gm".to_string()),
+        }]
+    );
+
+    // A second NFT cannot be sponsored while the raffle already has one
+    let res = send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked("user1"),
+        &pp_addr,
+        "2",
+        &ReceiveNftMsg::SponsorRaffle {
+            table_id: None,
+            message: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidRaffleNft {})
+    ));
+}
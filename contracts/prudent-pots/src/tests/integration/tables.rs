@@ -0,0 +1,254 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, to_json_binary, Addr, Uint128};
+use cw721::OwnerOfResponse;
+use cw_multi_test::Executor;
+
+use crate::{
+    msg::{
+        ExecuteMsg, GameConfigResponse, GameStateResponse, PotStateResponse, QueryMsg,
+        ReceiveNftMsg, TablesResponse,
+    },
+    state::Cw721Token,
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+        },
+        helpers::{allocate_tokens, create_table, game_end, mint_nfts, recover_assets},
+    },
+    ContractError,
+};
+
+const DENOM_TABLE: &str = "utable";
+
+fn query_game_state(
+    app: &cw_multi_test::App,
+    pp_addr: &Addr,
+    table_id: Option<u64>,
+) -> GameStateResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::GameState { table_id })
+        .unwrap()
+}
+
+fn query_pot_state(
+    app: &cw_multi_test::App,
+    pp_addr: &Addr,
+    table_id: Option<u64>,
+    pot_id: u8,
+) -> PotStateResponse {
+    app.wrap()
+        .query_wasm_smart(pp_addr, &QueryMsg::PotState { table_id, pot_id })
+        .unwrap()
+}
+
+#[test]
+fn test_tables_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![
+            coin(100_000_000u128, DENOM_GAME.to_string()),
+            coin(100_000_000u128, DENOM_TABLE.to_string()),
+        ],
+        None,
+        None,
+    );
+    app.init_modules(|router, _, storage| {
+        router.bank.init_balance(
+            storage,
+            &Addr::unchecked(ADMIN_ADDRESS),
+            vec![
+                coin(100_000_000u128, DENOM_GAME.to_string()),
+                coin(100_000_000u128, DENOM_TABLE.to_string()),
+            ],
+        )
+    })
+    .unwrap();
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    let mut table_config = game_config.config;
+
+    // Only the owner can create tables
+    let res = create_table(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(5_000_000, DENOM_GAME)),
+        table_config.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Tables share the contract balances, so each one needs its own denom
+    let res = create_table(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &coins(5_000_000, DENOM_GAME)),
+        table_config.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::DenomInUse { denom, table_id: 0 }) if denom == DENOM_GAME
+    ));
+
+    table_config.game_denom = DENOM_TABLE.to_string();
+    create_table(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &coins(5_000_000, DENOM_TABLE)),
+        table_config,
+    )
+    .unwrap();
+
+    let tables: TablesResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::Tables {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(tables.tables.len(), 2);
+    assert_eq!(tables.tables[1].id, 1);
+    assert_eq!(tables.tables[1].denoms, vec![DENOM_TABLE.to_string()]);
+
+    // Each table has its own pots, played with its own denom
+    app.execute_contract(
+        Addr::unchecked("user1"),
+        pp_addr.clone(),
+        &ExecuteMsg::AllocateTokens {
+            table_id: Some(1),
+            pot_id: 2,
+        },
+        &coins(1_000_000, DENOM_TABLE),
+    )
+    .unwrap();
+    let res = app
+        .execute_contract(
+            Addr::unchecked("user1"),
+            pp_addr.clone(),
+            &ExecuteMsg::AllocateTokens {
+                table_id: Some(1),
+                pot_id: 2,
+            },
+            &coins(1_200_000, DENOM_GAME),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidFunds {})
+    ));
+    assert_eq!(
+        query_pot_state(&app, &pp_addr, Some(1), 2).pot.amount,
+        Uint128::new(2_000_000)
+    );
+    assert_eq!(
+        query_pot_state(&app, &pp_addr, None, 2).pot.amount,
+        Uint128::new(1_000_000)
+    );
+
+    // Messages without a table id play on the default table, keeping its own round counter
+    allocate_tokens(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &coins(1_000_000, DENOM_GAME)),
+        3,
+    )
+    .unwrap();
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+    assert_eq!(query_game_state(&app, &pp_addr, None).state.round_count, 2);
+    assert_eq!(
+        query_game_state(&app, &pp_addr, Some(1)).state.round_count,
+        1
+    );
+
+    // The raffle prize of a table cannot be swept through another table
+    mint_nfts(&mut app, &cw721_addr, 1, 1, Addr::unchecked("user1"));
+    app.execute_contract(
+        Addr::unchecked("user1"),
+        cw721_addr.clone(),
+        &cw721::Cw721ExecuteMsg::SendNft {
+            contract: pp_addr.to_string(),
+            token_id: "1".to_string(),
+            msg: to_json_binary(&ReceiveNftMsg::SponsorRaffle {
+                table_id: Some(1),
+                message: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let res = recover_assets(
+        &mut app,
+        &pp_addr,
+        &mock_info(ADMIN_ADDRESS, &[]),
+        false,
+        vec![Cw721Token {
+            cw721_addr: cw721_addr.to_string(),
+            token_id: "1".to_string(),
+        }],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Cw721TokenNotRecoverable {})
+    ));
+
+    // The raffle prize transfer reply is routed back to the table that ended its round
+    app.send_tokens(
+        Addr::unchecked(ADMIN_ADDRESS),
+        pp_addr.clone(),
+        &coins(10_000_000, DENOM_TABLE),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(ADMIN_ADDRESS),
+        pp_addr.clone(),
+        &ExecuteMsg::GameEnd {
+            table_id: Some(1),
+            next_game_start: None,
+        },
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        query_game_state(&app, &pp_addr, Some(1)).state.round_count,
+        2
+    );
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, "user1");
+
+    // Unknown tables are rejected
+    let res = app
+        .execute_contract(
+            Addr::unchecked("user1"),
+            pp_addr.clone(),
+            &ExecuteMsg::AllocateTokens {
+                table_id: Some(2),
+                pot_id: 1,
+            },
+            &coins(1_000_000, DENOM_TABLE),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::TableNotFound { table_id: 2 })
+    ));
+}
@@ -1,11 +1,39 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Decimal, Uint128};
+use cw_multi_test::App;
 
-use crate::msg::{ExecuteMsg, GameConfigResponse, QueryMsg, UpdateGameConfig};
-use crate::state::GameConfig;
-use crate::tests::integration::fixtures::{default_with_balances, DENOM_GAME, GAME_DURATION};
-use crate::tests::integration::helpers::update_config;
+use crate::msg::{
+    BidCurveResponse, BidRangeResponse, ExecuteMsg, GameConfigResponse, PendingConfigResponse,
+    QueryMsg, UpdateGameConfig,
+};
+use crate::state::{BidCurve, Cw721Collection, GameConfig};
+use crate::tests::integration::fixtures::{
+    default_cw721_collection, default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME,
+    GAME_DURATION, GAME_EXTEND, TIMELOCK_DELAY,
+};
+use crate::tests::integration::helpers::{allocate_tokens, game_end, update_config};
+use crate::ContractError;
+
+fn empty_update_config(cw721_addr: &Addr) -> UpdateGameConfig {
+    UpdateGameConfig {
+        fee_reallocation: None,
+        game_cw721_collections: vec![default_cw721_collection(cw721_addr)],
+        game_duration: None,
+        game_duration_epoch: None,
+        game_extend: None,
+        game_end_threshold: None,
+        min_pot_initial_allocation: None,
+        decay_factor: None,
+        nft_claim_timeout: None,
+        raffle_expiry_rounds: None,
+        min_bid_floor: None,
+        voting_power_sources: None,
+        bid_curve: None,
+        max_bid_multiplier: None,
+        max_player_exposure: None,
+    }
+}
 
 #[test]
 fn test_update_config_works() {
@@ -21,19 +49,31 @@ fn test_update_config_works() {
         &mut app,
         &pp_addr,
         &ExecuteMsg::UpdateConfig {
+            table_id: None,
             config: Box::new(UpdateGameConfig {
-                fee: Some(10),
                 fee_reallocation: Some(10),
-                fee_address: Some(Addr::unchecked("new_address")),
-                game_denom: Some("new_denom".to_string()),
-                game_cw721_addrs: vec![Addr::unchecked("test")],
+                game_cw721_collections: vec![Cw721Collection {
+                    addr: Addr::unchecked("test"),
+                    discount_per_token: Decimal::from_str("0.1").unwrap(),
+                    max_tokens_counted: 1,
+                    trait_multipliers: vec![],
+                    extra_reallocations_per_token: 0,
+                    max_extra_reallocations: 0,
+                    holder_fee_reallocation: None,
+                }],
                 game_duration: Some(GAME_DURATION * 2),
                 game_duration_epoch: Some(600 * 3),
                 game_extend: Some(600 * 3),
                 game_end_threshold: Some(600 * 3),
                 min_pot_initial_allocation: Some(Uint128::new(1_000_000u128)),
                 decay_factor: Some(Decimal::from_str("0.5").unwrap()),
-                reallocations_limit: Some(10),
+                nft_claim_timeout: Some(86400),
+                raffle_expiry_rounds: Some(10),
+                min_bid_floor: Some(Uint128::new(900_000u128)),
+                voting_power_sources: Some(vec![]),
+                bid_curve: None,
+                max_bid_multiplier: None,
+                max_player_exposure: None,
             }),
         },
     )
@@ -44,16 +84,24 @@ fn test_update_config_works() {
 
     let new_config: GameConfigResponse = app
         .wrap()
-        .query_wasm_smart(pp_addr.clone(), &QueryMsg::GameConfig {})
+        .query_wasm_smart(pp_addr.clone(), &QueryMsg::GameConfig { table_id: None })
         .unwrap();
 
     let expected_new_config = GameConfigResponse {
         config: GameConfig {
-            fee: 10,
+            fee: 5,
             fee_reallocation: 10,
-            fee_address: Addr::unchecked("new_address"),
-            game_denom: "new_denom".to_string(),
-            game_cw721_addrs: vec![Addr::unchecked("test")],
+            fee_address: Addr::unchecked("treasury_addr"),
+            game_denom: DENOM_GAME.to_string(),
+            game_cw721_collections: vec![Cw721Collection {
+                addr: Addr::unchecked("test"),
+                discount_per_token: Decimal::from_str("0.1").unwrap(),
+                max_tokens_counted: 1,
+                trait_multipliers: vec![],
+                extra_reallocations_per_token: 0,
+                max_extra_reallocations: 0,
+                holder_fee_reallocation: None,
+            }],
             game_duration: GAME_DURATION * 2,
             game_duration_epoch: 600 * 3,
             game_extend: 600 * 3,
@@ -61,7 +109,301 @@ fn test_update_config_works() {
             min_pot_initial_allocation: Uint128::new(1_000_000u128),
             decay_factor: Decimal::from_str("0.5").unwrap(),
             reallocations_limit: 10,
+            nft_claim_timeout: 86400,
+            raffle_expiry_rounds: 10,
+            min_bid_floor: Uint128::new(900_000u128),
+            voting_power_sources: vec![],
+            timelock_delay: TIMELOCK_DELAY,
+            bid_curve: BidCurve::Linear {},
+            max_bid_multiplier: Decimal::from_ratio(2u128, 1u128),
+            max_player_exposure: Uint128::zero(),
         },
     };
     assert_eq!(new_config, expected_new_config);
 }
+
+#[test]
+fn test_update_config_invalid_field_fails() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    let cases = vec![
+        (
+            "fee_reallocation",
+            UpdateGameConfig {
+                fee_reallocation: Some(51),
+                ..empty_update_config(&cw721_addr)
+            },
+        ),
+        (
+            "game_duration_epoch",
+            UpdateGameConfig {
+                game_duration_epoch: Some(0),
+                ..empty_update_config(&cw721_addr)
+            },
+        ),
+        // Shortening the game duration below the current game extend is checked as well
+        (
+            "game_extend",
+            UpdateGameConfig {
+                game_duration: Some(1),
+                ..empty_update_config(&cw721_addr)
+            },
+        ),
+        (
+            "decay_factor",
+            UpdateGameConfig {
+                decay_factor: Some(Decimal::one()),
+                ..empty_update_config(&cw721_addr)
+            },
+        ),
+        (
+            "bid_curve",
+            UpdateGameConfig {
+                bid_curve: Some(BidCurve::Stepwise {
+                    multipliers: vec![],
+                }),
+                ..empty_update_config(&cw721_addr)
+            },
+        ),
+    ];
+    for (expected_field, config) in cases {
+        let res = update_config(
+            &mut app,
+            &pp_addr,
+            &ExecuteMsg::UpdateConfig {
+                table_id: None,
+                config: Box::new(config),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            res.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidConfigField { field, .. }) if field == expected_field
+        ));
+    }
+}
+
+#[test]
+fn test_update_config_pending_next_round_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let info = mock_info("user1", &coins(1_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info, 1).unwrap();
+
+    // With a player committed to the round, only the emergency fields take effect immediately
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                fee_reallocation: Some(3),
+                decay_factor: Some(Decimal::from_str("0.1").unwrap()),
+                nft_claim_timeout: Some(3600),
+                ..empty_update_config(&cw721_addr)
+            }),
+        },
+    )
+    .unwrap();
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.fee_reallocation, 5);
+    assert_eq!(
+        game_config.config.decay_factor,
+        Decimal::from_str("0.05").unwrap()
+    );
+    assert_eq!(game_config.config.nft_claim_timeout, 3600);
+
+    let pending_config: PendingConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PendingConfig { table_id: None })
+        .unwrap();
+    assert_eq!(
+        pending_config.changed_fields,
+        vec!["fee_reallocation".to_string(), "decay_factor".to_string()]
+    );
+    assert_eq!(pending_config.activates_at_round, Some(2));
+    assert_eq!(pending_config.config.unwrap().nft_claim_timeout, 3600);
+
+    // The pending changes apply once the round is ended
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &[]), None).unwrap();
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.fee_reallocation, 3);
+    assert_eq!(
+        game_config.config.decay_factor,
+        Decimal::from_str("0.1").unwrap()
+    );
+
+    let pending_config: PendingConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::PendingConfig { table_id: None })
+        .unwrap();
+    assert!(pending_config.config.is_none());
+    assert!(pending_config.changed_fields.is_empty());
+}
+
+#[test]
+fn test_update_config_bid_curve_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let query_curve = |app: &App| -> Vec<(Uint128, Uint128)> {
+        let res: BidCurveResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &pp_addr,
+                &QueryMsg::BidCurve {
+                    table_id: None,
+                    epochs: Some(3),
+                },
+            )
+            .unwrap();
+        res.points
+            .into_iter()
+            .map(|point| (point.min_bid, point.max_bid))
+            .collect()
+    };
+
+    // The default linear curve grows by the decay factor at each epoch
+    assert_eq!(
+        query_curve(&app),
+        vec![
+            (Uint128::new(1_000_000), Uint128::new(2_000_000)),
+            (Uint128::new(1_050_000), Uint128::new(2_000_000)),
+            (Uint128::new(1_100_000), Uint128::new(2_000_000)),
+        ]
+    );
+
+    // Nobody allocated yet, so the curve applies to the current round right away
+    let curves = vec![
+        (
+            BidCurve::Stepwise {
+                multipliers: vec![Decimal::one(), Decimal::from_str("2").unwrap()],
+            },
+            vec![
+                (Uint128::new(1_000_000), Uint128::new(2_000_000)),
+                (Uint128::new(2_000_000), Uint128::new(4_000_000)), // the max bid doubles the min bid once above the average
+                (Uint128::new(2_000_000), Uint128::new(4_000_000)),
+            ],
+        ),
+        (
+            BidCurve::Exponential {
+                growth: Decimal::from_str("0.1").unwrap(),
+            },
+            vec![
+                (Uint128::new(1_000_000), Uint128::new(2_000_000)),
+                (Uint128::new(1_100_000), Uint128::new(2_000_000)),
+                (Uint128::new(1_210_000), Uint128::new(2_000_000)),
+            ],
+        ),
+        (
+            BidCurve::PotValue {
+                ratio: Decimal::from_str("0.3").unwrap(),
+            },
+            vec![(Uint128::new(1_500_000), Uint128::new(2_000_000)); 3],
+        ),
+    ];
+    for (bid_curve, expected_points) in curves {
+        update_config(
+            &mut app,
+            &pp_addr,
+            &ExecuteMsg::UpdateConfig {
+                table_id: None,
+                config: Box::new(UpdateGameConfig {
+                    bid_curve: Some(bid_curve),
+                    ..empty_update_config(&cw721_addr)
+                }),
+            },
+        )
+        .unwrap();
+        assert_eq!(query_curve(&app), expected_points);
+    }
+
+    // The max bid multiplier is bounded, so the max bid stays representable
+    let res = update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                max_bid_multiplier: Some(Decimal::from_ratio(101u128, 1u128)),
+                ..empty_update_config(&cw721_addr)
+            }),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "max_bid_multiplier"
+    ));
+
+    // The exponential growth has to stay representable until the last epoch of a round
+    let res = update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                game_duration_epoch: Some(1),
+                bid_curve: Some(BidCurve::Exponential {
+                    growth: Decimal::one(),
+                }),
+                ..empty_update_config(&cw721_addr)
+            }),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "bid_curve"
+    ));
+
+    // The bid range follows the same curve as the epochs go by
+    update_config(
+        &mut app,
+        &pp_addr,
+        &ExecuteMsg::UpdateConfig {
+            table_id: None,
+            config: Box::new(UpdateGameConfig {
+                bid_curve: Some(BidCurve::Exponential {
+                    growth: Decimal::from_str("0.1").unwrap(),
+                }),
+                ..empty_update_config(&cw721_addr)
+            }),
+        },
+    )
+    .unwrap();
+    increase_app_time(&mut app, GAME_EXTEND * 2);
+    let bid_range: BidRangeResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::BidRange {
+                table_id: None,
+                address: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(bid_range.min_bid, Uint128::new(1_210_000));
+    assert_eq!(query_curve(&app)[0].0, Uint128::new(1_210_000));
+}
@@ -1,12 +1,13 @@
 use crate::{
-    msg::{GameStateResponse, QueryMsg, RaffleResponse},
+    msg::{ExecuteMsg, GameStateResponse, QueryMsg, RaffleResponse, ReceiveNftMsg},
     tests::integration::{
         fixtures::{default_with_balances, ADMIN_ADDRESS, DENOM_GAME},
-        helpers::{game_end, mint_nfts, update_next_game},
+        helpers::{game_end, mint_nfts, send_nft, update_next_game},
     },
+    ContractError,
 };
-use cosmwasm_std::{coin, coins, testing::mock_info, Addr, Uint128};
-use cw721::OwnerOfResponse;
+use cosmwasm_std::{coin, coins, testing::mock_info, to_json_binary, Addr, Uint128};
+use cw721::{Cw721ReceiveMsg, OwnerOfResponse};
 use cw_multi_test::Executor;
 
 use super::fixtures::{increase_app_time, GAME_DURATION};
@@ -23,7 +24,7 @@ fn test_update_next_game_works() {
     // Game state extend_count after
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.extend_count, 0);
     assert_eq!(game_state.state.round_count, 1);
@@ -36,12 +37,12 @@ fn test_update_next_game_works() {
 
     // Game end and set NO raffle prizes
     let info = mock_info(ADMIN_ADDRESS, &vec![]);
-    game_end(&mut app, &pp_addr, &info, None, None, Some(next_game_start)).unwrap();
+    game_end(&mut app, &pp_addr, &info, Some(next_game_start)).unwrap();
 
     // Assert game_state.start_time is now in the future
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.start_time, next_game_start);
 
@@ -57,8 +58,6 @@ fn test_update_next_game_works() {
         &mut app,
         &pp_addr,
         &mock_info(ADMIN_ADDRESS, &vec![]),
-        None,
-        None,
         Some(next_game_start), // Schedule it in the future
     )
     .unwrap();
@@ -66,11 +65,11 @@ fn test_update_next_game_works() {
     // Assert game_state.start_time is now in the future
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     let raffle_state: RaffleResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.start_time, next_game_start);
     assert_eq!(raffle_state.raffle.cw721_token_id, None);
@@ -79,35 +78,23 @@ fn test_update_next_game_works() {
     // mint 10x nfts as minter to admin
     mint_nfts(&mut app, &cw721_addr, 1, 2, Addr::unchecked(ADMIN_ADDRESS));
 
-    // ApproveAll as admin to operator pp contract
-    app.execute_contract(
-        Addr::unchecked(ADMIN_ADDRESS),
-        cw721_addr.clone(),
-        &cw721::Cw721ExecuteMsg::ApproveAll {
-            operator: pp_addr.to_string(),
-            expires: None,
-        },
-        &vec![],
-    )
-    .unwrap();
-
-    // Update the NFT to be some, from no NFT. we should approve it first
-    update_next_game(
+    // Update the NFT to be some, from no NFT. it is sent to the contract
+    send_nft(
         &mut app,
+        &cw721_addr,
+        &Addr::unchecked(ADMIN_ADDRESS),
         &pp_addr,
-        &mock_info(ADMIN_ADDRESS, &vec![]),
-        Some("1".to_string()),
-        Some(cw721_addr.to_string()),
-        None, // Schedule it in the future
+        "1",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
     )
     .unwrap();
     let game_state: GameStateResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::GameState {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameState { table_id: None })
         .unwrap();
     let raffle_state: RaffleResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
         .unwrap();
     assert_eq!(game_state.state.start_time, next_game_start);
     assert_eq!(raffle_state.raffle.cw721_token_id, Some("1".to_string()));
@@ -128,33 +115,120 @@ fn test_update_next_game_works() {
     assert_eq!(nft_owner.owner, Addr::unchecked(&pp_addr));
 
     // Try update the NFT with another one, we should get an error as InvalidNft
-    update_next_game(
+    let res = send_nft(
         &mut app,
+        &cw721_addr,
+        &Addr::unchecked(ADMIN_ADDRESS),
         &pp_addr,
-        &mock_info(ADMIN_ADDRESS, &vec![]),
-        Some("2".to_string()),
-        Some(cw721_addr.to_string()),
-        None, // Schedule it in the future
+        "2",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
     )
-    .unwrap_err(); // todo: assert type of error
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidRaffleNft {})
+    ));
+
+    // Without a start time nor funds there is nothing to update
+    let res =
+        update_next_game(&mut app, &pp_addr, &mock_info(ADMIN_ADDRESS, &vec![]), None).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidInput {})
+    ));
 
     // Update the next game again sending some funds and check the Raffle state before after
     update_next_game(
         &mut app,
         &pp_addr,
         &mock_info(ADMIN_ADDRESS, &coins(10_000_000, DENOM_GAME)),
-        None,
-        None,
         None, // Schedule it in the future
     )
     .unwrap(); // todo: assert type of error
 
     let raffle_state: RaffleResponse = app
         .wrap()
-        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle {})
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
         .unwrap();
     assert_eq!(
         raffle_state.raffle.denom_amount,
         Uint128::new(10_000_000u128)
     );
 }
+
+#[test]
+fn test_update_next_game_receive_nft_works() {
+    let (mut app, pp_addr, cw721_addr) = default_with_balances(
+        1,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+
+    // mint nfts as minter to admin and user1, no approval needed as they are sent via SendNft
+    mint_nfts(&mut app, &cw721_addr, 1, 1, Addr::unchecked(ADMIN_ADDRESS));
+    mint_nfts(&mut app, &cw721_addr, 2, 2, Addr::unchecked("user1"));
+
+    // Only the admin can register the raffle NFT this way
+    let res = send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked("user1"),
+        &pp_addr,
+        "2",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // A ReceiveNft hook not coming from a whitelisted collection is rejected
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ADMIN_ADDRESS),
+            pp_addr.clone(),
+            &ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: ADMIN_ADDRESS.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary(&ReceiveNftMsg::SetRaffleNft { table_id: None }).unwrap(),
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidRaffleNft {})
+    ));
+
+    // Send the NFT as admin, it is registered as the raffle prize
+    send_nft(
+        &mut app,
+        &cw721_addr,
+        &Addr::unchecked(ADMIN_ADDRESS),
+        &pp_addr,
+        "1",
+        &ReceiveNftMsg::SetRaffleNft { table_id: None },
+    )
+    .unwrap();
+
+    let raffle_state: RaffleResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::Raffle { table_id: None })
+        .unwrap();
+    assert_eq!(raffle_state.raffle.cw721_token_id, Some("1".to_string()));
+    assert_eq!(raffle_state.raffle.cw721_addr, Some(cw721_addr.to_string()));
+
+    let nft_owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &cw721_addr,
+            &cw721::Cw721QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(nft_owner.owner, pp_addr.to_string());
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::{coin, coins, testing::mock_info, Uint128};
+
+use crate::{
+    msg::{GameConfigResponse, PlayerVoteResponse, QueryMsg, VoteTallyResponse},
+    state::VoteOption,
+    tests::integration::{
+        fixtures::{
+            default_with_balances, increase_app_time, ADMIN_ADDRESS, DENOM_GAME, GAME_DURATION,
+        },
+        helpers::{allocate_tokens, game_end, query_votes, set_vote_options, vote},
+    },
+    ContractError,
+};
+
+fn default_vote_options() -> Vec<VoteOption> {
+    vec![
+        VoteOption {
+            label: "Longer rounds".to_string(),
+            game_duration: Some(GAME_DURATION * 2),
+            reallocations_limit: None,
+        },
+        VoteOption {
+            label: "More reallocations".to_string(),
+            game_duration: None,
+            reallocations_limit: Some(20),
+        },
+    ]
+}
+
+#[test]
+fn test_vote_works() {
+    let (mut app, pp_addr, _cw721_addr) = default_with_balances(
+        5,
+        vec![coin(100_000_000u128, DENOM_GAME.to_string())],
+        None,
+        None,
+    );
+    let admin_info = mock_info(ADMIN_ADDRESS, &[]);
+
+    // Only the owner can set the vote options
+    let res = set_vote_options(
+        &mut app,
+        &pp_addr,
+        &mock_info("user1", &[]),
+        default_vote_options(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::Unauthorized {})
+    ));
+
+    // Each option has to result in a valid config
+    let res = set_vote_options(
+        &mut app,
+        &pp_addr,
+        &admin_info,
+        vec![VoteOption {
+            label: "No rounds".to_string(),
+            game_duration: Some(0),
+            reallocations_limit: None,
+        }],
+    )
+    .unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidConfigField { field, .. }) if field == "game_duration"
+    ));
+
+    set_vote_options(&mut app, &pp_addr, &admin_info, default_vote_options()).unwrap();
+
+    // Only the players committed to the round can vote
+    let res = vote(&mut app, &pp_addr, &mock_info("user1", &[]), 0).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::NoAllocationsToVote {})
+    ));
+
+    let info_1 = mock_info("user1", &coins(2_000_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_1, 1).unwrap();
+    let info_2 = mock_info("user2", &coins(1_400_001, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_2, 2).unwrap();
+    let info_3 = mock_info("user3", &coins(1_680_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_3, 3).unwrap();
+    let info_4 = mock_info("user4", &coins(2_016_000, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_4, 4).unwrap();
+    let info_5 = mock_info("user5", &coins(2_419_200, DENOM_GAME));
+    allocate_tokens(&mut app, &pp_addr, &info_5, 5).unwrap();
+
+    let res = vote(&mut app, &pp_addr, &mock_info("user1", &[]), 2).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::InvalidVoteOption {})
+    ));
+
+    // Votes can be changed during the round
+    vote(&mut app, &pp_addr, &mock_info("user1", &[]), 0).unwrap();
+    vote(&mut app, &pp_addr, &mock_info("user1", &[]), 1).unwrap();
+    vote(&mut app, &pp_addr, &mock_info("user2", &[]), 0).unwrap();
+    vote(&mut app, &pp_addr, &mock_info("user3", &[]), 0).unwrap();
+
+    let player_vote: PlayerVoteResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::PlayerVote {
+                table_id: None,
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(player_vote.option_id, Some(1));
+
+    // The votes are weighted by the players allocations, the larger single allocation does not win alone
+    let votes = query_votes(&app, &pp_addr);
+    assert_eq!(
+        votes.tally.weights,
+        vec![Uint128::new(3_080_001), Uint128::new(2_000_000)]
+    );
+    assert_eq!(votes.tally.winning_option, Some(0));
+
+    // The options cannot change once the players started voting
+    let res =
+        set_vote_options(&mut app, &pp_addr, &admin_info, default_vote_options()).unwrap_err();
+    assert!(matches!(
+        res.downcast_ref::<ContractError>(),
+        Some(ContractError::VotingInProgress {})
+    ));
+
+    // The winning option applies to the next round and the tally is archived with the ended round
+    increase_app_time(&mut app, GAME_DURATION);
+    game_end(&mut app, &pp_addr, &admin_info, None).unwrap();
+
+    let game_config: GameConfigResponse = app
+        .wrap()
+        .query_wasm_smart(&pp_addr, &QueryMsg::GameConfig { table_id: None })
+        .unwrap();
+    assert_eq!(game_config.config.game_duration, GAME_DURATION * 2);
+    assert_eq!(game_config.config.reallocations_limit, 10);
+
+    let vote_tally: VoteTallyResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &pp_addr,
+            &QueryMsg::VoteTally {
+                table_id: None,
+                round: 1,
+            },
+        )
+        .unwrap();
+    let vote_tally = vote_tally.tally.unwrap();
+    assert_eq!(vote_tally.winning_option, Some(0));
+    assert!(vote_tally.applied);
+
+    // Votes do not carry over to the next round
+    let votes = query_votes(&app, &pp_addr);
+    assert_eq!(votes.round_count, 2);
+    assert_eq!(votes.tally.winning_option, None);
+}
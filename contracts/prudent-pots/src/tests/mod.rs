@@ -1,7 +1,10 @@
+#[allow(clippy::unnecessary_mut_passed)]
 mod calculate_total_losing_tokens;
 mod distribute_tokens;
 mod instantiate;
 #[cfg(test)]
 mod integration;
+#[allow(clippy::bool_assert_comparison, clippy::unnecessary_mut_passed)]
 mod is_winning_pot;
+mod nft_metadata;
 mod prepare_next_game;
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::from_json;
+
+    use crate::helpers::nft::{Metadata, Trait};
+
+    #[test]
+    fn metadata_unknown_fields_works() {
+        // Collections extending the on-chain metadata with their own keys still have their traits read
+        let metadata: Metadata = from_json(
+            br#"{
+                "name": "Pot #1",
+                "attributes": [
+                    {"trait_type": "Background", "value": "Gold", "rarity": 3}
+                ],
+                "edition": 1,
+                "properties": {"creator": "someone"}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.name, Some("Pot #1".to_string()));
+        assert_eq!(
+            metadata.attributes,
+            Some(vec![Trait {
+                display_type: None,
+                trait_type: "Background".to_string(),
+                value: "Gold".to_string(),
+            }])
+        );
+        assert_eq!(metadata.image, None);
+    }
+}